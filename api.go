@@ -1,19 +1,75 @@
 package libfetch
 
 import (
+	"context"
+	"crypto/x509"
+	"errors"
+	"fmt"
+	"log/slog"
+	"maps"
+	"net/http"
+	"net/url"
 	"os"
+	"path"
+	"path/filepath"
+	"slices"
 	"time"
 
 	"github.com/hashicorp/go-getter"
+	"golang.org/x/net/http/httpguts"
 )
 
 // Api 结构体用于配置和执行下载安装操作
 type Api struct {
-	installDir      string
-	progressTracker getter.ProgressTracker
-	retryCount      int
-	retryDelay      time.Duration
-	proxy           string
+	installDir       string
+	progressTracker  getter.ProgressTracker
+	retryCount       int
+	retryDelay       time.Duration
+	proxy            string
+	noProxy          string
+	expectedSHA256   string
+	token            string
+	resume           bool
+	outputName       string
+	extractProgress  ExtractProgressFunc
+	progressHandler  ProgressHandler
+	logger           *slog.Logger
+	progressInterval time.Duration
+	apiBaseURL       string
+	downloadBaseURL  string
+	mirrors          []string
+	dryRun           bool
+	maxConcurrency   int
+	stripComponents  int
+	ctx              context.Context
+	versionFile      string
+	header           http.Header
+	connectTimeout   time.Duration
+	timeout          time.Duration
+	maxRedirects     int
+	httpClient       *http.Client
+	etagCachePath    string
+	force            bool
+	keepVersions     int
+	spaceFactor      float64
+	preInstall       func(ctx *InstallContext) error
+	postInstall      func(outcome *InstallOutcome) error
+	parallelChunks   int
+	flatten          bool
+	userAgent        string
+	versionedLayout  bool
+	failOnCollision  bool
+	caseInsensitive  bool
+	deadline         time.Duration
+	extractors       map[string]Extractor
+	writeBufferSize  int
+	transport        http.RoundTripper
+	rootCertificates []*x509.Certificate
+	insecureTLS      bool
+	detectArchive    bool
+	keepArchive      bool
+	movingTags       []string
+	err              error
 }
 
 // RepoApi 结构体用于指定 GitHub 仓库
@@ -24,19 +80,46 @@ type RepoApi struct {
 
 // VersionApi 结构体用于指定版本
 type VersionApi struct {
-	api      *Api
-	repo     string
-	version  string
-	isLatest bool
+	api           *Api
+	repo          string
+	version       string
+	isLatest      bool
+	resolveLatest func(d *Downloader) (string, error)
+	// extractPattern 非空时只解压匹配的压缩包条目
+	extractPattern string
+	// assetDir 返回资产解压到的安装目录子目录
+	assetDir func(assetName string) string
+	// signatureAssetFunc 非空时解压前使用其生成的签名资产和 publicKey 校验资产
+	signatureAssetFunc func(version string) string
+	publicKey          string
 }
 
-// NewApi 创建新的 Api 实例，默认读取环境变量获取 HTTP 代理
+// URLApi 结构体用于从直接下载地址安装资产
+type URLApi struct {
+	api     *Api
+	url     string
+	version string
+}
+
+// FileApi 结构体用于从本地文件安装资产
+type FileApi struct {
+	api     *Api
+	path    string
+	version string
+}
+
+// NewApi 创建新的 Api 实例，默认读取环境变量获取 HTTP 代理、不使用代理的主机和 GitHub Token
 func NewApi() *Api {
 	// 读取环境变量获取 HTTP 代理
 	proxy := os.Getenv("HTTP_PROXY")
 	if proxy == "" {
 		proxy = os.Getenv("HTTPS_PROXY")
 	}
+	// 读取环境变量获取不使用代理的主机
+	noProxy := os.Getenv("NO_PROXY")
+	if noProxy == "" {
+		noProxy = os.Getenv("no_proxy")
+	}
 
 	return &Api{
 		installDir:      ".",
@@ -44,6 +127,14 @@ func NewApi() *Api {
 		retryCount:      3,
 		retryDelay:      3 * time.Second,
 		proxy:           proxy,
+		noProxy:         noProxy,
+		token:           os.Getenv("GITHUB_TOKEN"),
+		apiBaseURL:      DefaultAPIBaseURL,
+		downloadBaseURL: DefaultDownloadBaseURL,
+		stripComponents: DefaultStripComponents,
+		connectTimeout:  DefaultConnectTimeout,
+		timeout:         DefaultTimeout,
+		spaceFactor:     DefaultSpaceFactor,
 	}
 }
 
@@ -56,6 +147,49 @@ func (a *Api) SetInstallDir(dir string) *Api {
 // SetProgressTracker 设置进度跟踪器
 func (a *Api) SetProgressTracker(pt getter.ProgressTracker) *Api {
 	a.progressTracker = pt
+	if a.progressInterval > 0 {
+		setProgressInterval(pt, a.progressInterval)
+	}
+	return a
+}
+
+// SetProgressInterval 设置下载进度两次报告之间的最短时间间隔，下载完成时总会报告一次
+// 仅对 DefaultProgressTracker 和 NewProgressTracker 创建的进度跟踪器生效
+func (a *Api) SetProgressInterval(interval time.Duration) *Api {
+	a.progressInterval = interval
+	setProgressInterval(a.progressTracker, interval)
+	return a
+}
+
+// SetProgressHandler 设置进度事件回调，下载开始、进行中、完成以及解压开始、每个条目解压完成、解压完成时都会收到事件
+// 会替换 SetProgressTracker 设置的进度跟踪器
+func (a *Api) SetProgressHandler(handler ProgressHandler) *Api {
+	a.progressHandler = handler
+	return a.SetProgressTracker(NewProgressTracker(handler))
+}
+
+// SetLogger 设置接收调试日志的 slog.Logger，记录重试、重定向、选择的资产、解压方式和安装结果等决策，
+// 与进度事件相互独立；默认不输出任何日志
+func (a *Api) SetLogger(logger *slog.Logger) *Api {
+	a.logger = logger
+	return a
+}
+
+// ProgressChannel 设置一个把进度事件发送到返回的通道的进度回调，size 为通道缓冲区大小，用于在渲染循环中接收事件
+// 事件按发送顺序到达，通道满时下载会等待接收方，因此安装期间需要持续接收；通道不会被关闭
+// 会替换 SetProgressHandler 设置的回调和 SetProgressTracker 设置的进度跟踪器
+func (a *Api) ProgressChannel(size int) (*Api, <-chan ProgressEvent) {
+	events := make(chan ProgressEvent, max(size, 0))
+	return a.SetProgressHandler(func(event ProgressEvent) {
+		events <- event
+	}), events
+}
+
+// SetExtractProgress 设置解压进度回调，每解压一个条目调用一次，解压完成时再调用一次
+//
+// Deprecated: 请使用 SetProgressHandler，它会以解压事件的形式提供相同的信息
+func (a *Api) SetExtractProgress(fn ExtractProgressFunc) *Api {
+	a.extractProgress = fn
 	return a
 }
 
@@ -65,19 +199,395 @@ func (a *Api) SetRetryCount(count int) *Api {
 	return a
 }
 
-// SetRetryTimeDelay 设置重试延迟时间（秒）
+// SetRetryTimeDelay 设置重试延迟时间（秒），需要更精确的延迟时使用 SetRetryDelay
 func (a *Api) SetRetryTimeDelay(seconds int) *Api {
-	a.retryDelay = time.Duration(seconds) * time.Second
+	return a.SetRetryDelay(time.Duration(seconds) * time.Second)
+}
+
+// SetRetryDelay 设置重试延迟时间，可以小于一秒，如 250 * time.Millisecond
+func (a *Api) SetRetryDelay(delay time.Duration) *Api {
+	a.retryDelay = delay
 	return a
 }
 
-// SetProxy 设置 HTTP 代理
+// SetProxy 设置代理，支持 http://、https://、socks5:// 和 socks5h:// 协议，两种 SOCKS5 协议都由代理解析域名
+// 未指定协议时视为 HTTP 代理；代理地址无效或协议不受支持时所有请求都会返回 ErrInvalidProxy
 func (a *Api) SetProxy(proxy string) *Api {
 	a.proxy = proxy
 	return a
 }
 
-// Repo 设置 GitHub 仓库，返回 RepoApi
+// SetNoProxy 设置不使用代理直接连接的主机，多个主机用逗号分隔，格式与 NO_PROXY 环境变量相同
+// 默认读取 NO_PROXY 环境变量
+func (a *Api) SetNoProxy(hosts string) *Api {
+	a.noProxy = hosts
+	return a
+}
+
+// NoProxy 不使用任何代理，忽略环境变量和 SetProxy 的设置，所有请求都直接连接
+func (a *Api) NoProxy() *Api {
+	a.proxy = ""
+	return a
+}
+
+// SetConnectTimeout 设置建立连接（包括 TLS 握手）的超时时间，默认 30 秒，0 表示不限制
+func (a *Api) SetConnectTimeout(timeout time.Duration) *Api {
+	a.connectTimeout = timeout
+	return a
+}
+
+// SetTimeout 设置 API 请求的超时时间以及下载时允许多长时间收不到数据，默认 30 秒，0 表示不限制
+// 下载只要持续收到数据就不会因为总耗时过长而中断
+func (a *Api) SetTimeout(timeout time.Duration) *Api {
+	a.timeout = timeout
+	return a
+}
+
+// SetMaxRedirects 设置每个请求最多跟随的重定向次数，默认（0）跟随最多 10 次，负数表示不跟随重定向
+// 资产下载被重定向到的存储地址返回 403（签名过期）时，会重新请求资产地址并重试一次
+func (a *Api) SetMaxRedirects(n int) *Api {
+	a.maxRedirects = n
+	return a
+}
+
+// SetHTTPClient 设置所有请求使用的 HTTP 客户端，用于复用已有的连接池或自定义传输层
+// 设置后代理、连接超时、重定向次数和 API 请求超时由该客户端决定，SetTimeout 仍会中断长时间收不到数据的下载
+func (a *Api) SetHTTPClient(client *http.Client) *Api {
+	a.httpClient = client
+	return a
+}
+
+// SetTransport 设置执行所有请求的传输层，替换根据代理和连接超时设置创建的传输层，超时和重定向设置仍然生效
+// 测试中可以用它返回预设的发布信息和资产内容，不需要访问网络
+func (a *Api) SetTransport(transport http.RoundTripper) *Api {
+	a.transport = transport
+	return a
+}
+
+// AddRootCertificate 添加信任的根证书，用于解密 TLS 的企业代理等使用私有 CA 的场景，系统根证书仍然受信任
+// data 可以是一个或多个 PEM 编码的证书，也可以是一个 DER 编码的证书；无法解析时通过 Err 返回错误
+func (a *Api) AddRootCertificate(data []byte) *Api {
+	certs, err := ParseCertificates(data)
+	if err != nil {
+		a.setErr(fmt.Errorf("invalid root certificate: %w", err))
+		return a
+	}
+	a.rootCertificates = append(a.rootCertificates, certs...)
+	return a
+}
+
+// SetDangerAcceptInvalidCerts 设置是否跳过服务器证书校验。危险：开启后任何服务器或中间人都可以冒充 GitHub 提供任意资产，
+// 应优先使用 AddRootCertificate 信任代理的 CA
+func (a *Api) SetDangerAcceptInvalidCerts(accept bool) *Api {
+	a.insecureTLS = accept
+	return a
+}
+
+// SetETagCachePath 设置缓存最新版本 ETag 的文件，再次查询最新版本时发送条件请求，
+// 最新版本未变化时 GitHub 返回 304，直接使用缓存的版本且不消耗 API 速率限制；未设置时只在进程内缓存
+func (a *Api) SetETagCachePath(path string) *Api {
+	a.etagCachePath = path
+	return a
+}
+
+// SetGitHubBase 设置 GitHub API 和资产下载的基础地址，用于 GitHub Enterprise 等自建实例
+// 例如 SetGitHubBase("https://github.example.com/api/v3", "https://github.example.com")
+func (a *Api) SetGitHubBase(apiBaseURL, downloadBaseURL string) *Api {
+	a.apiBaseURL = apiBaseURL
+	a.downloadBaseURL = downloadBaseURL
+	return a
+}
+
+// AddMirror 添加一个资产下载的备用地址，从主地址下载遇到连接失败或 5xx 响应时按添加顺序使用相同路径从备用地址下载，
+// 例如 AddMirror("https://mirror.example.com/github")；备用地址不会收到 Token 和 SetHeader 设置的请求头，成功的备用地址通过 EventMirror 报告。
+// Release 元数据仍从 SetGitHubBase 设置的 API 地址获取
+func (a *Api) AddMirror(baseURL string) *Api {
+	u, err := url.Parse(baseURL)
+	if err != nil || (u.Scheme != "http" && u.Scheme != "https") || u.Host == "" {
+		a.setErr(fmt.Errorf("invalid mirror %q: must be an http or https URL", baseURL))
+		return a
+	}
+	a.mirrors = append(a.mirrors, baseURL)
+	return a
+}
+
+// SetToken 设置 GitHub Token，所有 API 请求和资产下载都会携带该 Token
+func (a *Api) SetToken(token string) *Api {
+	a.token = token
+	return a
+}
+
+// SetResume 设置是否断点续传，开启后未下载完成的文件会保留为 .part 文件，下次下载时继续
+func (a *Api) SetResume(resume bool) *Api {
+	a.resume = resume
+	return a
+}
+
+// SetOutputName 设置非压缩包资产下载后保存的文件名，默认使用下载地址的最后一段
+func (a *Api) SetOutputName(name string) *Api {
+	a.outputName = name
+	return a
+}
+
+// SetDryRun 设置是否只进行预检：解析版本和资产地址并确认资产存在，但不下载、不解压、不写入 version 文件
+func (a *Api) SetDryRun(dryRun bool) *Api {
+	a.dryRun = dryRun
+	return a
+}
+
+// SetForce 设置是否强制重新安装：已安装请求的版本时仍会重新下载并替换现有安装，用于修复损坏的安装
+// 与升级不同，强制重新安装不会切换到更新的版本
+func (a *Api) SetForce(force bool) *Api {
+	a.force = force
+	return a
+}
+
+// SetKeepVersions 设置替换安装时保留的旧版本数量，旧版本会移动到安装目录下的 .versions 目录，
+// 可以通过 RepoApi.Rollback 恢复；默认为 0，即删除旧版本
+func (a *Api) SetKeepVersions(n int) *Api {
+	a.keepVersions = n
+	return a
+}
+
+// SetSpaceFactor 设置估算磁盘空间时归档文件解压所需空间相对归档大小的倍数，下载前安装目录所在文件系统的可用空间不足时
+// 安装返回 InsufficientSpaceError；默认为 DefaultSpaceFactor，设置为 0 时不检查磁盘空间
+func (a *Api) SetSpaceFactor(factor float64) *Api {
+	a.spaceFactor = factor
+	return a
+}
+
+// SetPreInstallHook 设置安装前回调，在下载资产前调用，可以获得将要安装的版本、安装目录和资产名
+// 回调返回错误时安装终止，不会修改现有安装；没有需要安装的内容时不会调用
+func (a *Api) SetPreInstallHook(hook func(ctx *InstallContext) error) *Api {
+	a.preInstall = hook
+	return a
+}
+
+// SetPostInstallHook 设置安装后回调，在写入 version 文件后调用，用于设置可执行权限、首次初始化或写入配置等
+// 回调可以从 InstallOutcome 获得安装目录和安装的版本，返回的错误会作为安装的错误返回；没有安装任何内容时不会调用
+func (a *Api) SetPostInstallHook(hook func(outcome *InstallOutcome) error) *Api {
+	a.postInstall = hook
+	return a
+}
+
+// SetParallelChunks 设置大文件下载拆分成的并发分段请求数量，服务器支持 Range 请求并报告文件大小时生效，
+// 否则仍使用单个连接下载；小于 2 时不拆分
+func (a *Api) SetParallelChunks(n int) *Api {
+	a.parallelChunks = n
+	return a
+}
+
+// SetWriteBufferSize 设置断点续传下载写入文件时使用的缓冲区大小（字节），默认（0）为 DefaultWriteBufferSize，负数表示不使用缓冲
+// 服务器以很多小块返回数据时，缓冲可以减少写文件的系统调用次数
+func (a *Api) SetWriteBufferSize(size int) *Api {
+	a.writeBufferSize = size
+	return a
+}
+
+// SetMaxConcurrency 设置 InstallMany 同时下载的最大资产数量，默认一次下载一个
+// 并发下载时进度跟踪器和解压进度回调会被同时调用，进度跟踪器可以通过 src 参数区分不同的资产
+func (a *Api) SetMaxConcurrency(n int) *Api {
+	a.maxConcurrency = n
+	return a
+}
+
+// SetStripComponents 设置解压时从每个条目路径开头去掉的目录层数，对 tar 包和 zip 包都生效
+// 默认在 tar 包的所有条目都位于同一个顶层目录下时去掉该目录，否则原样解压，zip 包原样解压；设置为 0 时所有压缩包都原样解压
+func (a *Api) SetStripComponents(n int) *Api {
+	a.stripComponents = n
+	return a
+}
+
+// SetKeepArchive 设置解压后是否保留下载的压缩包，开启后压缩包以原文件名保存在解压出的文件旁边，便于检查或重新解压
+// 保留的压缩包和解压出的文件一样记录在 version 文件中，卸载和升级时会一并删除
+func (a *Api) SetKeepArchive(keep bool) *Api {
+	a.keepArchive = keep
+	return a
+}
+
+// SetDetectArchiveByMagic 设置是否根据文件开头的魔数识别没有压缩包扩展名的资产，默认关闭
+// 开启后内容为 zip 或 gzip、xz、bzip2 压缩的 tar 包的资产会被解压，其他文件（包括不是 tar 包的压缩文件）保持原样
+func (a *Api) SetDetectArchiveByMagic(detect bool) *Api {
+	a.detectArchive = detect
+	return a
+}
+
+// SetMovingTags 设置会在版本之间移动的标签（如 "nightly"），安装这些标签时解析为指向同一提交的发布版本，
+// 安装并在 version 文件中记录该版本的标签而不是别名，使更新检查可以发现标签已移动
+func (a *Api) SetMovingTags(tags ...string) *Api {
+	a.movingTags = tags
+	return a
+}
+
+// SetFlatten 设置解压时是否丢弃压缩包的目录结构，开启后所有文件以文件名直接解压到安装目录，目录条目会被跳过
+// 两个文件的文件名相同时解压失败，不会互相覆盖
+func (a *Api) SetFlatten(flatten bool) *Api {
+	a.flatten = flatten
+	return a
+}
+
+// SetCaseInsensitiveAssets 设置是否忽略大小写比较资产名：发布中没有指定名称的资产时改为下载仅大小写不同的资产，资产匹配模式也忽略大小写
+// 实际使用的资产名记录在 InstallOutcome.AssetNames 和 version 文件中
+func (a *Api) SetCaseInsensitiveAssets(caseInsensitive bool) *Api {
+	a.caseInsensitive = caseInsensitive
+	return a
+}
+
+// RegisterExtractor 注册扩展名为 ext（包含开头的点，不区分大小写，如 ".tar.zst"）的压缩包的解压器，
+// 优先于内置的解压器和 go-getter 使用，未注册的压缩包类型仍按原来的方式处理；解压器不受 SetStripComponents、SetExtractPattern 和 SetFlatten 影响
+// 内置的解压器可以通过 BuiltinExtractors 获得
+func (a *Api) RegisterExtractor(ext string, extractor Extractor) *Api {
+	if a.extractors == nil {
+		a.extractors = make(map[string]Extractor)
+	}
+	a.extractors[ext] = extractor
+	return a
+}
+
+// SetFailOnCollision 设置两个文件写入同一路径时是否报错（返回的错误匹配 ErrFileCollision），同一压缩包中的条目和同一次安装的多个资产都会检查
+// 默认后写入的文件覆盖先写入的文件，并通过 SetProgressHandler 设置的回调发送 EventCollision 事件
+func (a *Api) SetFailOnCollision(fail bool) *Api {
+	a.failOnCollision = fail
+	return a
+}
+
+// SetVersionedLayout 设置是否将每个版本安装到安装目录下以版本号命名的子目录中（如 <安装目录>/v1.2.3），version 文件也写入该子目录
+// 开启后多个版本可以并存，升级会把新版本安装到新的子目录而不删除旧版本，GetInstalledVersion、CheckUpdate 和 Uninstall 针对已安装的最新版本
+func (a *Api) SetVersionedLayout(versioned bool) *Api {
+	a.versionedLayout = versioned
+	return a
+}
+
+// SetContext 设置用于取消安装的 context，context 被取消后正在进行的下载会立即停止并返回 ErrCancelled
+// 未下载完成的文件会被删除，开启断点续传时保留 .part 文件以便下次继续
+func (a *Api) SetContext(ctx context.Context) *Api {
+	a.ctx = ctx
+	return a
+}
+
+// SetDeadline 设置整个安装操作（包括解析版本、所有重试和下载）允许的最长时间，超时后正在进行的下载立即停止并返回匹配 ErrDeadlineExceeded 的错误
+// 与 SetContext 取消时一样，未下载完成的文件会被删除；单个 API 请求的耗时由 SetTimeout 限制；设置为 0 表示不限制
+func (a *Api) SetDeadline(deadline time.Duration) *Api {
+	a.deadline = deadline
+	return a
+}
+
+// context 返回一次安装操作使用的 context，设置了 deadline 时到期后被取消，返回的 cancel 必须在操作结束后调用
+func (a *Api) context() (context.Context, context.CancelFunc) {
+	ctx := a.ctx
+	if ctx == nil {
+		ctx = context.Background()
+	}
+	if a.deadline > 0 {
+		return context.WithTimeoutCause(ctx, a.deadline, ErrDeadlineExceeded)
+	}
+	return context.WithCancel(ctx)
+}
+
+// SetVersionFile 设置安装目录中 version 文件的文件名，默认为 version.json
+// 多个工具安装到同一个目录时可以使用不同的文件名（如 llama.version.json），避免互相覆盖
+// 文件名不能为空且不能包含路径分隔符，否则安装、卸载等操作会返回错误
+func (a *Api) SetVersionFile(name string) *Api {
+	a.versionFile = name
+	return a
+}
+
+// SetExpectedSHA256 设置下载资产期望的 SHA-256 校验值（十六进制），校验失败时不会解压
+func (a *Api) SetExpectedSHA256(sum string) *Api {
+	a.expectedSHA256 = sum
+	return a
+}
+
+// SetUserAgent 设置 API 请求和资产下载携带的 User-Agent，未设置时使用 DefaultUserAgent
+// 值无效时 Err 以及之后的所有操作都会返回错误
+func (a *Api) SetUserAgent(userAgent string) *Api {
+	if !httpguts.ValidHeaderFieldValue(userAgent) {
+		a.setErr(fmt.Errorf("invalid user agent %q", userAgent))
+		return a
+	}
+	a.userAgent = userAgent
+	return a
+}
+
+// SetHeader 添加一个自定义请求头，API 请求和资产下载都会携带该请求头，可以多次调用添加多个请求头
+// 请求头名称或值无效时 Err 以及之后的所有操作都会返回错误
+func (a *Api) SetHeader(name, value string) *Api {
+	if !httpguts.ValidHeaderFieldName(name) {
+		a.setErr(fmt.Errorf("invalid header name %q", name))
+		return a
+	}
+	if !httpguts.ValidHeaderFieldValue(value) {
+		a.setErr(fmt.Errorf("invalid value for header %s", name))
+		return a
+	}
+
+	if a.header == nil {
+		a.header = http.Header{}
+	}
+	a.header.Add(name, value)
+	return a
+}
+
+// Err 返回配置 Api 时遇到的第一个错误，例如无效的请求头
+func (a *Api) Err() error {
+	return a.err
+}
+
+// setErr 记录配置 Api 时遇到的第一个错误
+func (a *Api) setErr(err error) {
+	if a.err == nil {
+		a.err = err
+	}
+}
+
+// newDownloader 根据 Api 的配置为指定仓库创建下载器，配置无效时返回错误
+func (a *Api) newDownloader(repo string) (*Downloader, error) {
+	if a.err != nil {
+		return nil, a.err
+	}
+	// 直接地址安装不涉及仓库
+	if repo != "" {
+		if err := ValidateRepo(repo); err != nil {
+			return nil, err
+		}
+	}
+
+	downloader := NewDownloaderWithConfig(repo, a.retryCount, a.retryDelay, a.proxy, a.progressTracker)
+	downloader.ApiURL = LatestReleaseURL(a.apiBaseURL, repo)
+	downloader.DownloadBaseURL = a.downloadBaseURL
+	downloader.Mirrors = a.mirrors
+	downloader.NoProxy = a.noProxy
+	downloader.ExpectedSHA256 = a.expectedSHA256
+	downloader.Token = a.token
+	downloader.Resume = a.resume
+	downloader.OutputName = a.outputName
+	downloader.ExtractProgress = a.extractProgress
+	downloader.ProgressHandler = a.progressHandler
+	downloader.Logger = a.logger
+	downloader.StripComponents = a.stripComponents
+	downloader.Flatten = a.flatten
+	downloader.FailOnCollision = a.failOnCollision
+	downloader.CaseInsensitiveAssets = a.caseInsensitive
+	downloader.Extractors = maps.Clone(a.extractors)
+	downloader.DetectArchiveByMagic = a.detectArchive
+	downloader.KeepArchive = a.keepArchive
+	downloader.MovingTags = slices.Clone(a.movingTags)
+	downloader.Header = a.header.Clone()
+	downloader.UserAgent = a.userAgent
+	downloader.ConnectTimeout = a.connectTimeout
+	downloader.Timeout = a.timeout
+	downloader.MaxRedirects = a.maxRedirects
+	downloader.HTTPClient = a.httpClient
+	downloader.Transport = a.transport
+	downloader.RootCertificates = slices.Clone(a.rootCertificates)
+	downloader.InsecureSkipVerify = a.insecureTLS
+	downloader.ETagCachePath = a.etagCachePath
+	downloader.ParallelChunks = a.parallelChunks
+	downloader.WriteBufferSize = a.writeBufferSize
+	return downloader, nil
+}
+
+// Repo 设置 GitHub 仓库，返回 RepoApi；仓库格式必须为 "owner/repo"，否则后续操作返回 ErrInvalidRepo
 func (a *Api) Repo(repo string) *RepoApi {
 	return &RepoApi{
 		api:  a,
@@ -85,22 +595,220 @@ func (a *Api) Repo(repo string) *RepoApi {
 	}
 }
 
+// FromURL 设置直接下载地址，返回 URLApi，安装时不会通过 GitHub API 解析版本
+// 代理、进度、重试、校验和解压等配置仍然生效
+func (a *Api) FromURL(url string) *URLApi {
+	return &URLApi{
+		api: a,
+		url: url,
+	}
+}
+
+// FromLocalFile 设置本地资产文件，返回 FileApi，用于离线环境中安装预先下载的发布资产，安装时不会访问网络
+// 压缩包按扩展名以与下载的资产相同的方式解压，其他文件直接复制到安装目录；SetExpectedSHA256 设置的校验和仍然生效
+func (a *Api) FromLocalFile(path string) *FileApi {
+	return &FileApi{
+		api:  a,
+		path: path,
+	}
+}
+
+// newInstall 根据 Api 的配置为指定仓库创建 Install 实例
+func (a *Api) newInstall(repo string) (*Install, error) {
+	downloader, err := a.newDownloader(repo)
+	if err != nil {
+		return nil, err
+	}
+
+	install := NewInstall(repo, a.installDir)
+	install.Downloader = downloader
+	install.Force = a.force
+	install.KeepVersions = a.keepVersions
+	install.SpaceFactor = a.spaceFactor
+	install.VersionedLayout = a.versionedLayout
+	install.PreInstall = a.preInstall
+	install.PostInstall = a.postInstall
+	if a.versionFile != "" {
+		if err := install.SetVersionFile(a.versionFile); err != nil {
+			return nil, err
+		}
+	}
+	return install, nil
+}
+
 // GetInstalledVersion 获取已安装的版本信息
 func (r *RepoApi) GetInstalledVersion() (*VersionInfo, error) {
-	install := NewInstall(r.repo, r.api.installDir)
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
 	return install.GetInstalledVersion()
 }
 
-// Latest 设置为下载最新版本，返回 VersionApi
+// UpgradePlan 返回升级到最新版本时会删除的已安装文件和会下载的资产，不会修改任何内容，可用于在交互式工具中升级前请求确认
+// 资产名来自 version 文件；没有安装时返回 ErrNotInstalled
+func (r *RepoApi) UpgradePlan() (*UpgradePlan, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return install.UpgradePlan()
+}
+
+// DiffLatest 比较已安装的文件与最新版本的资产会安装的文件，返回新增、删除和未变化的文件列表
+// 资产会下载到临时目录，压缩包只列出条目而不解压；没有安装时返回 ErrNotInstalled
+func (r *RepoApi) DiffLatest() (*ManifestDiff, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return install.DiffLatest()
+}
+
+// CheckUpdate 检查是否有新版本可用，只读取 version 文件并查询最新版本，不会下载任何内容
+func (r *RepoApi) CheckUpdate() (*UpdateStatus, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return install.CheckUpdate()
+}
+
+// Uninstall 删除已安装的文件及 version 文件，如果未安装或安装的是其他仓库则返回错误
+func (r *RepoApi) Uninstall() error {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return err
+	}
+	return install.Uninstall()
+}
+
+// Rollback 将安装恢复为最近保留的旧版本并重写 version 文件，返回恢复的版本信息，当前安装会被删除
+// 需要在升级前通过 SetKeepVersions 保留旧版本
+func (r *RepoApi) Rollback() (*VersionInfo, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return install.Rollback()
+}
+
+// Verify 重新计算已安装文件的 SHA-256 并返回缺失或内容与安装时记录不一致的文件，安装完好时返回空列表
+// 发现损坏后可以通过 SetForce 重新安装修复
+func (r *RepoApi) Verify() ([]string, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return install.Verify()
+}
+
+// CleanupTemp 删除中断的安装遗留的 .part 文件、临时文件和暂存目录，返回删除的数量
+// 已记录为安装文件的文件会被保留，不要在安装进行时调用
+func (r *RepoApi) CleanupTemp() (int, error) {
+	install, err := r.api.newInstall(r.repo)
+	if err != nil {
+		return 0, err
+	}
+	return install.CleanupTemp()
+}
+
+// DownloadLatestAssetGlob 下载最新版本中第一个文件名匹配 glob 模式（如 "*-linux-x64.tar.gz"）的资产到安装目录
+func (r *RepoApi) DownloadLatestAssetGlob(glob string) error {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return err
+	}
+	return downloader.DownloadLatestAssetGlob(glob, r.api.installDir)
+}
+
+// DownloadMatchingAll 下载最新版本中名称匹配任一 glob 模式的所有资产到安装目录（如二进制文件及其校验和、签名文件），返回下载的资产名
+// 任一模式没有匹配的资产时返回错误，不下载任何内容
+func (r *RepoApi) DownloadMatchingAll(globs ...string) ([]string, error) {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return downloader.DownloadMatchingAll(globs, "", r.api.installDir)
+}
+
+// ListVersions 列出仓库的所有发布版本，按发布时间从新到旧排列
+func (r *RepoApi) ListVersions() ([]ReleaseInfo, error) {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return downloader.ListReleases()
+}
+
+// LatestVersion 返回最新正式版本的 tag，不下载或安装任何内容
+func (r *RepoApi) LatestVersion() (string, error) {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return "", err
+	}
+	return downloader.LatestVersion()
+}
+
+// ReleaseNotes 获取最新正式版本的发布说明
+func (r *RepoApi) ReleaseNotes() (string, error) {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return "", err
+	}
+	return downloader.ReleaseNotes("")
+}
+
+// ResolveTag 获取标签指向的提交 SHA 和日期，附注标签会被解引用到提交，用于将已安装的版本对应到确切的提交
+func (r *RepoApi) ResolveTag(tag string) (*TagRef, error) {
+	downloader, err := r.api.newDownloader(r.repo)
+	if err != nil {
+		return nil, err
+	}
+	return downloader.ResolveTag(tag)
+}
+
+// Latest 设置为下载最新的正式版本，返回 VersionApi
 func (r *RepoApi) Latest() *VersionApi {
+	return r.latest((*Downloader).LatestVersion)
+}
+
+// LatestPrerelease 设置为下载最新的预发布版本，返回 VersionApi
+func (r *RepoApi) LatestPrerelease() *VersionApi {
+	return r.latest((*Downloader).LatestPrerelease)
+}
+
+// LatestIncludingPrereleases 设置为下载最新版本（包括预发布版本），返回 VersionApi
+func (r *RepoApi) LatestIncludingPrereleases() *VersionApi {
+	return r.latest((*Downloader).LatestVersionIncludingPrereleases)
+}
+
+// LatestOffset 设置为下载按发布时间从新到旧排列的第 n 个版本（0 为最新版本，1 为上一个版本），返回 VersionApi
+// includePrereleases 为 true 时预发布版本也参与排序；版本数量不足 n+1 个时安装返回错误
+func (r *RepoApi) LatestOffset(n int, includePrereleases bool) *VersionApi {
+	return r.latest(func(d *Downloader) (string, error) {
+		return d.LatestVersionOffset(n, includePrereleases)
+	})
+}
+
+// VersionReq 设置为下载满足版本约束（如 ">= 2.0, < 3.0"）的最高版本，返回 VersionApi；没有版本满足约束时安装返回错误
+func (r *RepoApi) VersionReq(constraint string) *VersionApi {
+	return r.latest(func(d *Downloader) (string, error) {
+		return d.LatestVersionMatching(constraint)
+	})
+}
+
+// latest 使用 resolveLatest 解析最新版本，返回 VersionApi
+func (r *RepoApi) latest(resolveLatest func(d *Downloader) (string, error)) *VersionApi {
 	return &VersionApi{
-		api:      r.api,
-		repo:     r.repo,
-		isLatest: true,
+		api:           r.api,
+		repo:          r.repo,
+		isLatest:      true,
+		resolveLatest: resolveLatest,
 	}
 }
 
-// Version 设置具体版本，返回 VersionApi
+// Version 设置具体版本，返回 VersionApi；已安装其他版本时安装会切换到该版本，包括降级到更旧的版本
 func (r *RepoApi) Version(version string) *VersionApi {
 	return &VersionApi{
 		api:      r.api,
@@ -110,29 +818,362 @@ func (r *RepoApi) Version(version string) *VersionApi {
 	}
 }
 
-// Install 安装指定的资产
+// Install 安装指定的资产，返回安装的版本、路径以及本次执行的操作
 // assetFunc 是一个回调函数，根据版本号生成资产文件名
-func (v *VersionApi) Install(assetFunc func(version string) string) error {
+func (v *VersionApi) Install(assetFunc func(version string) string) (*InstallOutcome, error) {
+	return v.InstallMany(func(version string) []string {
+		return []string{assetFunc(version)}
+	})
+}
+
+// InstallWith 与 Install 相同，但 assetFunc 可以使用配置好的下载器发起请求（如列出发布中的资产）来确定资产文件名
+// assetFunc 返回错误时安装终止并返回该错误
+func (v *VersionApi) InstallWith(assetFunc func(downloader *Downloader, version string) (string, error)) (*InstallOutcome, error) {
+	return v.install(func(downloader *Downloader, version string) ([]string, error) {
+		assetName, err := assetFunc(downloader, version)
+		if err != nil {
+			return nil, err
+		}
+		return []string{assetName}, nil
+	}, nil)
+}
+
+// InstallExtract 与 Install 相同，但只解压压缩包中名称匹配 filePattern 的条目，其余条目不会写入磁盘
+// filePattern 是 path.Match 格式的通配符，不含 / 时匹配条目的文件名（如 "tool*"），否则匹配去掉目录层数后的完整路径（如 "bin/*"）
+// 目录条目会被跳过，非压缩包资产不受影响；实际安装的文件记录在 InstallOutcome.Files 中
+func (v *VersionApi) InstallExtract(assetFunc func(version string) string, filePattern string) (*InstallOutcome, error) {
+	if _, err := path.Match(filePattern, ""); err != nil {
+		return nil, fmt.Errorf("invalid file pattern %q: %w", filePattern, err)
+	}
+	extract := *v
+	extract.extractPattern = filePattern
+	return extract.Install(assetFunc)
+}
+
+// InstallWithChecksums 与 Install 相同，但在解压前使用发布中的校验和资产（如 checksums.txt、SHA256SUMS）校验下载的资产
+// checksumAssetFunc 是一个回调函数，根据版本号生成校验和资产文件名
+func (v *VersionApi) InstallWithChecksums(assetFunc func(version string) string, checksumAssetFunc func(version string) string) (*InstallOutcome, error) {
+	return v.install(func(_ *Downloader, version string) ([]string, error) {
+		return []string{assetFunc(version)}, nil
+	}, checksumAssetFunc)
+}
+
+// InstallVerified 与 Install 相同，但在解压前使用发布中的 minisign 签名资产（如 tool.zip.minisig）和项目的公钥校验下载的资产，
+// 签名不匹配时返回 ErrSignatureMismatch，不会解压任何内容；不支持 GPG 签名
+// sigFunc 是一个回调函数，根据版本号生成签名资产文件名；publicKey 是 base64 编码的公钥或 .pub 文件的内容
+func (v *VersionApi) InstallVerified(assetFunc func(version string) string, sigFunc func(version string) string, publicKey string) (*InstallOutcome, error) {
+	verified := *v
+	verified.signatureAssetFunc = sigFunc
+	verified.publicKey = publicKey
+	return verified.Install(assetFunc)
+}
+
+// InstallMany 只解析一次版本，将多个资产下载并解压到同一个安装目录，version 文件会记录所有资产名
+// assetsFunc 是一个回调函数，根据版本号生成所有资产文件名
+func (v *VersionApi) InstallMany(assetsFunc func(version string) []string) (*InstallOutcome, error) {
+	return v.install(func(_ *Downloader, version string) ([]string, error) {
+		return assetsFunc(version), nil
+	}, nil)
+}
+
+// InstallManyInto 与 InstallMany 相同，但每个资产解压到安装目录下各自的子目录中，避免不同资产中的同名文件互相覆盖
+// assetsFunc 根据版本号返回资产文件名到子目录（相对于安装目录，如 "bin"、"models"）的映射，子目录为空字符串的资产解压到安装目录本身
+func (v *VersionApi) InstallManyInto(assetsFunc func(version string) map[string]string) (*InstallOutcome, error) {
+	var dirs map[string]string
+	into := *v
+	into.assetDir = func(assetName string) string {
+		return dirs[assetName]
+	}
+	return into.install(func(_ *Downloader, version string) ([]string, error) {
+		// 升级时会为新版本重新生成映射
+		dirs = assetsFunc(version)
+		return slices.Sorted(maps.Keys(dirs)), nil
+	}, nil)
+}
+
+// install 解析版本并安装 assetsFunc 生成的资产，checksumAssetFunc 不为 nil 时使用校验和资产校验每个资产
+func (v *VersionApi) install(assetsFunc func(downloader *Downloader, version string) ([]string, error), checksumAssetFunc func(version string) string) (*InstallOutcome, error) {
+	ctx, cancel := v.api.context()
+	defer cancel()
+
 	// 创建下载器，传递所有配置
-	downloader := NewDownloaderWithConfig(v.repo, v.api.retryCount, v.api.retryDelay, v.api.proxy, v.api.progressTracker)
+	downloader, err := v.api.newDownloader(v.repo)
+	if err != nil {
+		return nil, err
+	}
+	downloader.Context = ctx
 
 	var version string
-	var err error
 
 	if v.isLatest {
-		version, err = downloader.LatestVersion()
+		version, err = v.resolveLatest(downloader)
 		if err != nil {
-			return err
+			return nil, err
 		}
 	} else {
-		version = v.version
+		version, err = downloader.ResolveVersion(v.version)
+		if err != nil {
+			return nil, err
+		}
 	}
 
-	// 生成资产文件名
-	assetName := assetFunc(version)
 	//https://github.com/libffi/libffi/releases/download/v3.5.1/libffi-3.5.1-x86-32bit-msvc-binaries.zip
+	// 生成资产文件名
+	assetNames, err := assetsFunc(downloader, version)
+	if err != nil {
+		return nil, err
+	}
 	// 创建 Install 实例并安装资产
-	install := NewInstall(v.repo, v.api.installDir)
+	install, err := v.api.newInstall(v.repo)
+	if err != nil {
+		return nil, err
+	}
+	if v.extractPattern != "" {
+		downloader.ExtractPattern = v.extractPattern
+	}
 	install.Downloader = downloader
-	return install.InstallAsset(assetName, version, v.isLatest)
+	install.ResolveAssets = func(version string) ([]string, error) {
+		return assetsFunc(downloader, version)
+	}
+	install.ChecksumAssetFunc = checksumAssetFunc
+	install.AssetDir = v.assetDir
+	if v.signatureAssetFunc != nil {
+		install.SignatureAssetFunc = func(version, _ string) string {
+			return v.signatureAssetFunc(version)
+		}
+		install.PublicKey = v.publicKey
+	}
+	install.MaxConcurrency = v.api.maxConcurrency
+	install.Context = ctx
+	if v.isLatest {
+		install.ResolveLatest = func() (string, error) {
+			return v.resolveLatest(downloader)
+		}
+	}
+
+	if v.api.dryRun {
+		return v.dryRun(install, assetNames, version)
+	}
+	return install.InstallAssets(assetNames, version, v.isLatest)
+}
+
+// ReleaseNotes 获取将要安装的版本的发布说明
+func (v *VersionApi) ReleaseNotes() (string, error) {
+	downloader, err := v.api.newDownloader(v.repo)
+	if err != nil {
+		return "", err
+	}
+
+	version := v.version
+	if v.isLatest {
+		version, err = v.resolveLatest(downloader)
+		if err != nil {
+			return "", err
+		}
+	}
+
+	return downloader.ReleaseNotes(version)
+}
+
+// Size 返回资产的大小（字节），不下载资产；服务器未报告大小时返回 -1
+// assetFunc 是一个回调函数，根据版本号生成资产文件名
+func (v *VersionApi) Size(assetFunc func(version string) string) (int64, error) {
+	ctx, cancel := v.api.context()
+	defer cancel()
+
+	downloader, err := v.api.newDownloader(v.repo)
+	if err != nil {
+		return 0, err
+	}
+	downloader.Context = ctx
+
+	version := v.version
+	if v.isLatest {
+		version, err = v.resolveLatest(downloader)
+		if err != nil {
+			return 0, err
+		}
+	}
+
+	return downloader.AssetSize(assetFunc(version), version)
+}
+
+// dryRun 确认资产存在并报告其总大小以及安装将执行的操作，不下载也不写入任何文件
+func (v *VersionApi) dryRun(install *Install, assetNames []string, version string) (*InstallOutcome, error) {
+	if len(assetNames) == 0 {
+		return nil, errors.New("no assets to install")
+	}
+	if install.VersionedLayout {
+		versioned, err := install.versionedInstall(version)
+		if err != nil {
+			return nil, err
+		}
+		install = versioned
+	}
+
+	var assetURL string
+	var total int64
+	for i, assetName := range assetNames {
+		url := install.Downloader.GetReleaseAssetURLByVersion(assetName, version)
+		size, err := install.Downloader.headAsset(url)
+		if err != nil {
+			return nil, err
+		}
+		if i == 0 {
+			assetURL = url
+		}
+		// 任意一个资产大小未知时总大小也未知
+		if size < 0 || total < 0 {
+			total = -1
+		} else {
+			total += size
+		}
+	}
+
+	action := InstallActionFresh
+	if installed, err := install.GetInstalledVersion(); err == nil {
+		switch {
+		case install.Force:
+			action = InstallActionReinstall
+		case v.isLatest && installed.UpgradeAvailable(version):
+			action = InstallActionUpgrade
+		case !v.isLatest && installed.TagName != version:
+			action = switchAction(installed, version)
+		default:
+			action = InstallActionNone
+		}
+	}
+
+	return &InstallOutcome{
+		Version:     version,
+		InstallPath: install.InstallPath,
+		AssetName:   assetNames[0],
+		AssetNames:  assetNames,
+		Action:      action,
+		DryRun:      true,
+		URL:         assetURL,
+		Size:        total,
+	}, nil
+}
+
+// Version 设置写入 version 文件的版本号，默认使用下载地址中的文件名
+func (u *URLApi) Version(version string) *URLApi {
+	u.version = version
+	return u
+}
+
+// Install 下载并安装资产，已安装相同版本时不会重新下载，已安装其他版本时会替换
+func (u *URLApi) Install() (*InstallOutcome, error) {
+	install, err := u.api.newInstall("")
+	if err != nil {
+		return nil, err
+	}
+
+	ctx, cancel := u.api.context()
+	defer cancel()
+	install.Context = ctx
+	install.Downloader.Context = ctx
+
+	if u.api.dryRun {
+		return u.dryRun(install)
+	}
+	return install.InstallURL(u.url, u.version)
+}
+
+// dryRun 确认资产存在并报告其大小以及安装将执行的操作，不下载也不写入任何文件
+func (u *URLApi) dryRun(install *Install) (*InstallOutcome, error) {
+	assetName, err := urlAssetName(u.url)
+	if err != nil {
+		return nil, err
+	}
+	version := u.version
+	if version == "" {
+		version = assetName
+	}
+
+	if install.VersionedLayout {
+		install, err = install.versionedInstall(version)
+		if err != nil {
+			return nil, err
+		}
+	}
+
+	size, err := install.Downloader.headAsset(u.url)
+	if err != nil {
+		return nil, err
+	}
+
+	return sourceDryRun(install, assetName, version, u.url, size), nil
+}
+
+// sourceDryRun 报告将 version 版本的资产 assetName 从 url 安装到 install 时将执行的操作
+func sourceDryRun(install *Install, assetName, version, url string, size int64) *InstallOutcome {
+	action := InstallActionFresh
+	if installed, err := install.GetInstalledVersion(); err == nil {
+		switch {
+		case installed.TagName != version:
+			action = InstallActionUpgrade
+		case install.Force:
+			action = InstallActionReinstall
+		default:
+			action = InstallActionNone
+		}
+	}
+
+	return &InstallOutcome{
+		Version:     version,
+		InstallPath: install.InstallPath,
+		AssetName:   assetName,
+		AssetNames:  []string{assetName},
+		Action:      action,
+		DryRun:      true,
+		URL:         url,
+		Size:        size,
+	}
+}
+
+// Version 设置写入 version 文件的版本号，默认使用本地文件的文件名
+func (f *FileApi) Version(version string) *FileApi {
+	f.version = version
+	return f
+}
+
+// Install 安装本地资产，已安装相同版本时不会重新安装，已安装其他版本时会替换
+func (f *FileApi) Install() (*InstallOutcome, error) {
+	install, err := f.api.newInstall("")
+	if err != nil {
+		return nil, err
+	}
+
+	if f.api.dryRun {
+		return f.dryRun(install)
+	}
+
+	ctx, cancel := f.api.context()
+	defer cancel()
+	install.Context = ctx
+	install.Downloader.Context = ctx
+	return install.InstallFile(f.path, f.version)
+}
+
+// dryRun 确认本地文件存在并报告其大小以及安装将执行的操作，不写入任何文件
+func (f *FileApi) dryRun(install *Install) (*InstallOutcome, error) {
+	info, err := os.Stat(f.path)
+	if err != nil {
+		return nil, fmt.Errorf("error reading local asset: %w", err)
+	}
+	assetName := filepath.Base(f.path)
+	version := f.version
+	if version == "" {
+		version = assetName
+	}
+
+	if install.VersionedLayout {
+		install, err = install.versionedInstall(version)
+		if err != nil {
+			return nil, err
+		}
+	}
+
+	return sourceDryRun(install, assetName, version, f.path, info.Size()), nil
 }
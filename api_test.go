@@ -1,9 +1,22 @@
 package libfetch_test
 
 import (
+	"bytes"
+	"context"
+	"encoding/pem"
+	"errors"
 	"fmt"
+	"log/slog"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"slices"
 	"strings"
+	"sync"
+	"sync/atomic"
 	"testing"
+	"time"
 
 	"github.com/Cyberhan123/libfetch"
 )
@@ -12,7 +25,7 @@ func TestDownLoadLLAMA(t *testing.T) {
 	api := libfetch.NewApi()
 	api.SetInstallDir("./llamalib")
 	// 下载最新版本
-	err := api.Repo("ggml-org/llama.cpp").Latest().Install(func(version string) string {
+	_, err := api.Repo("ggml-org/llama.cpp").Latest().Install(func(version string) string {
 		return fmt.Sprintf("llama-%s-bin-win-cpu-x64.zip", version)
 	})
 	if err != nil {
@@ -34,7 +47,7 @@ func TestDownLoadSD(t *testing.T) {
 	api.SetInstallDir("./sd")
 
 	// 通过 version文件判断版本是否一致， 如果版本不一致，则下载当前版本替换到当前目录，如果一致则不进行下载，下载完成后会进行解压安装，并在目录中留下version文件
-	err := api.Repo("leejet/stable-diffusion.cpp").Latest().Install(func(version string) string {
+	_, err := api.Repo("leejet/stable-diffusion.cpp").Latest().Install(func(version string) string {
 		cleanVersion := strings.TrimPrefix(version, "master-487-")
 		return fmt.Sprintf("sd-master-%s-bin-win-avx2-x64.zip", cleanVersion)
 	})
@@ -49,7 +62,7 @@ func TestDownLoadLibFFI(t *testing.T) {
 	libffiapi := libfetch.NewApi()
 	libffiapi.SetInstallDir("./libffi")
 	// 如果版本不一致，则下载当前版本替换到当前目录，如果一致则不进行下载
-	err := libffiapi.Repo("libffi/libffi").Version("v3.5.1").Install(func(version string) string {
+	_, err := libffiapi.Repo("libffi/libffi").Version("v3.5.1").Install(func(version string) string {
 		cleanVersion := strings.TrimPrefix(version, "v")
 		return fmt.Sprintf("libffi-%s-x86-32bit-msvc-binaries.zip", cleanVersion)
 	})
@@ -58,3 +71,634 @@ func TestDownLoadLibFFI(t *testing.T) {
 	}
 	t.Log("libffi installed successfully")
 }
+
+func TestSetHeaderRejectsInvalidHeaders(t *testing.T) {
+	if err := libfetch.NewApi().SetHeader("X-Internal-Auth", "secret").Err(); err != nil {
+		t.Errorf("valid header rejected: %v", err)
+	}
+
+	api := libfetch.NewApi().SetHeader("Bad Header", "value")
+	if api.Err() == nil {
+		t.Fatal("invalid header name accepted")
+	}
+	if _, err := api.Repo("owner/repo").ListVersions(); err != api.Err() {
+		t.Errorf("ListVersions() = %v, want the configuration error", err)
+	}
+
+	if libfetch.NewApi().SetHeader("X-Test", "line\nbreak").Err() == nil {
+		t.Error("invalid header value accepted")
+	}
+}
+
+func TestRootCertificates(t *testing.T) {
+	server := httptest.NewTLSServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	defer server.Close()
+
+	latestVersion := func(api *libfetch.Api) (string, error) {
+		return api.SetGitHubBase(server.URL, server.URL).SetRetryCount(1).Repo("owner/repo").LatestVersion()
+	}
+	if _, err := latestVersion(libfetch.NewApi()); err == nil {
+		t.Fatal("expected the certificate of the test server to be rejected")
+	}
+
+	cert := server.Certificate()
+	pemCert := pem.EncodeToMemory(&pem.Block{Type: "CERTIFICATE", Bytes: cert.Raw})
+	for name, data := range map[string][]byte{"PEM": pemCert, "DER": cert.Raw} {
+		if version, err := latestVersion(libfetch.NewApi().AddRootCertificate(data)); err != nil || version != "v1.0.0" {
+			t.Errorf("%s root certificate: LatestVersion() = %q, %v", name, version, err)
+		}
+	}
+	if version, err := latestVersion(libfetch.NewApi().SetDangerAcceptInvalidCerts(true)); err != nil || version != "v1.0.0" {
+		t.Errorf("accepting invalid certificates: LatestVersion() = %q, %v", version, err)
+	}
+
+	for _, data := range [][]byte{[]byte("not a certificate"), []byte("-----BEGIN CERTIFICATE-----\nbm90IGRlcg==\n-----END CERTIFICATE-----\n")} {
+		if libfetch.NewApi().AddRootCertificate(data).Err() == nil {
+			t.Errorf("malformed certificate %q accepted", data)
+		}
+	}
+}
+
+func TestAddMirror(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	release := newReleaseServer(t, &latest)
+	unavailable := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		http.Error(w, "unavailable", http.StatusServiceUnavailable)
+	}))
+	defer unavailable.Close()
+	incomplete := httptest.NewServer(http.NotFoundHandler())
+	defer incomplete.Close()
+	mirror := httptest.NewServer(http.StripPrefix("/github", http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Authorization") != "" || r.Header.Get("X-Api-Key") != "" {
+			t.Errorf("credentials were sent to a mirror: %v", r.Header)
+		}
+		release.Config.Handler.ServeHTTP(w, r)
+	})))
+	defer mirror.Close()
+
+	var mirrored []string
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(release.URL, unavailable.URL).
+		SetToken("secret").
+		SetHeader("X-Api-Key", "secret").
+		AddMirror(incomplete.URL).
+		AddMirror(mirror.URL + "/github").
+		SetProgressTracker(nil).
+		SetRetryCount(1).
+		SetProgressHandler(func(event libfetch.ProgressEvent) {
+			if event.Kind == libfetch.EventMirror {
+				mirrored = append(mirrored, event.URL)
+			}
+		})
+
+	if _, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool-" + version + ".zip"
+	}); err != nil {
+		t.Fatal(err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("installed %q, %v", d, err)
+	}
+	want := mirror.URL + "/github/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip"
+	if !slices.Equal(mirrored, []string{want}) {
+		t.Errorf("reported mirrors %v, want %s", mirrored, want)
+	}
+
+	if libfetch.NewApi().AddMirror("mirror.example.com").Err() == nil {
+		t.Error("mirror without a scheme accepted")
+	}
+}
+
+func TestSetLogger(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	var buf bytes.Buffer
+	api := libfetch.NewApi().
+		SetInstallDir(t.TempDir()).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetLogger(slog.New(slog.NewTextHandler(&buf, &slog.HandlerOptions{Level: slog.LevelDebug})))
+
+	if _, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool-" + version + ".zip"
+	}); err != nil {
+		t.Fatal(err)
+	}
+	for _, want := range []string{
+		`msg="resolved latest release" repo=owner/repo tag=v1.0.0`,
+		`msg="downloading asset" repo=owner/repo asset=tool-v1.0.0.zip version=v1.0.0`,
+		`msg="extracting archive" file=tool-v1.0.0.zip type=.zip`,
+		`msg=installed repo=owner/repo action=fresh version=v1.0.0`,
+	} {
+		if !strings.Contains(buf.String(), want) {
+			t.Errorf("log does not contain %q:\n%s", want, buf.String())
+		}
+	}
+}
+
+func TestSize(t *testing.T) {
+	storage := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Method != http.MethodHead {
+			t.Errorf("storage received a %s request", r.Method)
+		}
+		w.Header().Set("Content-Length", "1234")
+	}))
+	defer storage.Close()
+	github := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/owner/repo/releases/download/v1.0.0/tool.zip" {
+			http.NotFound(w, r)
+			return
+		}
+		http.Redirect(w, r, storage.URL+"/signed/tool.zip", http.StatusFound)
+	}))
+	defer github.Close()
+
+	api := libfetch.NewApi().SetGitHubBase(github.URL, github.URL).SetRetryCount(1)
+	size, err := api.Repo("owner/repo").Version("v1.0.0").Size(func(version string) string {
+		return "tool.zip"
+	})
+	if err != nil || size != 1234 {
+		t.Errorf("Size() = %d, %v, want the size reported after the redirect", size, err)
+	}
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	_, err = api.SetContext(ctx).Repo("owner/repo").Version("v1.0.0").Size(func(version string) string {
+		return "tool.zip"
+	})
+	if !errors.Is(err, libfetch.ErrCancelled) {
+		t.Errorf("expected ErrCancelled with a cancelled context, got %v", err)
+	}
+}
+
+func TestTokenIsSentWithAllRequests(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	release := newReleaseServer(t, &latest)
+
+	for _, tt := range []struct {
+		name  string
+		env   string
+		token string
+	}{
+		{"GITHUB_TOKEN", "env-token", ""},
+		{"SetToken", "env-token", "api-token"},
+	} {
+		t.Setenv("GITHUB_TOKEN", tt.env)
+		want := "Bearer " + tt.env
+		if tt.token != "" {
+			want = "Bearer " + tt.token
+		}
+
+		var mu sync.Mutex
+		authorization := map[string]string{}
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			mu.Lock()
+			authorization[r.Method+" "+r.URL.Path] = r.Header.Get("Authorization")
+			mu.Unlock()
+			release.Config.Handler.ServeHTTP(w, r)
+		}))
+
+		api := libfetch.NewApi().
+			SetInstallDir(t.TempDir()).
+			SetGitHubBase(server.URL, server.URL).
+			SetProgressTracker(nil)
+		if tt.token != "" {
+			api.SetToken(tt.token)
+		}
+		_, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+			return "tool-" + version + ".zip"
+		})
+		server.Close()
+		if err != nil {
+			t.Fatalf("%s: %v", tt.name, err)
+		}
+
+		for _, request := range []string{
+			"GET /repos/owner/repo/releases/latest",
+			"GET /owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip",
+		} {
+			if got, ok := authorization[request]; !ok || got != want {
+				t.Errorf("%s: %s sent Authorization %q, want %q", tt.name, request, got, want)
+			}
+		}
+	}
+}
+
+func TestRepoApiLatestPrerelease(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/repos/owner/repo/releases" {
+			http.NotFound(w, r)
+			return
+		}
+		fmt.Fprint(w, `[
+			{"tag_name": "v1.0.0", "published_at": "2024-01-01T00:00:00Z"},
+			{"tag_name": "v1.1.0-rc1", "prerelease": true, "published_at": "2024-02-01T00:00:00Z"},
+			{"tag_name": "v1.2.0-rc1", "prerelease": true, "draft": true, "published_at": "2024-04-01T00:00:00Z"},
+			{"tag_name": "v1.1.0", "published_at": "2024-03-01T00:00:00Z"}
+		]`)
+	}))
+	defer server.Close()
+
+	api := libfetch.NewApi().
+		SetInstallDir(t.TempDir()).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetRetryTimeDelay(0)
+
+	errStop := errors.New("stop after resolving the version")
+	tests := []struct {
+		name   string
+		choose func(repo *libfetch.RepoApi) *libfetch.VersionApi
+		want   string
+	}{
+		{"LatestPrerelease", (*libfetch.RepoApi).LatestPrerelease, "v1.1.0-rc1"},
+		{"LatestIncludingPrereleases", (*libfetch.RepoApi).LatestIncludingPrereleases, "v1.1.0"},
+	}
+	for _, tt := range tests {
+		var got string
+		_, err := tt.choose(api.Repo("owner/repo")).InstallWith(func(downloader *libfetch.Downloader, version string) (string, error) {
+			got = version
+			return "", errStop
+		})
+		if !errors.Is(err, errStop) || got != tt.want {
+			t.Errorf("%s resolved %q, %v, want %q", tt.name, got, err, tt.want)
+		}
+	}
+}
+
+func TestDryRun(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	release := newReleaseServer(t, &latest)
+
+	assetPath := "/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip"
+	resp, err := http.Get(release.URL + assetPath)
+	if err != nil {
+		t.Fatal(err)
+	}
+	resp.Body.Close()
+	wantSize := resp.ContentLength
+
+	var mu sync.Mutex
+	var requests []string
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		mu.Lock()
+		requests = append(requests, r.Method+" "+r.URL.Path)
+		mu.Unlock()
+		release.Config.Handler.ServeHTTP(w, r)
+	}))
+	defer server.Close()
+
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetRetryTimeDelay(0).
+		SetDryRun(true)
+
+	outcome, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool-" + version + ".zip"
+	})
+	if err != nil {
+		t.Fatalf("dry run of the latest release failed: %v", err)
+	}
+	if !outcome.DryRun || outcome.Action != libfetch.InstallActionFresh || outcome.Version != "v1.0.0" ||
+		outcome.URL != server.URL+assetPath || outcome.Size != wantSize {
+		t.Errorf("dry run outcome = %+v, want a fresh install of %s with %d bytes", outcome, server.URL+assetPath, wantSize)
+	}
+
+	outcome, err = api.FromURL(server.URL + assetPath).Install()
+	if err != nil {
+		t.Fatalf("dry run of the asset URL failed: %v", err)
+	}
+	if !outcome.DryRun || outcome.Action != libfetch.InstallActionFresh || outcome.URL != server.URL+assetPath || outcome.Size != wantSize {
+		t.Errorf("URL dry run outcome = %+v, want a fresh install of %s with %d bytes", outcome, server.URL+assetPath, wantSize)
+	}
+
+	want := []string{
+		"GET /repos/owner/repo/releases/latest",
+		"HEAD " + assetPath,
+		"HEAD " + assetPath,
+	}
+	if !slices.Equal(requests, want) {
+		t.Errorf("dry runs sent %q, want %q", requests, want)
+	}
+
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(entries) != 0 {
+		t.Errorf("dry runs wrote %d entries into the install dir", len(entries))
+	}
+}
+
+// recordingTransport records the method and path of every request before passing it on.
+type recordingTransport struct {
+	mu       sync.Mutex
+	requests []string
+}
+
+func (rt *recordingTransport) RoundTrip(req *http.Request) (*http.Response, error) {
+	rt.mu.Lock()
+	rt.requests = append(rt.requests, req.Method+" "+req.URL.Path)
+	rt.mu.Unlock()
+	return http.DefaultTransport.RoundTrip(req)
+}
+
+func TestSetHTTPClient(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	transport := &recordingTransport{}
+	api := libfetch.NewApi().
+		SetInstallDir(t.TempDir()).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetRetryTimeDelay(0).
+		SetHTTPClient(&http.Client{Transport: transport})
+
+	if _, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool-" + version + ".zip"
+	}); err != nil {
+		t.Fatal(err)
+	}
+
+	for _, want := range []string{
+		"GET /repos/owner/repo/releases/latest",
+		"GET /owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip",
+	} {
+		if !slices.Contains(transport.requests, want) {
+			t.Errorf("injected client did not send %s, sent %q", want, transport.requests)
+		}
+	}
+}
+
+func TestInstallWith(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetRetryTimeDelay(0)
+
+	errNoAsset := errors.New("no asset for this platform")
+	_, err := api.Repo("owner/repo").Latest().InstallWith(func(downloader *libfetch.Downloader, version string) (string, error) {
+		return "", errNoAsset
+	})
+	if !errors.Is(err, errNoAsset) {
+		t.Fatalf("expected the asset function error, got %v", err)
+	}
+
+	outcome, err := api.Repo("owner/repo").Latest().InstallWith(func(downloader *libfetch.Downloader, version string) (string, error) {
+		if downloader.Repo != "owner/repo" {
+			return "", fmt.Errorf("unexpected downloader for %s", downloader.Repo)
+		}
+		return "tool-" + version + ".zip", nil
+	})
+	if err != nil || outcome.Action != libfetch.InstallActionFresh || outcome.AssetName != "tool-v1.0.0.zip" {
+		t.Fatalf("InstallWith() = %+v, %v", outcome, err)
+	}
+}
+
+func TestValidateRepo(t *testing.T) {
+	tests := []struct {
+		repo  string
+		valid bool
+	}{
+		{"ggml-org/llama.cpp", true},
+		{"owner/repo", true},
+		{"", false},
+		{"ggml-org-llama.cpp", false},
+		{"/repo", false},
+		{"owner/", false},
+		{"owner/repo/extra", false},
+		{"owner//repo", false},
+		{" owner/repo", false},
+		{"owner/my repo", false},
+		{"owner/repo\n", false},
+	}
+
+	for _, tt := range tests {
+		err := libfetch.ValidateRepo(tt.repo)
+		if tt.valid && err != nil {
+			t.Errorf("ValidateRepo(%q) = %v, want nil", tt.repo, err)
+		}
+		if !tt.valid && !errors.Is(err, libfetch.ErrInvalidRepo) {
+			t.Errorf("ValidateRepo(%q) = %v, want ErrInvalidRepo", tt.repo, err)
+		}
+	}
+
+	if _, err := libfetch.NewApi().Repo("ggml-org-llama.cpp").ListVersions(); !errors.Is(err, libfetch.ErrInvalidRepo) {
+		t.Errorf("ListVersions() of an invalid repo = %v, want ErrInvalidRepo", err)
+	}
+}
+
+func TestRepoLatestVersion(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.2.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(server.URL, server.URL).
+		SetRetryTimeDelay(0)
+
+	version, err := api.Repo("owner/repo").LatestVersion()
+	if err != nil || version != "v1.2.0" {
+		t.Fatalf("LatestVersion() = %q, %v", version, err)
+	}
+	if entries, _ := os.ReadDir(dir); len(entries) != 0 {
+		t.Errorf("LatestVersion() wrote to the install directory")
+	}
+}
+
+func TestProgressEventsReportDestination(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+	defer server.Close()
+
+	var mu sync.Mutex
+	dests := make(map[libfetch.ProgressEventKind]string)
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressHandler(func(event libfetch.ProgressEvent) {
+			mu.Lock()
+			defer mu.Unlock()
+			dests[event.Kind] = event.Dest
+		})
+
+	if _, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool-" + version + ".zip"
+	}); err != nil {
+		t.Fatal(err)
+	}
+	for _, kind := range []libfetch.ProgressEventKind{libfetch.EventStarted, libfetch.EventCompleted, libfetch.EventExtractCompleted} {
+		if dest, ok := dests[kind]; !ok || dest != dir {
+			t.Errorf("event %v reported destination %q, want %q", kind, dest, dir)
+		}
+	}
+}
+
+func TestSetProgressInterval(t *testing.T) {
+	const pieces, pieceSize = 10, 512
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Length", fmt.Sprint(pieces*pieceSize))
+		for range pieces {
+			w.Write(bytes.Repeat([]byte("x"), pieceSize))
+			w.(http.Flusher).Flush()
+			time.Sleep(30 * time.Millisecond)
+		}
+	}))
+	defer server.Close()
+
+	const interval = 100 * time.Millisecond
+	var mu sync.Mutex
+	var progress []time.Time
+	var last libfetch.ProgressEvent
+	api := libfetch.NewApi().
+		SetInstallDir(t.TempDir()).
+		SetProgressHandler(func(event libfetch.ProgressEvent) {
+			mu.Lock()
+			defer mu.Unlock()
+			if event.Kind == libfetch.EventProgress {
+				progress = append(progress, time.Now())
+			}
+			last = event
+		}).
+		SetProgressInterval(interval)
+
+	if _, err := api.FromURL(server.URL + "/tool.bin").Install(); err != nil {
+		t.Fatal(err)
+	}
+
+	if len(progress) == 0 || len(progress) >= pieces {
+		t.Errorf("received %d progress events for %d reads, want them throttled to one per %v", len(progress), pieces, interval)
+	}
+	for i := 1; i < len(progress); i++ {
+		// Allow for the time between the throttle check and the handler call
+		if gap := progress[i].Sub(progress[i-1]); gap < interval-10*time.Millisecond {
+			t.Errorf("progress events %d and %d were %v apart, want at least %v", i-1, i, gap, interval)
+		}
+	}
+	if last.Kind != libfetch.EventCompleted || last.Downloaded != pieces*pieceSize || last.Total != pieces*pieceSize {
+		t.Errorf("final event = %+v, want a completion of %d bytes", last, pieces*pieceSize)
+	}
+}
+
+func TestSetRetryDelay(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		http.Error(w, "unavailable", http.StatusServiceUnavailable)
+	}))
+	defer server.Close()
+
+	var delays []time.Duration
+	api := libfetch.NewApi().
+		SetGitHubBase(server.URL, server.URL).
+		SetRetryCount(2).
+		SetRetryDelay(5 * time.Millisecond).
+		SetProgressHandler(func(event libfetch.ProgressEvent) {
+			if event.Kind == libfetch.EventRetrying {
+				delays = append(delays, event.Delay)
+			}
+		})
+
+	if _, err := api.Repo("owner/repo").LatestVersion(); err == nil {
+		t.Fatal("expected an error from a failing server")
+	}
+	if !slices.Equal(delays, []time.Duration{5 * time.Millisecond}) {
+		t.Errorf("retried after %v, want a single 5ms delay", delays)
+	}
+}
+
+func TestDeadline(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if strings.HasSuffix(r.URL.Path, "/latest") {
+			fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+			return
+		}
+		// Send part of the asset, then stall until the client gives up
+		w.Header().Set("Content-Length", "10")
+		w.Write([]byte("tool"))
+		w.(http.Flusher).Flush()
+		<-r.Context().Done()
+	}))
+	defer server.Close()
+
+	dir := t.TempDir()
+	api := libfetch.NewApi().
+		SetInstallDir(dir).
+		SetGitHubBase(server.URL, server.URL).
+		SetProgressTracker(nil).
+		SetRetryTimeDelay(0).
+		SetDeadline(200 * time.Millisecond)
+
+	start := time.Now()
+	_, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+		return "tool.bin"
+	})
+	if !errors.Is(err, libfetch.ErrDeadlineExceeded) {
+		t.Fatalf("expected ErrDeadlineExceeded, got %v", err)
+	}
+	if elapsed := time.Since(start); elapsed > 5*time.Second {
+		t.Errorf("install took %v despite the deadline", elapsed)
+	}
+	if entries, _ := os.ReadDir(dir); len(entries) != 0 {
+		t.Errorf("partial download was left behind: %v", entries)
+	}
+}
+
+func TestProgressChannel(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	api, events := libfetch.NewApi().
+		SetInstallDir(t.TempDir()).
+		SetGitHubBase(server.URL, server.URL).
+		ProgressChannel(0)
+
+	done := make(chan error, 1)
+	go func() {
+		_, err := api.Repo("owner/repo").Latest().Install(func(version string) string {
+			return "tool-" + version + ".zip"
+		})
+		done <- err
+	}()
+
+	// The channel is unbuffered, so every event is received before the install returns
+	var kinds []libfetch.ProgressEventKind
+loop:
+	for {
+		select {
+		case event := <-events:
+			kinds = append(kinds, event.Kind)
+		case err := <-done:
+			if err != nil {
+				t.Fatal(err)
+			}
+			break loop
+		}
+	}
+	if !slices.Contains(kinds, libfetch.EventCompleted) || !slices.Contains(kinds, libfetch.EventExtractCompleted) {
+		t.Errorf("received events %v, want download and extraction completion", kinds)
+	}
+}
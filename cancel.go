@@ -0,0 +1,116 @@
+package libfetch
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"sync/atomic"
+	"time"
+
+	"github.com/hashicorp/go-getter"
+)
+
+// cancelledError returns the error of an operation stopped because ctx was cancelled. It matches
+// ErrCancelled and the cause of the cancellation, e.g. context.Canceled or ErrDeadlineExceeded.
+func cancelledError(ctx context.Context) error {
+	return fmt.Errorf("%w: %w", ErrCancelled, context.Cause(ctx))
+}
+
+// cancelTracker is a getter.ProgressTracker that stops reading a download as soon as ctx is
+// cancelled, checking before every chunk, and resets the idle timeout of ctx, if any, whenever
+// data arrives. Progress is forwarded to tracker, if any.
+type cancelTracker struct {
+	ctx     context.Context
+	idle    *idleTimeout
+	tracker getter.ProgressTracker
+	// digest, if set, hashes the downloaded data.
+	digest *digest
+}
+
+func newCancelTracker(ctx context.Context, tracker getter.ProgressTracker) *cancelTracker {
+	idle, _ := ctx.Value(idleTimeoutKey{}).(*idleTimeout)
+	// Report the destination of the download to trackers that know about it
+	if pr, ok := tracker.(*progressReader); ok {
+		if dest := destinationFrom(ctx); dest != "" {
+			tracker = pr.withDest(dest)
+		}
+	}
+	return &cancelTracker{ctx: ctx, idle: idle, tracker: tracker}
+}
+
+func (t *cancelTracker) TrackProgress(src string, currentSize, totalSize int64, stream io.ReadCloser) io.ReadCloser {
+	if t.digest != nil {
+		stream = t.digest.track(currentSize, stream)
+	}
+	var body io.ReadCloser = &contextReader{ctx: t.ctx, idle: t.idle, ReadCloser: stream}
+	if t.tracker != nil {
+		if tracked := t.tracker.TrackProgress(src, currentSize, totalSize, body); tracked != nil {
+			body = tracked
+		}
+	}
+	return body
+}
+
+// contextReader fails reads once ctx is cancelled.
+type contextReader struct {
+	ctx  context.Context
+	idle *idleTimeout
+	io.ReadCloser
+}
+
+func (r *contextReader) Read(p []byte) (int, error) {
+	if err := r.ctx.Err(); err != nil {
+		return 0, err
+	}
+	n, err := r.ReadCloser.Read(p)
+	if n > 0 {
+		r.idle.reset()
+	}
+	return n, err
+}
+
+type idleTimeoutKey struct{}
+
+// idleTimeout cancels a download that goes without receiving data for longer than timeout.
+type idleTimeout struct {
+	timeout time.Duration
+	timer   *time.Timer
+	cancel  context.CancelFunc
+	fired   atomic.Bool
+}
+
+// withIdleTimeout returns a context that is cancelled when the download reading from it receives
+// no data for timeout. A timeout of zero or less disables the idle timeout.
+func withIdleTimeout(ctx context.Context, timeout time.Duration) (context.Context, *idleTimeout) {
+	idle := &idleTimeout{timeout: timeout}
+	if timeout <= 0 {
+		return ctx, idle
+	}
+
+	ctx, idle.cancel = context.WithCancel(ctx)
+	idle.timer = time.AfterFunc(timeout, func() {
+		idle.fired.Store(true)
+		idle.cancel()
+	})
+	return context.WithValue(ctx, idleTimeoutKey{}, idle), idle
+}
+
+// reset restarts the timeout after data was received.
+func (t *idleTimeout) reset() {
+	if t != nil && t.timer != nil {
+		t.timer.Reset(t.timeout)
+	}
+}
+
+// stop releases the timer once the download is over.
+func (t *idleTimeout) stop() {
+	if t.timer != nil {
+		t.timer.Stop()
+		t.cancel()
+	}
+}
+
+// expired reports whether the download was cancelled by the timeout.
+func (t *idleTimeout) expired() bool {
+	return t.fired.Load()
+}
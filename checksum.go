@@ -0,0 +1,108 @@
+package libfetch
+
+import (
+	"bufio"
+	"context"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"net/http"
+	"strings"
+)
+
+// maxChecksumFileSize is the largest checksum file read from a release.
+const maxChecksumFileSize = 1 << 20
+
+// LookupChecksum downloads the checksum asset checksumAssetName (e.g. "checksums.txt" or
+// "SHA256SUMS") of the given release version (empty string for latest) and returns the hex
+// encoded SHA-256 listed for assetName.
+func (f *Downloader) LookupChecksum(checksumAssetName, assetName, version string) (string, error) {
+	return f.lookupChecksum(f.context(), checksumAssetName, assetName, version)
+}
+
+// VerifyAgainstChecksumAsset downloads assetName into dest like DownloadAssetVerified, taking the
+// expected SHA-256 from the checksum asset checksumAssetName of the same release. Archives are
+// verified before extraction begins.
+func (f *Downloader) VerifyAgainstChecksumAsset(assetName, checksumAssetName, version, dest string) error {
+	ctx := f.context()
+	checksum, err := f.lookupChecksum(ctx, checksumAssetName, assetName, version)
+	if err != nil {
+		return err
+	}
+
+	_, err = f.downloadAsset(ctx, assetName, version, dest, checksum)
+	return err
+}
+
+func (f *Downloader) lookupChecksum(ctx context.Context, checksumAssetName, assetName, version string) (string, error) {
+	checksumURL, err := f.assetURL(checksumAssetName, version)
+	if err != nil {
+		return "", err
+	}
+
+	var sums map[string]string
+	err = f.withMirrors(ctx, checksumURL, func(f *Downloader, src string) error {
+		var err error
+		sums, err = f.getChecksums(ctx, src)
+		return err
+	})
+	if err != nil {
+		return "", fmt.Errorf("error downloading checksum asset %s: %w", checksumAssetName, err)
+	}
+
+	checksum, ok := sums[assetName]
+	if !ok {
+		return "", fmt.Errorf("checksum asset %s has no checksum for %s", checksumAssetName, assetName)
+	}
+	return checksum, nil
+}
+
+// getChecksums downloads the checksum file at checksumURL and parses it.
+func (f *Downloader) getChecksums(ctx context.Context, checksumURL string) (map[string]string, error) {
+	req, err := http.NewRequestWithContext(ctx, "GET", checksumURL, nil)
+	if err != nil {
+		return nil, err
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+
+	resp, err := f.createHTTPClient().Do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return nil, &DownloadStatusError{StatusCode: resp.StatusCode, URL: checksumURL}
+	}
+
+	return parseChecksums(io.LimitReader(resp.Body, maxChecksumFileSize))
+}
+
+// parseChecksums parses checksum files in the format written by sha256sum: one
+// "<hex sha256>  <filename>" line per file, where the file name may be prefixed with "*" for
+// binary mode or with "./". Lines that are not SHA-256 checksums are ignored.
+func parseChecksums(r io.Reader) (map[string]string, error) {
+	sums := make(map[string]string)
+
+	scanner := bufio.NewScanner(r)
+	for scanner.Scan() {
+		sum, name, ok := strings.Cut(strings.TrimSpace(scanner.Text()), " ")
+		if !ok || len(sum) != 64 {
+			continue
+		}
+		if _, err := hex.DecodeString(sum); err != nil {
+			continue
+		}
+
+		name = strings.TrimPrefix(strings.TrimSpace(name), "*")
+		name = strings.TrimPrefix(name, "./")
+		sums[name] = strings.ToLower(sum)
+	}
+	if err := scanner.Err(); err != nil {
+		return nil, fmt.Errorf("error reading checksum file: %w", err)
+	}
+
+	return sums, nil
+}
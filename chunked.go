@@ -0,0 +1,268 @@
+package libfetch
+
+import (
+	"context"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"github.com/hashicorp/go-getter"
+	"golang.org/x/sync/errgroup"
+)
+
+// minChunkSize is the smallest range a parallel download is split into. Assets smaller than two
+// chunks are downloaded in a single stream.
+const minChunkSize = 1 << 20
+
+// getChunked downloads src into dest like getAsset, using ParallelChunks concurrent range
+// requests. It reports false without downloading anything when the server does not accept
+// ranges or report the size of src, or src is too small to split; the caller then downloads it in
+// a single stream.
+func (f *Downloader) getChunked(ctx context.Context, src, dest, checksum, ext string) ([]string, bool, error) {
+	size, ok := f.probeRanges(ctx, src)
+	if !ok {
+		return nil, false, nil
+	}
+
+	u, err := url.Parse(src)
+	if err != nil {
+		return nil, true, fmt.Errorf("invalid download url: %w", err)
+	}
+
+	if ext != "" {
+		spoolDir, err := os.MkdirTemp("", "libfetch-*")
+		if err != nil {
+			return nil, true, fmt.Errorf("failed to create temporary directory: %w", err)
+		}
+		defer os.RemoveAll(spoolDir)
+
+		downloadFile := filepath.Join(spoolDir, path.Base(u.Path))
+		if err := f.downloadChunks(ctx, src, downloadFile, size); err != nil {
+			return nil, true, err
+		}
+		if err := verifyChunked(ctx, downloadFile, checksum); err != nil {
+			return nil, true, err
+		}
+
+		files, err := f.extractArchive(downloadFile, dest, ext)
+		if err != nil {
+			return files, true, err
+		}
+		files, err = f.keepArchive(downloadFile, dest, files)
+		return files, true, err
+	}
+
+	if err := os.MkdirAll(dest, 0755); err != nil {
+		return nil, true, fmt.Errorf("failed to create destination directory: %w", err)
+	}
+
+	name := path.Base(u.Path)
+	if f.OutputName != "" {
+		name = f.OutputName
+	}
+	target := filepath.Join(dest, name)
+	partFile := target + ".part"
+
+	if err := f.downloadChunks(ctx, src, partFile, size); err != nil {
+		os.Remove(partFile)
+		return nil, true, err
+	}
+	if err := verifyChunked(ctx, partFile, checksum); err != nil {
+		os.Remove(partFile)
+		return nil, true, err
+	}
+
+	if err := os.Rename(partFile, target); err != nil {
+		return nil, true, fmt.Errorf("failed to rename partial download: %w", err)
+	}
+	return []string{name}, true, nil
+}
+
+// verifyChunked checks the file name downloaded in chunks against checksum, if set, and records
+// its SHA-256 in the digest of ctx, if any. The ranges arrive out of order, so the file is hashed
+// once it is complete.
+func verifyChunked(ctx context.Context, name, checksum string) error {
+	d := digestFrom(ctx)
+	if d == nil {
+		if checksum == "" {
+			return nil
+		}
+		return verifySHA256(name, checksum)
+	}
+
+	actual, err := d.setFile(name)
+	if err != nil {
+		return err
+	}
+	if checksum != "" && !strings.EqualFold(actual, checksum) {
+		return checksumMismatch(checksum, actual)
+	}
+	return nil
+}
+
+// probeRanges issues a HEAD request for src and returns its size if the server accepts range
+// requests for it and it is large enough to be split.
+func (f *Downloader) probeRanges(ctx context.Context, src string) (int64, bool) {
+	req, err := http.NewRequestWithContext(ctx, "HEAD", src, nil)
+	if err != nil {
+		return 0, false
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+
+	resp, err := f.createDownloadClient().Do(req)
+	if err != nil {
+		return 0, false
+	}
+	resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK || resp.Header.Get("Accept-Ranges") != "bytes" || resp.ContentLength < 2*minChunkSize {
+		return 0, false
+	}
+	return resp.ContentLength, true
+}
+
+// downloadChunks downloads the size bytes of src into the file target, splitting them into up to
+// ParallelChunks ranges that are downloaded concurrently and written at their offsets.
+func (f *Downloader) downloadChunks(ctx context.Context, src, target string, size int64) error {
+	file, err := os.Create(target)
+	if err != nil {
+		return fmt.Errorf("failed to create file: %w", err)
+	}
+	defer file.Close()
+
+	if err := file.Truncate(size); err != nil {
+		return fmt.Errorf("failed to allocate file: %w", err)
+	}
+
+	chunks := min(int64(f.ParallelChunks), size/minChunkSize)
+	chunkSize := (size + chunks - 1) / chunks
+
+	progress := newChunkProgress(ctx, f.ProgressTracker, src, size)
+	g, ctx := errgroup.WithContext(ctx)
+	for start := int64(0); start < size; start += chunkSize {
+		end := min(start+chunkSize, size)
+		g.Go(func() error {
+			return f.downloadRange(ctx, src, file, start, end, progress)
+		})
+	}
+	err = g.Wait()
+	progress.finish()
+	if err != nil {
+		return err
+	}
+
+	if err := file.Close(); err != nil {
+		return fmt.Errorf("failed to write file: %w", err)
+	}
+	return nil
+}
+
+// downloadRange downloads the bytes from start up to end of src and writes them at the same
+// offsets of file.
+func (f *Downloader) downloadRange(ctx context.Context, src string, file *os.File, start, end int64, progress *chunkProgress) error {
+	req, err := http.NewRequestWithContext(ctx, "GET", src, nil)
+	if err != nil {
+		return err
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+	req.Header.Set("Range", fmt.Sprintf("bytes=%d-%d", start, end-1))
+
+	resp, err := f.createDownloadClient().Do(req)
+	if err != nil {
+		return err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusPartialContent {
+		return &DownloadStatusError{StatusCode: resp.StatusCode, URL: src}
+	}
+
+	n, err := io.Copy(io.NewOffsetWriter(file, start), &chunkReader{reader: resp.Body, progress: progress})
+	if err != nil {
+		return fmt.Errorf("failed to write file: %w", err)
+	}
+	if n != end-start {
+		return fmt.Errorf("received %d bytes for range %d-%d of %s", n, start, end-1, src)
+	}
+	return nil
+}
+
+// chunkProgress combines the progress of the chunks of a download into a single stream read
+// through the progress tracker, which also checks for cancellation and resets the idle timeout.
+type chunkProgress struct {
+	received chan int
+	pending  int
+	done     chan struct{}
+}
+
+func newChunkProgress(ctx context.Context, tracker getter.ProgressTracker, src string, size int64) *chunkProgress {
+	p := &chunkProgress{
+		received: make(chan int, 64),
+		done:     make(chan struct{}),
+	}
+
+	stream := newCancelTracker(ctx, tracker).TrackProgress(src, 0, size, p)
+	go func() {
+		defer close(p.done)
+		io.Copy(io.Discard, stream)
+		stream.Close()
+		// Keep accepting progress after a cancellation so the chunks never block
+		for range p.received {
+		}
+	}()
+	return p
+}
+
+// add reports that n more bytes were received.
+func (p *chunkProgress) add(n int) {
+	p.received <- n
+}
+
+// finish ends the stream once all chunks are done.
+func (p *chunkProgress) finish() {
+	close(p.received)
+	<-p.done
+}
+
+// Read returns as many bytes as the chunks received since the last read. Their content is
+// meaningless, only the count matters.
+func (p *chunkProgress) Read(b []byte) (int, error) {
+	for p.pending == 0 {
+		n, ok := <-p.received
+		if !ok {
+			return 0, io.EOF
+		}
+		p.pending += n
+	}
+
+	n := min(p.pending, len(b))
+	p.pending -= n
+	return n, nil
+}
+
+func (p *chunkProgress) Close() error {
+	return nil
+}
+
+// chunkReader reports the data read from the body of a chunk to the progress of the download.
+type chunkReader struct {
+	reader   io.Reader
+	progress *chunkProgress
+}
+
+func (r *chunkReader) Read(b []byte) (int, error) {
+	n, err := r.reader.Read(b)
+	if n > 0 {
+		r.progress.add(n)
+	}
+	return n, err
+}
@@ -0,0 +1,100 @@
+package libfetch
+
+import (
+	"encoding/json"
+	"fmt"
+	"io/fs"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// CleanupTemp removes what interrupted installations leave behind and returns how many files and
+// directories were removed: partial downloads ("*.part") and temporary version files below the
+// install path, and staging directories next to it. Files recorded as installed are kept even
+// if their names look temporary. CleanupTemp must not run while installing into the install
+// path, as it would remove the files of the running installation.
+func (i *Install) CleanupTemp() (int, error) {
+	abs, err := filepath.Abs(i.InstallPath)
+	if err != nil {
+		return 0, err
+	}
+
+	// Staging directories of the install path are created next to it
+	var stale []string
+	parent := filepath.Dir(abs)
+	entries, err := os.ReadDir(parent)
+	if err != nil && !os.IsNotExist(err) {
+		return 0, fmt.Errorf("error reading install directory: %w", err)
+	}
+	prefix := strings.TrimSuffix(fmt.Sprintf(stagingDirPattern, filepath.Base(abs)), "*")
+	for _, entry := range entries {
+		if entry.IsDir() && strings.HasPrefix(entry.Name(), prefix) {
+			stale = append(stale, filepath.Join(parent, entry.Name()))
+		}
+	}
+
+	// Versions installed below the install path have their staging directories in it
+	installed := make(map[string]bool)
+	err = filepath.WalkDir(abs, func(name string, entry fs.DirEntry, err error) error {
+		if err != nil {
+			if name == abs && os.IsNotExist(err) {
+				return filepath.SkipAll
+			}
+			return err
+		}
+
+		switch {
+		case name != abs && entry.IsDir() && isStagingDir(entry.Name()):
+			stale = append(stale, name)
+			return filepath.SkipDir
+		case entry.IsDir():
+			i.recordInstalledFiles(name, installed)
+		case entry.Type().IsRegular() && isTempFile(entry.Name()) && !installed[name]:
+			stale = append(stale, name)
+		}
+		return nil
+	})
+	if err != nil {
+		return 0, fmt.Errorf("error reading install directory: %w", err)
+	}
+
+	for n, name := range stale {
+		if err := os.RemoveAll(name); err != nil {
+			return n, fmt.Errorf("error removing %s: %w", name, err)
+		}
+	}
+	return len(stale), nil
+}
+
+// recordInstalledFiles adds the files recorded by the version file in dir, if there is one, to
+// installed.
+func (i *Install) recordInstalledFiles(dir string, installed map[string]bool) {
+	d, err := os.ReadFile(filepath.Join(dir, i.versionFile))
+	if err != nil {
+		return
+	}
+	var versionInfo VersionInfo
+	if err := json.Unmarshal(d, &versionInfo); err != nil {
+		return
+	}
+	for _, file := range versionInfo.Files {
+		installed[filepath.Join(dir, filepath.FromSlash(file))] = true
+	}
+}
+
+// isStagingDir reports whether name is that of a staging directory created from stagingDirPattern.
+func isStagingDir(name string) bool {
+	base, ok := strings.CutPrefix(name, ".")
+	return ok && strings.Contains(base, ".staging-")
+}
+
+// isTempFile reports whether name is that of a partial download or of a temporary file created
+// by writeFileAtomic.
+func isTempFile(name string) bool {
+	if strings.HasSuffix(name, ".part") {
+		return true
+	}
+	base, ok := strings.CutPrefix(name, ".")
+	return ok && strings.Contains(base, ".tmp-")
+}
@@ -0,0 +1,123 @@
+package libfetch
+
+import (
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"hash"
+	"io"
+	"os"
+	"sync"
+)
+
+type digestKey struct{}
+
+// digest computes the SHA-256 of a download while its body is streamed, so no second pass over
+// the downloaded file is needed.
+type digest struct {
+	mu   sync.Mutex
+	hash hash.Hash
+	// offset is the number of bytes hashed from a resumed part file before the stream starts.
+	offset int64
+	// complete reports that the hash covers the whole download. It does not when a stream starts
+	// in the middle of the file without the bytes before it having been hashed.
+	complete bool
+	// fileSum, when set, is the checksum of a download that was hashed after the fact.
+	fileSum string
+}
+
+func newDigest() *digest {
+	return &digest{hash: sha256.New()}
+}
+
+// withDigest returns a context whose downloads record the SHA-256 of their data in the returned digest.
+func withDigest(ctx context.Context) (context.Context, *digest) {
+	d := newDigest()
+	return context.WithValue(ctx, digestKey{}, d), d
+}
+
+// digestFrom returns the digest recorded by downloads made with ctx, or nil if there is none.
+func digestFrom(ctx context.Context) *digest {
+	d, _ := ctx.Value(digestKey{}).(*digest)
+	return d
+}
+
+// resume hashes the part file name, which the next stream continues.
+func (d *digest) resume(name string) error {
+	file, err := os.Open(name)
+	if err != nil {
+		return fmt.Errorf("failed to open partial download: %w", err)
+	}
+	defer file.Close()
+
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	d.hash.Reset()
+	d.fileSum = ""
+	n, err := io.Copy(d.hash, file)
+	if err != nil {
+		return fmt.Errorf("failed to hash partial download: %w", err)
+	}
+	d.offset = n
+	return nil
+}
+
+// track returns body, the stream of a download starting at byte currentSize, hashing the data
+// as it is read. Every stream starts the hash over, unless it continues a resumed part file.
+func (d *digest) track(currentSize int64, body io.ReadCloser) io.ReadCloser {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	if d.offset == 0 || currentSize != d.offset {
+		d.hash.Reset()
+	}
+	d.complete = currentSize == 0 || currentSize == d.offset
+	d.offset = 0
+	d.fileSum = ""
+	return &digestReader{ReadCloser: body, d: d}
+}
+
+// setFile records the hex encoded SHA-256 of the downloaded file name, for downloads whose data
+// is not streamed in order.
+func (d *digest) setFile(name string) (string, error) {
+	sum, err := fileSHA256(name)
+	if err != nil {
+		return "", err
+	}
+
+	d.mu.Lock()
+	defer d.mu.Unlock()
+	d.fileSum = sum
+	return sum, nil
+}
+
+// checksum returns the hex encoded SHA-256 of the download, or an empty string if it is not known.
+func (d *digest) checksum() string {
+	d.mu.Lock()
+	defer d.mu.Unlock()
+
+	if d.fileSum != "" {
+		return d.fileSum
+	}
+	if !d.complete {
+		return ""
+	}
+	return hex.EncodeToString(d.hash.Sum(nil))
+}
+
+// digestReader feeds the data read from a download into its digest.
+type digestReader struct {
+	io.ReadCloser
+	d *digest
+}
+
+func (r *digestReader) Read(p []byte) (int, error) {
+	n, err := r.ReadCloser.Read(p)
+	if n > 0 {
+		r.d.mu.Lock()
+		r.d.hash.Write(p[:n])
+		r.d.mu.Unlock()
+	}
+	return n, err
+}
@@ -0,0 +1,61 @@
+package libfetch
+
+import (
+	"os"
+	"path/filepath"
+)
+
+// DefaultSpaceFactor is the default multiple of an archive's size its download and extraction are
+// assumed to need on disk.
+const DefaultSpaceFactor = 3
+
+// checkDiskSpace returns an *InsufficientSpaceError if the filesystem of dest has less free space
+// than the assets at assetURLs are estimated to need: their size, or SpaceFactor times their size
+// for archives. The sizes are requested with HEAD requests. Assets whose size is not reported,
+// and filesystems whose free space cannot be determined, are not checked.
+func (i *Install) checkDiskSpace(dest string, assetURLs []string) error {
+	if i.SpaceFactor <= 0 {
+		return nil
+	}
+
+	var needed int64
+	for _, assetURL := range assetURLs {
+		size, err := i.Downloader.headAsset(assetURL)
+		if err != nil || size < 0 {
+			// The download reports the failure
+			continue
+		}
+		if i.Downloader.isArchive(assetURL) {
+			size = int64(float64(size) * i.SpaceFactor)
+		}
+		needed += size
+	}
+	if needed == 0 {
+		return nil
+	}
+
+	available, err := availableSpace(dest)
+	if err != nil {
+		return nil
+	}
+	if needed > available {
+		return &InsufficientSpaceError{Path: dest, Needed: needed, Available: available}
+	}
+	return nil
+}
+
+// availableSpace returns the free space in bytes of the filesystem path is on, or would be created
+// on if it does not exist yet.
+func availableSpace(path string) (int64, error) {
+	for {
+		if _, err := os.Stat(path); err == nil {
+			break
+		}
+		parent := filepath.Dir(path)
+		if parent == path {
+			break
+		}
+		path = parent
+	}
+	return diskFree(path)
+}
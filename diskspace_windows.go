@@ -0,0 +1,18 @@
+//go:build windows
+
+package libfetch
+
+import "golang.org/x/sys/windows"
+
+// diskFree returns the space in bytes available to the current user on the volume of path.
+func diskFree(path string) (int64, error) {
+	name, err := windows.UTF16PtrFromString(path)
+	if err != nil {
+		return 0, err
+	}
+	var free uint64
+	if err := windows.GetDiskFreeSpaceEx(name, &free, nil, nil); err != nil {
+		return 0, err
+	}
+	return int64(free), nil
+}
@@ -1,24 +1,50 @@
 package libfetch
 
 import (
-	"archive/tar"
-	"compress/gzip"
 	"context"
-	"encoding/json"
+	"crypto/x509"
 	"errors"
 	"fmt"
-	"io"
+	"io/fs"
+	"log/slog"
+	"net"
 	"net/http"
 	"net/url"
 	"os"
+	"path"
 	"path/filepath"
 	"regexp"
+	"slices"
+	"strconv"
 	"strings"
+	"sync"
 	"time"
+	"unicode"
 
 	"github.com/hashicorp/go-getter"
 )
 
+const (
+	// DefaultAPIBaseURL is the base URL of the public GitHub API.
+	DefaultAPIBaseURL = "https://api.github.com"
+	// DefaultDownloadBaseURL is the base URL release assets are downloaded from on public GitHub.
+	DefaultDownloadBaseURL = "https://github.com"
+	// DefaultStripComponents strips the top-level directory of tarballs whose entries all lie below
+	// one, and extracts other tarballs and zip archives verbatim.
+	DefaultStripComponents = -1
+	// DefaultConnectTimeout is the default time allowed for establishing a connection.
+	DefaultConnectTimeout = 30 * time.Second
+	// DefaultTimeout is the default time allowed for an API request, and for a download to go without
+	// receiving data.
+	DefaultTimeout = 30 * time.Second
+	// DefaultMaxRedirects is the default number of redirects a request follows.
+	DefaultMaxRedirects = 10
+	// DefaultUserAgent is the User-Agent sent with requests when none is configured.
+	DefaultUserAgent = "libfetch"
+	// DefaultWriteBufferSize is the default size of the buffer resumable downloads are written through.
+	DefaultWriteBufferSize = 64 << 10
+)
+
 type Downloader struct {
 	// RetryCount is how many times the package will retry to obtain the latest version.
 	RetryCount int
@@ -26,20 +52,151 @@ type Downloader struct {
 	RetryDelay time.Duration
 	// ApiURL is the GitHub API URL for fetching the latest release.
 	ApiURL string
+	// DownloadBaseURL is the base URL release assets are downloaded from, e.g. "https://github.com".
+	DownloadBaseURL string
+	// Mirrors lists alternate base URLs assets are downloaded from when a download from
+	// DownloadBaseURL fails with a network error or a 5xx response, tried in order with the same
+	// path, e.g. "https://mirror.example.com/github" for "https://github.com". Token and Header are
+	// not sent to mirrors, so set ExpectedSHA256 or verify assets against a checksum or signature asset to
+	// detect a mirror serving other data. Release metadata is always requested from ApiURL.
+	Mirrors []string
 	// Repo is the GitHub repository in format "owner/repo".
 	Repo string
-	// Proxy is the HTTP proxy to use for downloads.
+	// Proxy is the proxy to use for all requests. The schemes http, https, socks5 and socks5h are
+	// supported; a proxy without a scheme is an HTTP proxy. Requests fail with ErrInvalidProxy
+	// when the proxy is not a valid URL with a supported scheme.
 	Proxy string
+	// NoProxy lists the hosts connected to directly even when a proxy is set, separated by commas
+	// in the format of the NO_PROXY environment variable, e.g. "mirror.internal,.corp.example.com".
+	NoProxy string
+	// Logger receives debug, info and warning records of the decisions made while downloading and
+	// installing, such as retries, redirects, the assets chosen and how they are extracted.
+	// Nothing is logged when it is nil. Progress is reported separately, see ProgressHandler.
+	Logger *slog.Logger
 	// ProgressTracker is the progress tracker to use for downloads.
 	ProgressTracker getter.ProgressTracker
+	// Header holds extra headers sent with every API request and asset download.
+	Header http.Header
+	// UserAgent is the User-Agent sent with every API request and asset download, DefaultUserAgent
+	// when empty. A User-Agent in Header takes precedence.
+	UserAgent string
+	// Token is the GitHub token sent as a bearer token with every request.
+	Token string
+	// Context cancels API requests, the waits between their retries and downloads started by
+	// methods without a context parameter. context.Background() is used when it is nil.
+	Context context.Context
+	// Resume enables resuming interrupted downloads of raw (non-archive) assets from a
+	// "<filename>.part" file left in the destination directory.
+	Resume bool
+	// ProgressHandler receives the extraction events of archives. Download progress is reported
+	// through ProgressTracker, see NewProgressTracker.
+	ProgressHandler ProgressHandler
+	// ExtractProgress is called while archives are extracted.
+	//
+	// Deprecated: use ProgressHandler.
+	ExtractProgress ExtractProgressFunc
+	// OutputName is the file name raw (non-archive) downloads are saved as.
+	// The last segment of the download URL is used when it is empty.
+	OutputName string
+	// ExpectedSHA256 is the hex encoded SHA-256 checksum the downloaded asset must match.
+	// An empty value disables verification.
+	ExpectedSHA256 string
+	// StripComponents is the number of leading path components removed from archive entries.
+	// A negative value strips the top-level directory of tarballs whose entries all lie below one,
+	// and extracts other tarballs and zip archives verbatim.
+	StripComponents int
+	// ExtractPattern, when not empty, limits extraction to the archive entries matching this glob
+	// (see path.Match), after StripComponents is applied. A pattern without a slash is matched
+	// against the base name of entries, e.g. "tool*"; others against the whole name, e.g. "bin/*".
+	// Directory entries are skipped. Raw downloads are not affected.
+	ExtractPattern string
+	// Flatten discards the directory structure of archives: files are extracted directly into the
+	// destination under their base name and directory entries are skipped. Extraction fails when
+	// two files have the same base name.
+	Flatten bool
+	// FailOnCollision fails a download when a file would overwrite a file written before by the same
+	// archive or, for installations, by another asset. Collisions are reported to ProgressHandler as
+	// EventCollision otherwise.
+	FailOnCollision bool
+	// KeepArchive keeps downloaded archives in the destination under their file name next to the
+	// files extracted from them, instead of deleting them after extraction.
+	KeepArchive bool
+	// DetectArchiveByMagic extracts downloads without a known archive extension whose leading
+	// bytes identify a zip archive or a gzip, xz or bzip2 compressed tarball. Other files,
+	// including compressed files that are not tarballs, are kept as they are.
+	DetectArchiveByMagic bool
+	// MovingTags lists tags that are moved from release to release, e.g. "nightly". Installing
+	// such a tag installs and records the release whose tag points to the same commit instead, see
+	// ResolveMovingTag, so a later install of the moving tag can tell whether it moved.
+	MovingTags []string
+	// Extractors maps archive extensions, including the leading dot and matched case-insensitively,
+	// to the extractors of such archives, e.g. ".tar.zst". They take precedence over the archive
+	// types libfetch extracts itself and over go-getter. The StripComponents, ExtractPattern and
+	// Flatten settings do not apply to registered extractors.
+	Extractors map[string]Extractor
+	// CaseInsensitiveAssets compares asset names ignoring case: when the release has no asset with
+	// the requested name, the asset whose name differs only in case is downloaded instead, and asset
+	// patterns and globs match regardless of case. Installations record the name that was used.
+	CaseInsensitiveAssets bool
+	// ConnectTimeout limits how long establishing a connection, including the TLS handshake, may
+	// take. Zero means no limit.
+	ConnectTimeout time.Duration
+	// Timeout limits how long API requests may take in total and how long a download may go
+	// without receiving data; slow downloads that keep receiving data are not interrupted.
+	// Zero means no limit.
+	Timeout time.Duration
+	// MaxRedirects limits how many redirects a request follows. Zero means DefaultMaxRedirects,
+	// a negative value disables following redirects.
+	MaxRedirects int
+	// HTTPClient, when set, is used for all requests instead of the clients built from the settings
+	// above. Proxy, NoProxy, ConnectTimeout, MaxRedirects and the API request timeout are then up
+	// to the client; Timeout still stops downloads that stall.
+	HTTPClient *http.Client
+	// Transport, when set, performs all requests instead of the transport built from Proxy,
+	// NoProxy and ConnectTimeout, while timeouts and redirects are still handled as configured.
+	// It lets tests serve canned release metadata and assets without a network.
+	Transport http.RoundTripper
+	// RootCertificates are trusted in addition to the system's root certificates, e.g. the private
+	// CA of a proxy that intercepts TLS, see ParseCertificates.
+	RootCertificates []*x509.Certificate
+	// InsecureSkipVerify disables the verification of server certificates. This is DANGEROUS: any
+	// server, or anyone intercepting the connection, can then impersonate GitHub and serve
+	// arbitrary assets. Prefer adding the CA to RootCertificates.
+	InsecureSkipVerify bool
+	// ETagCachePath is the file the ETag and tag of the latest release are cached in, so the
+	// cache survives restarts. They are cached in memory for the lifetime of the process when it
+	// is empty.
+	ETagCachePath string
+	// ParallelChunks is the number of concurrent range requests large downloads are split into
+	// when the server supports ranges. Values below 2 download in a single stream.
+	ParallelChunks int
+	// WriteBufferSize is the size in bytes of the buffer resumable downloads are written through,
+	// so responses arriving in many small chunks do not cost a write each. Zero uses
+	// DefaultWriteBufferSize, a negative value writes every chunk directly.
+	WriteBufferSize int
+}
+
+// LatestReleaseURL returns the API URL of the latest release of repo for the GitHub API at apiBaseURL.
+func LatestReleaseURL(apiBaseURL, repo string) string {
+	return fmt.Sprintf("%s/repos/%s/releases/latest", strings.TrimSuffix(apiBaseURL, "/"), repo)
+}
+
+// ValidateRepo checks that repo is in the format "owner/repo": two non-empty components separated
+// by a single slash, without whitespace. It returns an error matching ErrInvalidRepo otherwise.
+func ValidateRepo(repo string) error {
+	owner, name, ok := strings.Cut(repo, "/")
+	if !ok || owner == "" || name == "" || strings.Contains(name, "/") || strings.ContainsFunc(repo, unicode.IsSpace) {
+		return fmt.Errorf("%w: %q", ErrInvalidRepo, repo)
+	}
+	return nil
 }
 
 func NewDownloader(repo string) *Downloader {
-	apiURL := fmt.Sprintf("https://api.github.com/repos/%s/releases/latest", repo)
 	return &Downloader{
 		RetryCount:      3,
 		RetryDelay:      3 * time.Second,
-		ApiURL:          apiURL,
+		ApiURL:          LatestReleaseURL(DefaultAPIBaseURL, repo),
+		DownloadBaseURL: DefaultDownloadBaseURL,
 		Repo:            repo,
 		Proxy:           "",
 		ProgressTracker: DefaultProgressTracker(),
@@ -47,82 +204,246 @@ func NewDownloader(repo string) *Downloader {
 }
 
 func NewDownloaderWithConfig(repo string, retryCount int, retryDelay time.Duration, proxy string, progressTracker getter.ProgressTracker) *Downloader {
-	apiURL := fmt.Sprintf("https://api.github.com/repos/%s/releases/latest", repo)
 	return &Downloader{
 		RetryCount:      retryCount,
 		RetryDelay:      retryDelay,
-		ApiURL:          apiURL,
+		ApiURL:          LatestReleaseURL(DefaultAPIBaseURL, repo),
+		DownloadBaseURL: DefaultDownloadBaseURL,
 		Repo:            repo,
 		Proxy:           proxy,
 		ProgressTracker: progressTracker,
+		StripComponents: DefaultStripComponents,
+		ConnectTimeout:  DefaultConnectTimeout,
+		Timeout:         DefaultTimeout,
 	}
 }
 
 // LatestVersion fetches the latest release tag from the GitHub API for the specified repository.
+// When the API rate limit is exhausted and resets soon, it waits for the reset before retrying.
+// The API may report a draft as the latest release to authenticated requests; drafts are skipped
+// in favor of the most recently published release, and ErrDraftRelease is returned if there is none.
 func (f *Downloader) LatestVersion() (string, error) {
+	ctx := f.context()
 	var version string
 	var err error
-	for range f.RetryCount {
+	for n := range f.RetryCount {
 		version, err = f.getLatestVersion()
 		if err == nil {
 			return version, nil
 		}
-		time.Sleep(f.RetryDelay)
+		if ctx.Err() != nil {
+			return "", cancelledError(ctx)
+		}
+		if n+1 == f.RetryCount {
+			break
+		}
+
+		wait := f.RetryDelay
+		var rateLimitErr *RateLimitError
+		if errors.As(err, &rateLimitErr) && !rateLimitErr.Reset.IsZero() {
+			wait = time.Until(rateLimitErr.Reset)
+			if wait > maxRateLimitWait {
+				break
+			}
+			if wait <= 0 {
+				// The limit has reset already, or the reset time is off
+				wait = f.RetryDelay
+			}
+		}
+		f.reportRetry(ctx, f.ApiURL, n+2, f.RetryCount, wait, err)
+		select {
+		case <-ctx.Done():
+			return "", cancelledError(ctx)
+		case <-time.After(wait):
+		}
 	}
 
-	return "", errors.New("unable to fetch latest version")
+	if err == nil {
+		return "", errors.New("unable to fetch latest version")
+	}
+	return "", fmt.Errorf("unable to fetch latest version: %w", err)
 }
 
+// getLatestVersion fetches the latest release tag once. The ETag of the previous response is
+// sent along, so an unchanged latest release is answered from the cache without using up the
+// rate limit.
 func (f *Downloader) getLatestVersion() (string, error) {
-	req, err := http.NewRequest("GET", f.ApiURL, nil)
+	cached, _ := f.cachedLatestVersion()
+
+	var result struct {
+		TagName string `json:"tag_name"`
+		Draft   bool   `json:"draft"`
+	}
+
+	header, notModified, err := f.getJSONIfModified(f.ApiURL, cached.ETag, &result)
 	if err != nil {
 		return "", err
 	}
+	if notModified {
+		f.logger().Debug("latest release not modified, using cached tag", "repo", f.Repo, "tag", cached.TagName)
+		return cached.TagName, nil
+	}
+	if result.Draft {
+		// The assets of drafts cannot be downloaded from their public URLs
+		f.logger().Warn("latest release is a draft, looking for the latest published release", "repo", f.Repo, "tag", result.TagName)
+		return f.latestPublishedVersion()
+	}
+
+	if etag := header.Get("ETag"); etag != "" {
+		f.cacheLatestVersion(etagEntry{ETag: etag, TagName: result.TagName})
+	}
+	f.logger().Debug("resolved latest release", "repo", f.Repo, "tag", result.TagName)
+	return result.TagName, nil
+}
+
+// latestPublishedVersion returns the tag of the most recently published release that is neither a
+// draft nor a prerelease, for when the API reports a draft as the latest release.
+func (f *Downloader) latestPublishedVersion() (string, error) {
+	version, err := f.latestReleaseMatching(func(release ReleaseInfo) bool {
+		return !release.Prerelease
+	})
+	if err != nil {
+		return "", fmt.Errorf("%w; authenticate or use a specific tag: %w", ErrDraftRelease, err)
+	}
+	return version, nil
+}
+
+// newAPIRequest creates a GET request for the GitHub API with the required headers set.
+func (f *Downloader) newAPIRequest(apiURL string) (*http.Request, error) {
+	req, err := http.NewRequestWithContext(f.context(), "GET", apiURL, nil)
+	if err != nil {
+		return nil, err
+	}
 
 	// Set required headers for GitHub API
 	req.Header.Set("Accept", "application/vnd.github+json")
 	req.Header.Set("X-GitHub-Api-Version", "2022-11-28")
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
 
-	// Create HTTP client with proxy support
-	client := f.createHTTPClient()
-	resp, err := client.Do(req)
-	if err != nil {
-		return "", err
+	return req, nil
+}
+
+// context returns the context of API requests and of downloads without an explicit context.
+func (f *Downloader) context() context.Context {
+	if f.Context != nil {
+		return f.Context
 	}
-	defer resp.Body.Close()
+	return context.Background()
+}
 
-	if resp.StatusCode != http.StatusOK {
-		body, _ := io.ReadAll(resp.Body)
-		return "", fmt.Errorf("received status code %d from GitHub API: %s", resp.StatusCode, string(body))
+// requestHeader returns the headers that are sent with every request, API and download alike.
+func (f *Downloader) requestHeader() http.Header {
+	header := http.Header{}
+	if f.UserAgent != "" {
+		header.Set("User-Agent", f.UserAgent)
+	} else {
+		header.Set("User-Agent", DefaultUserAgent)
+	}
+	if f.Token != "" {
+		header.Set("Authorization", "Bearer "+f.Token)
 	}
+	for name, values := range f.Header {
+		header[http.CanonicalHeaderKey(name)] = values
+	}
+	return header
+}
 
-	var result struct {
-		TagName string `json:"tag_name"`
+// createHTTPClient creates an HTTP client for API and other small requests, which must complete
+// within Timeout. HTTPClient is returned when it is set.
+func (f *Downloader) createHTTPClient() *http.Client {
+	if f.HTTPClient != nil {
+		return f.HTTPClient
+	}
+	return &http.Client{
+		Timeout:       f.Timeout,
+		Transport:     f.roundTripper(),
+		CheckRedirect: f.checkRedirect,
 	}
+}
 
-	if err := json.NewDecoder(resp.Body).Decode(&result); err != nil {
-		return "", err
+// createDownloadClient creates an HTTP client for asset downloads. Downloads have no overall time
+// limit, a stalled download is stopped by the idle timeout of get instead. HTTPClient is returned
+// when it is set.
+func (f *Downloader) createDownloadClient() *http.Client {
+	if f.HTTPClient != nil {
+		return f.HTTPClient
+	}
+	return &http.Client{
+		Transport:     f.roundTripper(),
+		CheckRedirect: f.checkRedirect,
 	}
+}
 
-	return result.TagName, nil
+// roundTripper returns Transport if it is set, or the transport built from the settings.
+func (f *Downloader) roundTripper() http.RoundTripper {
+	if f.Transport != nil {
+		return f.Transport
+	}
+	return f.transport()
 }
 
-// createHTTPClient creates an HTTP client with proxy support if configured
-func (f *Downloader) createHTTPClient() *http.Client {
-	transport := &http.Transport{}
+// transportConfig holds the settings an HTTP transport is built from.
+type transportConfig struct {
+	proxy          string
+	noProxy        string
+	connectTimeout time.Duration
+	timeout        time.Duration
+	// rootCertificates holds the DER encodings of the extra root certificates, concatenated.
+	rootCertificates   string
+	insecureSkipVerify bool
+}
 
-	// Set proxy if configured
-	if f.Proxy != "" {
-		proxyURL, err := url.Parse(f.Proxy)
-		if err == nil {
-			transport.Proxy = http.ProxyURL(proxyURL)
-		}
+// transports caches the transports built for each transportConfig, so connections and TLS
+// sessions are reused across requests and downloaders with the same settings.
+var transports sync.Map
+
+// transport returns the HTTP transport for the connect timeout, proxy and TLS settings, creating
+// it on first use.
+func (f *Downloader) transport() *http.Transport {
+	config := transportConfig{
+		proxy:              f.Proxy,
+		noProxy:            f.NoProxy,
+		connectTimeout:     f.ConnectTimeout,
+		timeout:            f.Timeout,
+		rootCertificates:   rootCertificatesKey(f.RootCertificates),
+		insecureSkipVerify: f.InsecureSkipVerify,
+	}
+	if transport, ok := transports.Load(config); ok {
+		return transport.(*http.Transport)
 	}
 
-	return &http.Client{
-		Timeout:   30 * time.Second,
-		Transport: transport,
+	transport, _ := transports.LoadOrStore(config, newTransport(config))
+	return transport.(*http.Transport)
+}
+
+// newTransport creates an HTTP transport with the connect timeout, proxy support and TLS settings
+// if configured.
+func newTransport(config transportConfig) *http.Transport {
+	dialer := &net.Dialer{
+		Timeout:   config.connectTimeout,
+		KeepAlive: 30 * time.Second,
+	}
+	transport := &http.Transport{
+		DialContext:           dialer.DialContext,
+		TLSHandshakeTimeout:   config.connectTimeout,
+		ResponseHeaderTimeout: config.timeout,
+		MaxIdleConnsPerHost:   10,
+		IdleConnTimeout:       90 * time.Second,
 	}
+
+	// Set proxy if configured
+	if config.proxy != "" {
+		transport.Proxy = proxyFunc(config.proxy, config.noProxy)
+	}
+	if tlsConfig := tlsConfig(config); tlsConfig != nil {
+		transport.TLSClientConfig = tlsConfig
+		// A custom TLS configuration disables HTTP/2 unless it is requested
+		transport.ForceAttemptHTTP2 = true
+	}
+
+	return transport
 }
 
 // GetReleaseAssetURL returns the download URL for a specific asset in the latest release.
@@ -133,22 +454,91 @@ func (f *Downloader) GetReleaseAssetURL(assetName string) (string, error) {
 		return "", err
 	}
 
-	// Construct the download URL
-	baseURL := fmt.Sprintf("https://github.com/%s/releases/download/%s", f.Repo, version)
-	return fmt.Sprintf("%s/%s", baseURL, assetName), nil
+	return f.GetReleaseAssetURLByVersion(assetName, version), nil
+}
+
+// downloadBaseURL returns the base URL release assets are downloaded from.
+func (f *Downloader) downloadBaseURL() string {
+	if f.DownloadBaseURL == "" {
+		return DefaultDownloadBaseURL
+	}
+	return strings.TrimSuffix(f.DownloadBaseURL, "/")
 }
 
 // GetReleaseAssetURLByVersion returns the download URL for a specific asset in a specific release version.
+// The URL is constructed from the download base URL; the asset name is escaped as a path segment.
 func (f *Downloader) GetReleaseAssetURLByVersion(assetName, version string) string {
-	baseURL := fmt.Sprintf("https://github.com/%s/releases/download/%s", f.Repo, version)
-	return fmt.Sprintf("%s/%s", baseURL, assetName)
+	baseURL := fmt.Sprintf("%s/%s/releases/download/%s", f.downloadBaseURL(), f.Repo, version)
+	return fmt.Sprintf("%s/%s", baseURL, url.PathEscape(assetName))
+}
+
+// assetURL returns the download URL of assetName in the given release version (empty string for latest).
+func (f *Downloader) assetURL(assetName, version string) (string, error) {
+	if version == "" {
+		// Get latest version
+		return f.GetReleaseAssetURL(assetName)
+	}
+
+	// Use specified version
+	return f.GetReleaseAssetURLByVersion(assetName, version), nil
+}
+
+// AssetSize returns the size in bytes of assetName in the given release version (empty string
+// for latest) without downloading it, or -1 if the server does not report it. GitHub redirects
+// asset downloads to its storage backend; the size is read from the final response.
+func (f *Downloader) AssetSize(assetName, version string) (int64, error) {
+	assetURL, err := f.assetURL(assetName, version)
+	if err != nil {
+		return 0, err
+	}
+
+	return f.headAsset(assetURL)
+}
+
+// headAsset issues a HEAD request for assetURL with retries, following redirects, and returns
+// the Content-Length of the final response, or -1 when it is not reported.
+func (f *Downloader) headAsset(assetURL string) (int64, error) {
+	ctx := f.context()
+	var size int64
+	err := f.withRetry(ctx, assetURL, func() error {
+		var err error
+		size, err = f.head(ctx, assetURL)
+		return err
+	})
+	if err != nil && ctx.Err() != nil {
+		return 0, cancelledError(ctx)
+	}
+	return size, err
+}
+
+// head issues a single HEAD request for assetURL like headAsset.
+func (f *Downloader) head(ctx context.Context, assetURL string) (int64, error) {
+	req, err := http.NewRequestWithContext(ctx, http.MethodHead, assetURL, nil)
+	if err != nil {
+		return 0, err
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+
+	resp, err := f.createHTTPClient().Do(req)
+	if err != nil {
+		return 0, err
+	}
+	resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return 0, &DownloadStatusError{StatusCode: resp.StatusCode, URL: assetURL}
+	}
+
+	return resp.ContentLength, nil
 }
 
 // DownloadAsset downloads a specific asset from the latest release of the repository.
 // assetName is the name of the asset to download.
 // dest is the destination directory for the downloaded asset.
 func (f *Downloader) DownloadAsset(assetName, version, dest string) error {
-	return f.DownloadAssetWithContext(context.Background(), assetName, version, dest)
+	return f.DownloadAssetWithContext(f.context(), assetName, version, dest)
 }
 
 // DownloadAssetWithContext downloads a specific asset from a release using the provided context and progress tracker.
@@ -156,202 +546,536 @@ func (f *Downloader) DownloadAsset(assetName, version, dest string) error {
 // version is the release version to download from (empty string for latest).
 // dest is the destination directory for the downloaded asset.
 func (f *Downloader) DownloadAssetWithContext(ctx context.Context, assetName, version, dest string) error {
-	var url string
-	var err error
+	_, err := f.downloadAsset(ctx, assetName, version, dest, f.ExpectedSHA256)
+	return err
+}
 
-	if version == "" {
-		// Get latest version
-		url, err = f.GetReleaseAssetURL(assetName)
-		if err != nil {
-			return err
+// DownloadAssetVerified downloads a specific asset like DownloadAsset and verifies the downloaded
+// bytes against expectedSHA256 (hex encoded). Archives are verified before extraction begins,
+// so nothing is written to dest when the checksum does not match.
+func (f *Downloader) DownloadAssetVerified(assetName, version, dest, expectedSHA256 string) error {
+	_, err := f.downloadAsset(f.context(), assetName, version, dest, expectedSHA256)
+	return err
+}
+
+// downloadAsset downloads assetName into dest and returns the paths of the files it wrote,
+// relative to dest and using forward slashes.
+func (f *Downloader) downloadAsset(ctx context.Context, assetName, version, dest, checksum string) ([]string, error) {
+	_, files, err := f.downloadNamedAsset(ctx, assetName, version, dest, checksum)
+	return files, err
+}
+
+// downloadNamedAsset downloads assetName like downloadAsset and also returns the name of the
+// downloaded asset. When CaseInsensitiveAssets is set and the release has no asset named
+// assetName, the asset whose name differs only in case is downloaded instead.
+func (f *Downloader) downloadNamedAsset(ctx context.Context, assetName, version, dest, checksum string) (string, []string, error) {
+	url, err := f.assetURL(assetName, version)
+	if err != nil {
+		return "", nil, err
+	}
+
+	f.logger().Info("downloading asset", "repo", f.Repo, "asset", assetName, "version", version, "dest", dest)
+	files, err := f.downloadURL(ctx, url, dest, checksum)
+	if err == nil || !f.CaseInsensitiveAssets || !errors.Is(err, ErrAssetNotFound) {
+		return assetName, files, err
+	}
+
+	asset, err := f.findAssetFold(assetName, version)
+	if err != nil {
+		return "", nil, err
+	}
+	f.logger().Info("asset not found, downloading the asset whose name differs in case", "asset", assetName, "match", asset.Name)
+	url = asset.BrowserDownloadURL
+	if url == "" {
+		if url, err = f.assetURL(asset.Name, version); err != nil {
+			return "", nil, err
 		}
-	} else {
-		// Use specified version
-		url = f.GetReleaseAssetURLByVersion(assetName, version)
 	}
+	files, err = f.downloadURL(ctx, url, dest, checksum)
+	return asset.Name, files, err
+}
 
-	return f.get(ctx, url, dest)
+// findAssetFold returns the asset of the release tagged version (empty string for latest) whose
+// name equals name ignoring case.
+func (f *Downloader) findAssetFold(name, version string) (AssetInfo, error) {
+	assets, err := f.GetReleaseAssetInfo(version)
+	if err != nil {
+		return AssetInfo{}, err
+	}
+	for _, asset := range assets {
+		if strings.EqualFold(asset.Name, name) {
+			return asset, nil
+		}
+	}
+	return AssetInfo{}, noMatchingAsset(name, assetNamesOf(assets))
+}
+
+// downloadURL downloads the asset at url into dest with retries, extracting archives, and returns
+// the files written relative to dest.
+func (f *Downloader) downloadURL(ctx context.Context, url, dest, checksum string) ([]string, error) {
+	ctx = withDestination(ctx, dest)
+
+	// go-getter does not keep the cause of failed requests, so reject an invalid proxy up front
+	if f.Proxy != "" {
+		if _, err := parseProxy(f.Proxy); err != nil {
+			return nil, err
+		}
+	}
+
+	var files []string
+	err := f.withMirrors(ctx, url, func(f *Downloader, src string) error {
+		var err error
+		files, err = f.get(ctx, src, dest, checksum)
+		return err
+	})
+	if err != nil && ctx.Err() != nil {
+		return nil, cancelledError(ctx)
+	}
+	return files, f.tokenScopeError(err)
 }
 
 // DownloadLatestAsset downloads the latest asset that matches a pattern from the repository.
 // pattern is a regex pattern to match against asset names.
 // dest is the destination directory for the downloaded asset.
 func (f *Downloader) DownloadLatestAsset(pattern string, dest string) error {
-	assets, err := f.GetLatestReleaseAssets()
+	return f.DownloadAssetByPattern(pattern, "", dest)
+}
+
+// DownloadAssetByPattern downloads the first asset of the release tagged version (empty string
+// for latest) whose name matches a pattern.
+// pattern is a regex pattern to match against asset names.
+// dest is the destination directory for the downloaded asset.
+func (f *Downloader) DownloadAssetByPattern(pattern, version, dest string) error {
+	expr := pattern
+	if f.CaseInsensitiveAssets {
+		expr = "(?i)" + pattern
+	}
+	re, err := regexp.Compile(expr)
+	if err != nil {
+		return fmt.Errorf("invalid asset pattern %q: %w", pattern, err)
+	}
+
+	assets, err := f.GetReleaseAssetInfo(version)
 	if err != nil {
 		return err
 	}
 
 	// Find the first asset that matches the pattern
 	for _, asset := range assets {
-		if matched, _ := regexp.MatchString(pattern, asset); matched {
-			return f.DownloadAsset(asset, "", dest)
+		if re.MatchString(asset.Name) {
+			f.logger().Debug("asset matches pattern", "pattern", pattern, "asset", asset.Name)
+			return f.downloadReleaseAsset(asset, version, dest)
 		}
 	}
 
-	return errors.New("no matching asset found")
+	return noMatchingAsset("/"+pattern+"/", assetNamesOf(assets))
 }
 
-// GetLatestReleaseAssets returns a list of asset names from the latest release.
-func (f *Downloader) GetLatestReleaseAssets() ([]string, error) {
-	req, err := http.NewRequest("GET", f.ApiURL, nil)
+// DownloadLatestAssetGlob downloads the first asset of the latest release whose name matches a
+// shell glob pattern such as "*-linux-x64.tar.gz". The whole asset name must match; "*" matches
+// any sequence of characters, "?" matches a single character and "[...]" matches a character class,
+// as implemented by path.Match.
+// dest is the destination directory for the downloaded asset.
+func (f *Downloader) DownloadLatestAssetGlob(glob string, dest string) error {
+	if _, err := path.Match(glob, ""); err != nil {
+		return fmt.Errorf("invalid glob pattern %q: %w", glob, err)
+	}
+
+	assets, err := f.GetLatestReleaseAssetInfo()
 	if err != nil {
-		return nil, err
+		return err
 	}
 
-	// Set required headers for GitHub API
-	req.Header.Set("Accept", "application/vnd.github+json")
-	req.Header.Set("X-GitHub-Api-Version", "2022-11-28")
+	for _, asset := range assets {
+		if f.matchGlob(glob, asset.Name) {
+			return f.downloadReleaseAsset(asset, "", dest)
+		}
+	}
+
+	return noMatchingAsset(glob, assetNamesOf(assets))
+}
 
-	// Create HTTP client with proxy support
-	client := f.createHTTPClient()
-	resp, err := client.Do(req)
+// DownloadMatchingAll downloads every asset of the release tagged version (empty string for latest)
+// whose name matches one of globs, shell glob patterns like those of DownloadLatestAssetGlob, e.g.
+// a binary together with its checksum and signature files. Nothing is downloaded when a glob
+// matches no asset. It returns the names of the downloaded assets in release order; assets
+// matched by several globs are downloaded once.
+// dest is the destination directory for the downloaded assets.
+func (f *Downloader) DownloadMatchingAll(globs []string, version, dest string) ([]string, error) {
+	if len(globs) == 0 {
+		return nil, errors.New("no asset patterns given")
+	}
+	for _, glob := range globs {
+		if _, err := path.Match(glob, ""); err != nil {
+			return nil, fmt.Errorf("invalid glob pattern %q: %w", glob, err)
+		}
+	}
+
+	assets, err := f.GetReleaseAssetInfo(version)
 	if err != nil {
 		return nil, err
 	}
-	defer resp.Body.Close()
 
-	if resp.StatusCode != http.StatusOK {
-		body, _ := io.ReadAll(resp.Body)
-		return nil, fmt.Errorf("received status code %d from GitHub API: %s", resp.StatusCode, string(body))
+	for _, glob := range globs {
+		if !slices.ContainsFunc(assets, func(asset AssetInfo) bool { return f.matchGlob(glob, asset.Name) }) {
+			return nil, noMatchingAsset(glob, assetNamesOf(assets))
+		}
+	}
+	var matched []AssetInfo
+	for _, asset := range assets {
+		if slices.ContainsFunc(globs, func(glob string) bool { return f.matchGlob(glob, asset.Name) }) {
+			matched = append(matched, asset)
+		}
+	}
+	if f.ExpectedSHA256 != "" && len(matched) > 1 {
+		return nil, errors.New("an expected SHA-256 can only be verified for a single asset")
 	}
 
-	var result struct {
-		Assets []struct {
-			Name string `json:"name"`
-		} `json:"assets"`
+	names := make([]string, 0, len(matched))
+	for _, asset := range matched {
+		if err := f.downloadReleaseAsset(asset, version, dest); err != nil {
+			return names, fmt.Errorf("error downloading asset %s: %w", asset.Name, err)
+		}
+		names = append(names, asset.Name)
+	}
+	return names, nil
+}
+
+// matchGlob reports whether the asset name matches glob, ignoring case when CaseInsensitiveAssets is set.
+func (f *Downloader) matchGlob(glob, name string) bool {
+	if f.CaseInsensitiveAssets {
+		glob, name = strings.ToLower(glob), strings.ToLower(name)
+	}
+	matched, _ := path.Match(glob, name)
+	return matched
+}
+
+// downloadReleaseAsset downloads an asset listed by the GitHub API from its browser_download_url,
+// falling back to the URL constructed from version when the API did not report one.
+func (f *Downloader) downloadReleaseAsset(asset AssetInfo, version, dest string) error {
+	if asset.BrowserDownloadURL == "" {
+		return f.DownloadAsset(asset.Name, version, dest)
 	}
 
-	if err := json.NewDecoder(resp.Body).Decode(&result); err != nil {
+	_, err := f.downloadURL(f.context(), asset.BrowserDownloadURL, dest, f.ExpectedSHA256)
+	return err
+}
+
+// noMatchingAsset returns the error reported when no asset matches pattern, listing the
+// available assets so a wrong pattern is easy to spot.
+func noMatchingAsset(pattern string, assets []string) error {
+	return fmt.Errorf("%w: no asset matched %s; available: [%s]", ErrAssetNotFound, pattern, strings.Join(assets, ", "))
+}
+
+// GetLatestReleaseAssets returns a list of asset names from the latest release.
+func (f *Downloader) GetLatestReleaseAssets() ([]string, error) {
+	return f.GetReleaseAssets("")
+}
+
+// GetReleaseAssets returns a list of asset names from the release tagged version, or from the
+// latest release when version is empty.
+func (f *Downloader) GetReleaseAssets(version string) ([]string, error) {
+	assets, err := f.GetReleaseAssetInfo(version)
+	if err != nil {
 		return nil, err
 	}
 
-	assetNames := make([]string, len(result.Assets))
-	for i, asset := range result.Assets {
-		assetNames[i] = asset.Name
+	return assetNamesOf(assets), nil
+}
+
+// assetNamesOf returns the names of assets.
+func assetNamesOf(assets []AssetInfo) []string {
+	names := make([]string, len(assets))
+	for i, asset := range assets {
+		names[i] = asset.Name
+	}
+	return names
+}
+
+// GetLatestReleaseAssetInfo returns the metadata of the assets of the latest release.
+func (f *Downloader) GetLatestReleaseAssetInfo() ([]AssetInfo, error) {
+	return f.GetReleaseAssetInfo("")
+}
+
+// GetReleaseAssetInfo returns the metadata of the assets of the release tagged version, or of the
+// latest release when version is empty.
+func (f *Downloader) GetReleaseAssetInfo(version string) ([]AssetInfo, error) {
+	var result struct {
+		Assets []AssetInfo `json:"assets"`
+	}
+
+	if _, err := f.getJSON(f.releaseURL(version), &result); err != nil {
+		return nil, err
 	}
 
-	return assetNames, nil
+	return result.Assets, nil
 }
 
 func (f *Downloader) setGetterClient(ctx context.Context, url, dest string) *getter.Client {
 	myHttpGetter := &getter.HttpGetter{
-		Client: f.createHTTPClient(),
+		Client: f.createDownloadClient(),
+		Header: f.requestHeader(),
 	}
+	// Check for cancellation, reset the idle timeout and hash the data while the body is read
+	tracker := newCancelTracker(ctx, f.ProgressTracker)
+	tracker.digest = digestFrom(ctx)
+
 	client := &getter.Client{
 		Ctx:              ctx,
 		Src:              url,
 		Dst:              dest,
 		Mode:             getter.ClientModeAny,
-		ProgressListener: f.ProgressTracker,
+		ProgressListener: tracker,
 		Getters: map[string]getter.Getter{
 			"http":  myHttpGetter,
 			"https": myHttpGetter,
 		},
 	}
 
-	if f.ProgressTracker != nil {
-		client.ProgressListener = f.ProgressTracker
-	}
 	return client
 }
 
-func (f *Downloader) get(ctx context.Context, url, dest string) error {
-	// Check if it's a .tar.gz file
-	if strings.HasSuffix(url, ".tar.gz") {
-		return f.downloadAndExtractTarGz(ctx, url, dest)
+// get downloads src into dest, extracting archives, and returns the files written relative to dest.
+// When checksum is not empty the downloaded bytes must match it (hex encoded SHA-256); go-getter
+// verifies archives before decompressing them.
+func (f *Downloader) get(ctx context.Context, src, dest, checksum string) ([]string, error) {
+	// Stop downloads that go without receiving data for longer than Timeout
+	ctx, idle := withIdleTimeout(ctx, f.Timeout)
+	defer idle.stop()
+
+	ctx, redirects := withRedirectTracking(ctx)
+	files, err := f.getAsset(ctx, src, dest, checksum)
+	if expiredRedirect(err, redirects) {
+		// Requesting src again resolves a freshly signed redirect target
+		files, err = f.getAsset(ctx, src, dest, checksum)
 	}
-	client := f.setGetterClient(ctx, url, dest)
-	if err := client.Get(); err != nil {
-		return err
+	if err != nil && idle.expired() {
+		return nil, fmt.Errorf("%w: no data received for %s", ErrIdleTimeout, f.Timeout)
 	}
-
-	return nil
+	if err == nil && f.DetectArchiveByMagic && len(files) == 1 && !f.isArchive(src) {
+		return f.extractSniffed(dest, files[0])
+	}
+	return files, err
 }
 
-// downloadAndExtractTarGz downloads a .tar.gz file and extracts it to the destination directory.
-func (f *Downloader) downloadAndExtractTarGz(ctx context.Context, url, dest string) error {
-	downloadFile := filepath.Join(dest, filepath.Base(url))
+// getAsset downloads src into dest like get.
+func (f *Downloader) getAsset(ctx context.Context, src, dest, checksum string) ([]string, error) {
+	// Check if it's an archive we extract ourselves
+	archiveExt := f.archiveExtension(src)
 
-	client := f.setGetterClient(ctx, url+"?archive=false", dest)
+	if f.Resume && !f.isArchive(src) {
+		return f.downloadRaw(ctx, src, dest, checksum)
+	}
 
-	if err := client.Get(); err != nil {
-		return err
+	// Archives that libfetch does not extract itself are left to go-getter
+	if f.ParallelChunks > 1 && (archiveExt != "" || !f.isArchive(src)) {
+		if files, ok, err := f.getChunked(ctx, src, dest, checksum, archiveExt); ok {
+			return files, err
+		}
 	}
-	defer os.Remove(downloadFile)
 
-	resp, err := os.Open(downloadFile)
-	if err != nil {
-		return fmt.Errorf("failed to open downloaded file: %w", err)
+	if checksum != "" {
+		src = withQuery(src, "checksum", "sha256:"+strings.ToLower(checksum))
 	}
-	defer resp.Close()
 
-	// Create gzip reader
-	gzr, err := gzip.NewReader(resp)
+	if archiveExt != "" {
+		return f.downloadAndExtract(ctx, src, dest, archiveExt)
+	}
+	if f.OutputName != "" {
+		src = withQuery(src, "filename", f.OutputName)
+	}
+	// go-getter does not report what it wrote, so compare the directory before and after
+	before := listFiles(dest)
+	client := f.setGetterClient(ctx, src, dest)
+	if err := client.Get(); err != nil {
+		f.removePartialFile(src, dest, before)
+		return nil, getterError(err, src)
+	}
+	return addedFiles(dest, before), nil
+}
+
+// removePartialFile removes what a failed go-getter download of the raw file src left in dest,
+// unless the file already existed before the download.
+func (f *Downloader) removePartialFile(src, dest string, before []string) {
+	if f.isArchive(src) {
+		return
+	}
+	u, err := url.Parse(src)
 	if err != nil {
-		return fmt.Errorf("failed to create gzip reader: %w", err)
+		return
 	}
-	defer gzr.Close()
 
-	// Create tar reader
-	tr := tar.NewReader(gzr)
+	name := path.Base(u.Path)
+	if f.OutputName != "" {
+		name = f.OutputName
+	}
+	if !slices.Contains(before, name) {
+		os.Remove(filepath.Join(dest, name))
+	}
+}
 
-	// Extract files
-	for {
-		header, err := tr.Next()
-		if err == io.EOF {
-			break
+// listFiles returns the paths of all files below dir, relative to dir and using forward slashes.
+func listFiles(dir string) []string {
+	var files []string
+	filepath.WalkDir(dir, func(name string, d fs.DirEntry, err error) error {
+		if err != nil || d.IsDir() {
+			return nil
 		}
-		if err != nil {
-			return fmt.Errorf("failed to read tar header: %w", err)
+		if rel, err := filepath.Rel(dir, name); err == nil {
+			files = append(files, filepath.ToSlash(rel))
 		}
+		return nil
+	})
+	return files
+}
 
-		// Strip the top-level directory (e.g., "llama-b1234/")
-		name := header.Name
-		if idx := strings.Index(name, "/"); idx != -1 {
-			name = name[idx+1:]
+// addedFiles returns the files below dir, relative to dir and using forward slashes, that are
+// not listed in before.
+func addedFiles(dir string, before []string) []string {
+	var files []string
+	for _, file := range listFiles(dir) {
+		if !slices.Contains(before, file) {
+			files = append(files, file)
 		}
+	}
+	return files
+}
 
-		// Skip empty names (the top-level directory itself)
-		if name == "" {
-			continue
+// withQuery returns rawURL with the query parameter key set to value.
+func withQuery(rawURL, key, value string) string {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return rawURL
+	}
+	q := u.Query()
+	q.Set(key, value)
+	u.RawQuery = q.Encode()
+	return u.String()
+}
+
+// getterStatusPattern matches the status errors reported by go-getter's HTTP getter.
+var getterStatusPattern = regexp.MustCompile(`bad response code: (\d{3})`)
+
+// getterError translates go-getter failures for src into libfetch errors.
+func getterError(err error, src string) error {
+	var cerr *getter.ChecksumError
+	if errors.As(err, &cerr) {
+		return fmt.Errorf("%w: expected %x, got %x", ErrChecksumMismatch, cerr.Expected, cerr.Actual)
+	}
+	if m := getterStatusPattern.FindStringSubmatch(err.Error()); m != nil {
+		statusCode, _ := strconv.Atoi(m[1])
+		return &DownloadStatusError{StatusCode: statusCode, URL: src}
+	}
+	return err
+}
+
+// downloadAndExtract downloads an archive and extracts it to the destination directory.
+// The archive is spooled to a temporary directory and decoded as a stream from there, so memory
+// use stays bounded regardless of the asset size and the archive never lands inside dest.
+func (f *Downloader) downloadAndExtract(ctx context.Context, src, dest, ext string) ([]string, error) {
+	u, err := url.Parse(src)
+	if err != nil {
+		return nil, fmt.Errorf("invalid download url: %w", err)
+	}
+
+	spoolDir, err := os.MkdirTemp("", "libfetch-*")
+	if err != nil {
+		return nil, fmt.Errorf("failed to create temporary directory: %w", err)
+	}
+	defer os.RemoveAll(spoolDir)
+
+	downloadFile := filepath.Join(spoolDir, path.Base(u.Path))
+
+	client := f.setGetterClient(ctx, withQuery(src, "archive", "false"), spoolDir)
+
+	if err := client.Get(); err != nil {
+		return nil, getterError(err, src)
+	}
+
+	files, err := f.extractArchive(downloadFile, dest, ext)
+	if err != nil {
+		return files, err
+	}
+	return f.keepArchive(downloadFile, dest, files)
+}
+
+// keepArchive moves the downloaded archive file into dest when KeepArchive is set and returns
+// files, the files extracted from it, together with the archive.
+func (f *Downloader) keepArchive(downloadFile, dest string, files []string) ([]string, error) {
+	if !f.KeepArchive {
+		return files, nil
+	}
+
+	name := filepath.Base(downloadFile)
+	target := filepath.Join(dest, name)
+	if err := os.Rename(downloadFile, target); err != nil {
+		// The archive may have been spooled to another file system
+		if err := copyFile(downloadFile, target); err != nil {
+			return files, fmt.Errorf("failed to keep archive: %w", err)
 		}
+	}
+	if !slices.Contains(files, name) {
+		files = append(files, name)
+	}
+	return files, nil
+}
 
-		target := filepath.Join(dest, filepath.Clean(name))
+// extractArchive extracts the downloaded archive file with extension ext into dest and returns
+// the files written relative to dest.
+func (f *Downloader) extractArchive(downloadFile, dest, ext string) ([]string, error) {
+	if err := os.MkdirAll(dest, 0755); err != nil {
+		return nil, fmt.Errorf("failed to create destination directory: %w", err)
+	}
 
-		switch header.Typeflag {
-		case tar.TypeDir:
-			if err := os.MkdirAll(target, os.FileMode(header.Mode)); err != nil {
-				return fmt.Errorf("failed to create directory: %w", err)
-			}
-		case tar.TypeReg:
-			// Ensure parent directory exists
-			if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
-				return fmt.Errorf("failed to create parent directory: %w", err)
-			}
+	opts := &extractOptions{
+		progress:        destinationHandler(extractHandler(f.ProgressHandler, f.ExtractProgress), dest),
+		strip:           f.StripComponents,
+		pattern:         f.ExtractPattern,
+		flatten:         f.Flatten,
+		failOnCollision: f.FailOnCollision,
+	}
+	extractor := f.extractor(ext)
+	if builtin, ok := extractor.(builtinExtractor); ok {
+		f.logger().Debug("extracting archive", "file", filepath.Base(downloadFile), "type", ext, "dest", dest, "strip_components", f.StripComponents, "pattern", f.ExtractPattern, "flatten", f.Flatten)
+		if err := builtin(downloadFile, dest, opts); err != nil {
+			return opts.files, &archiveError{err: err}
+		}
+		return opts.files, nil
+	}
 
-			// Create the file
-			f, err := os.OpenFile(target, os.O_CREATE|os.O_RDWR|os.O_TRUNC, os.FileMode(header.Mode))
-			if err != nil {
-				return fmt.Errorf("failed to create file: %w", err)
-			}
+	// Registered extractors do not report what they wrote, so compare the directory before and after
+	f.logger().Debug("extracting archive with registered extractor", "file", filepath.Base(downloadFile), "type", ext, "dest", dest)
+	before := listFiles(dest)
+	opts.start(0)
+	if err := extractor.Extract(downloadFile, dest); err != nil {
+		return addedFiles(dest, before), &archiveError{err: err}
+	}
+	files := addedFiles(dest, before)
+	opts.report(len(files), len(files), "", true)
+	return files, nil
+}
 
-			// Copy contents
-			if _, err := io.Copy(f, tr); err != nil {
-				f.Close()
-				return fmt.Errorf("failed to write file: %w", err)
-			}
-			f.Close()
-		case tar.TypeSymlink:
-			// Handle symlinks
-			if err := os.Symlink(header.Linkname, target); err != nil {
-				// Ignore error if symlink already exists
-				if !os.IsExist(err) {
-					return fmt.Errorf("failed to create symlink: %w", err)
-				}
-			}
+// archiveExtension returns the extension of src in lower case that is extracted by an extractor
+// in Extractors or by libfetch itself, preferring the longest one, or an empty string if there
+// is none.
+func (f *Downloader) archiveExtension(src string) string {
+	ext := archiveExtension(src)
+	name := strings.ToLower(urlPath(src))
+	for registered := range f.Extractors {
+		registered = strings.ToLower(registered)
+		if len(registered) > len(ext) && strings.HasSuffix(name, registered) {
+			ext = registered
 		}
 	}
+	return ext
+}
 
-	return nil
+// extractor returns the extractor of archives with the extension ext, as returned by archiveExtension.
+func (f *Downloader) extractor(ext string) Extractor {
+	for registered, extractor := range f.Extractors {
+		if strings.EqualFold(registered, ext) {
+			return extractor
+		}
+	}
+	return archiveExtractors[ext]
 }
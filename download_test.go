@@ -0,0 +1,1141 @@
+package libfetch_test
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"bytes"
+	"compress/gzip"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"net"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path"
+	"path/filepath"
+	"slices"
+	"strings"
+	"sync"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/Cyberhan123/libfetch"
+)
+
+func TestLatestVersionRateLimited(t *testing.T) {
+	reset := time.Now().Add(time.Hour).Truncate(time.Second)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("X-RateLimit-Remaining", "0")
+		w.Header().Set("X-RateLimit-Reset", fmt.Sprint(reset.Unix()))
+		w.WriteHeader(http.StatusForbidden)
+		fmt.Fprint(w, `{"message": "API rate limit exceeded"}`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL
+	downloader.RetryDelay = 0
+
+	_, err := downloader.LatestVersion()
+
+	var rateLimitErr *libfetch.RateLimitError
+	if !errors.As(err, &rateLimitErr) {
+		t.Fatalf("expected a RateLimitError, got %v", err)
+	}
+	if !rateLimitErr.Reset.Equal(reset) {
+		t.Errorf("reset = %v, want %v", rateLimitErr.Reset, reset)
+	}
+}
+
+func TestLatestVersionRateLimitedWithoutReset(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("X-RateLimit-Remaining", "0")
+		w.Header().Set("X-RateLimit-Reset", "soon")
+		w.WriteHeader(http.StatusForbidden)
+		fmt.Fprint(w, `{"message": "API rate limit exceeded"}`)
+	}))
+	defer server.Close()
+
+	var delays []time.Duration
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL
+	downloader.RetryCount = 2
+	downloader.RetryDelay = 5 * time.Millisecond
+	downloader.ProgressHandler = func(event libfetch.ProgressEvent) {
+		if event.Kind == libfetch.EventRetrying {
+			delays = append(delays, event.Delay)
+		}
+	}
+
+	var rateLimitErr *libfetch.RateLimitError
+	if _, err := downloader.LatestVersion(); !errors.As(err, &rateLimitErr) {
+		t.Fatalf("expected a RateLimitError, got %v", err)
+	}
+	if !slices.Equal(delays, []time.Duration{5 * time.Millisecond}) {
+		t.Errorf("retried after %v, want RetryDelay without a reset time", delays)
+	}
+}
+
+func TestDownloadAssetVerified(t *testing.T) {
+	var buf bytes.Buffer
+	zw := zip.NewWriter(&buf)
+	fw, err := zw.Create("bin/tool")
+	if err == nil {
+		_, err = fw.Write([]byte("tool"))
+	}
+	if err == nil {
+		err = zw.Close()
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+	archive := buf.Bytes()
+	sum := sha256.Sum256(archive)
+	checksum := hex.EncodeToString(sum[:])
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path != "/owner/repo/releases/download/v1.0.0/tool.zip" {
+			http.NotFound(w, r)
+			return
+		}
+		w.Write(archive)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+
+	dest := t.TempDir()
+	if err := downloader.DownloadAssetVerified("tool.zip", "v1.0.0", dest, strings.ToUpper(checksum)); err != nil {
+		t.Fatalf("matching checksum: %v", err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dest, "bin", "tool")); err != nil || string(d) != "tool" {
+		t.Errorf("extracted %q, %v", d, err)
+	}
+
+	wrong := strings.Repeat("ab", 32)
+	mismatch := func(err error) {
+		t.Helper()
+		if !errors.Is(err, libfetch.ErrChecksumMismatch) {
+			t.Fatalf("expected ErrChecksumMismatch, got %v", err)
+		}
+		if want := "checksum mismatch: expected " + wrong + ", got " + checksum; !strings.Contains(err.Error(), want) {
+			t.Errorf("error %q does not contain %q", err, want)
+		}
+	}
+
+	dest = t.TempDir()
+	mismatch(downloader.DownloadAssetVerified("tool.zip", "v1.0.0", dest, wrong))
+	downloader.ExpectedSHA256 = wrong
+	mismatch(downloader.DownloadAsset("tool.zip", "v1.0.0", dest))
+	if entries, err := os.ReadDir(dest); err != nil || len(entries) != 0 {
+		t.Errorf("files were extracted despite the mismatch: %v, %v", entries, err)
+	}
+}
+
+func TestDownloadAssetRetriesFailedTransfer(t *testing.T) {
+	const content = "0123456789"
+	for _, resume := range []bool{false, true} {
+		var requests atomic.Int32
+		var ranges []string
+		server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+			if requests.Add(1) == 1 {
+				if !resume {
+					http.Error(w, "unavailable", http.StatusServiceUnavailable)
+					return
+				}
+				// Break off the transfer after the first bytes
+				w.Header().Set("Content-Length", fmt.Sprint(len(content)))
+				w.Write([]byte(content[:4]))
+				return
+			}
+			ranges = append(ranges, r.Header.Get("Range"))
+			if r.Header.Get("Range") == "bytes=4-" {
+				w.Header().Set("Content-Range", fmt.Sprintf("bytes 4-%d/%d", len(content)-1, len(content)))
+				w.WriteHeader(http.StatusPartialContent)
+				w.Write([]byte(content[4:]))
+				return
+			}
+			w.Write([]byte(content))
+		}))
+
+		downloader := libfetch.NewDownloader("owner/repo")
+		downloader.DownloadBaseURL = server.URL
+		downloader.ProgressTracker = nil
+		downloader.RetryCount = 2
+		downloader.RetryDelay = 0
+		downloader.Resume = resume
+
+		dest := t.TempDir()
+		if err := downloader.DownloadAsset("tool.bin", "v1.0.0", dest); err != nil {
+			t.Fatalf("resume %v: %v", resume, err)
+		}
+		server.Close()
+
+		if d, err := os.ReadFile(filepath.Join(dest, "tool.bin")); err != nil || string(d) != content {
+			t.Errorf("resume %v: downloaded %q, %v", resume, d, err)
+		}
+		if n := requests.Load(); n != 2 {
+			t.Errorf("resume %v: sent %d requests, want 2", resume, n)
+		}
+		wantRange := ""
+		if resume {
+			wantRange = "bytes=4-"
+		}
+		if !slices.Equal(ranges, []string{wantRange}) {
+			t.Errorf("resume %v: retried with ranges %q, want %q", resume, ranges, wantRange)
+		}
+	}
+}
+
+func TestDownloadAssetDoesNotRetryPathTraversal(t *testing.T) {
+	var buf bytes.Buffer
+	zw := zip.NewWriter(&buf)
+	fw, err := zw.Create("../evil")
+	if err == nil {
+		_, err = fw.Write([]byte("evil"))
+	}
+	if err == nil {
+		err = zw.Close()
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	var requests atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		requests.Add(1)
+		w.Write(buf.Bytes())
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.Mirrors = []string{server.URL + "/mirror"}
+	downloader.ProgressTracker = nil
+	downloader.RetryCount = 3
+	downloader.RetryDelay = 0
+
+	root := t.TempDir()
+	err = downloader.DownloadAsset("evil.zip", "v1.0.0", filepath.Join(root, "dest"))
+	if !errors.Is(err, libfetch.ErrArchive) {
+		t.Fatalf("expected ErrArchive, got %v", err)
+	}
+	if n := requests.Load(); n != 1 {
+		t.Errorf("fetched the archive %d times, want once", n)
+	}
+	if _, err := os.Stat(filepath.Join(root, "evil")); !os.IsNotExist(err) {
+		t.Error("file was written outside of dest")
+	}
+}
+
+func TestDownloadLatestAssetNoMatchListsAssets(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0", "assets": [{"name": "a.zip"}, {"name": "b.tar.gz"}]}`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL
+
+	err := downloader.DownloadLatestAsset(`linux\.zip$`, t.TempDir())
+	if !errors.Is(err, libfetch.ErrAssetNotFound) {
+		t.Fatalf("expected ErrAssetNotFound, got %v", err)
+	}
+	if !strings.Contains(err.Error(), "available: [a.zip, b.tar.gz]") {
+		t.Errorf("error does not list the available assets: %v", err)
+	}
+}
+
+func TestDownloadAssetCancelledRemovesPartialFile(t *testing.T) {
+	ctx, cancel := context.WithCancel(context.Background())
+	defer cancel()
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		// Send the first chunk, then cancel and stall until the client goes away
+		w.Header().Set("Content-Length", "1048576")
+		w.Write(make([]byte, 1024))
+		w.(http.Flusher).Flush()
+		cancel()
+		<-r.Context().Done()
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+
+	dest := t.TempDir()
+	err := downloader.DownloadAssetWithContext(ctx, "tool.bin", "v1.0.0", dest)
+	if !errors.Is(err, libfetch.ErrCancelled) || !errors.Is(err, context.Canceled) {
+		t.Fatalf("expected ErrCancelled, got %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(dest, "tool.bin")); !os.IsNotExist(err) {
+		t.Errorf("partial file was not removed: %v", err)
+	}
+}
+
+func TestVerifyAgainstChecksumAsset(t *testing.T) {
+	content := "tool"
+	// SHA-256 of "tool"
+	sum := "7c9bbe5ec9b3fb774e8fa0f54247e93c34ddf8e5d16fe3073420de0ae81a262d"
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch path := r.URL.Path; {
+		case strings.HasSuffix(path, "/SHA256SUMS"):
+			fmt.Fprintf(w, "%s  tool.bin\n%s *other.bin\n", sum, strings.Repeat("0", 64))
+		case strings.HasSuffix(path, ".bin"):
+			fmt.Fprint(w, content)
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+
+	got, err := downloader.LookupChecksum("SHA256SUMS", "tool.bin", "v1.0.0")
+	if err != nil || got != sum {
+		t.Fatalf("LookupChecksum() = %q, %v, want %q", got, err, sum)
+	}
+
+	if err := downloader.VerifyAgainstChecksumAsset("tool.bin", "SHA256SUMS", "v1.0.0", t.TempDir()); err != nil {
+		t.Errorf("verification of a matching asset failed: %v", err)
+	}
+
+	err = downloader.VerifyAgainstChecksumAsset("other.bin", "SHA256SUMS", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrChecksumMismatch) {
+		t.Errorf("expected ErrChecksumMismatch, got %v", err)
+	}
+
+	if _, err := downloader.LookupChecksum("SHA256SUMS", "missing.bin", "v1.0.0"); err == nil {
+		t.Errorf("LookupChecksum() of an unlisted asset succeeded")
+	}
+}
+
+func TestInvalidProxyScheme(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+	downloader.Proxy = "ftp://proxy.example.com:21"
+
+	err := downloader.DownloadAsset("tool.bin", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrInvalidProxy) || !strings.Contains(err.Error(), `unsupported scheme "ftp"`) {
+		t.Fatalf("expected ErrInvalidProxy, got %v", err)
+	}
+}
+
+func TestNoProxyBypassesProxy(t *testing.T) {
+	var proxied atomic.Int32
+	proxy := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		proxied.Add(1)
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	defer proxy.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = "http://mirror.invalid/repos/owner/repo/releases/latest"
+	downloader.RetryCount = 1
+	downloader.RetryDelay = 0
+	downloader.Proxy = proxy.URL
+
+	if version, err := downloader.LatestVersion(); err != nil || version != "v1.0.0" {
+		t.Fatalf("LatestVersion() through the proxy = %q, %v", version, err)
+	}
+
+	// The host does not resolve, so the request fails when it bypasses the proxy
+	downloader.NoProxy = "example.com,mirror.invalid"
+	if _, err := downloader.LatestVersion(); err == nil {
+		t.Error("LatestVersion() succeeded without the proxy")
+	}
+	if n := proxied.Load(); n != 1 {
+		t.Errorf("proxy received %d requests, want 1", n)
+	}
+}
+
+// newSocksProxy starts a minimal SOCKS5 proxy without authentication that connects every
+// request to backend and records the requested host:port in targets.
+func newSocksProxy(t *testing.T, backend string, targets *sync.Map) net.Listener {
+	ln, err := net.Listen("tcp", "127.0.0.1:0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	t.Cleanup(func() { ln.Close() })
+
+	go func() {
+		for {
+			conn, err := ln.Accept()
+			if err != nil {
+				return
+			}
+			go func() {
+				defer conn.Close()
+				// Greeting: version, number of methods, methods
+				buf := make([]byte, 262)
+				if _, err := io.ReadFull(conn, buf[:2]); err != nil {
+					return
+				}
+				if _, err := io.ReadFull(conn, buf[:buf[1]]); err != nil {
+					return
+				}
+				conn.Write([]byte{5, 0})
+
+				// Request: version, command, reserved, address type, address, port
+				if _, err := io.ReadFull(conn, buf[:4]); err != nil {
+					return
+				}
+				var host string
+				switch buf[3] {
+				case 1:
+					if _, err := io.ReadFull(conn, buf[:4]); err != nil {
+						return
+					}
+					host = net.IP(buf[:4]).String()
+				case 3:
+					if _, err := io.ReadFull(conn, buf[:1]); err != nil {
+						return
+					}
+					n := int(buf[0])
+					if _, err := io.ReadFull(conn, buf[:n]); err != nil {
+						return
+					}
+					host = string(buf[:n])
+				default:
+					return
+				}
+				if _, err := io.ReadFull(conn, buf[:2]); err != nil {
+					return
+				}
+				targets.Store(net.JoinHostPort(host, fmt.Sprint(int(buf[0])<<8|int(buf[1]))), true)
+
+				upstream, err := net.Dial("tcp", backend)
+				if err != nil {
+					conn.Write([]byte{5, 1, 0, 1, 0, 0, 0, 0, 0, 0})
+					return
+				}
+				defer upstream.Close()
+				conn.Write([]byte{5, 0, 0, 1, 0, 0, 0, 0, 0, 0})
+				go io.Copy(upstream, conn)
+				io.Copy(conn, upstream)
+			}()
+		}
+	}()
+	return ln
+}
+
+func TestSocksProxy(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	defer server.Close()
+
+	for _, scheme := range []string{"socks5", "socks5h", "SOCKS5H"} {
+		var targets sync.Map
+		proxy := newSocksProxy(t, server.Listener.Addr().String(), &targets)
+
+		downloader := libfetch.NewDownloader("owner/repo")
+		// The host does not resolve, so the request only succeeds when the proxy resolves it
+		downloader.ApiURL = "http://mirror.invalid/repos/owner/repo/releases/latest"
+		downloader.RetryCount = 1
+		downloader.RetryDelay = 0
+		downloader.Proxy = scheme + "://" + proxy.Addr().String()
+
+		if version, err := downloader.LatestVersion(); err != nil || version != "v1.0.0" {
+			t.Errorf("%s: LatestVersion() through the proxy = %q, %v", scheme, version, err)
+			continue
+		}
+		if _, ok := targets.Load("mirror.invalid:80"); !ok {
+			t.Errorf("%s: proxy was not asked to connect to mirror.invalid:80", scheme)
+		}
+	}
+}
+
+func TestUserAgent(t *testing.T) {
+	var agents sync.Map
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		agents.Store(r.UserAgent(), true)
+		if strings.HasSuffix(r.URL.Path, "/latest") {
+			fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+			return
+		}
+		fmt.Fprint(w, "tool")
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.UserAgent = "corp-tool/1.0"
+
+	if err := downloader.DownloadAsset("tool.bin", "", t.TempDir()); err != nil {
+		t.Fatal(err)
+	}
+	agents.Range(func(agent, _ any) bool {
+		if agent != "corp-tool/1.0" {
+			t.Errorf("request sent with User-Agent %q", agent)
+		}
+		return true
+	})
+}
+
+func TestCustomHeadersAreSent(t *testing.T) {
+	var missing atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("X-Internal-Auth") != "secret" {
+			missing.Add(1)
+		}
+		if strings.HasSuffix(r.URL.Path, "/latest") {
+			fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+			return
+		}
+		fmt.Fprint(w, "tool")
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.Header = http.Header{"X-Internal-Auth": {"secret"}}
+
+	if err := downloader.DownloadAsset("tool.bin", "", t.TempDir()); err != nil {
+		t.Fatal(err)
+	}
+	if n := missing.Load(); n != 0 {
+		t.Errorf("%d requests were sent without the custom header", n)
+	}
+}
+
+func TestTimeoutStopsStalledDownloadsOnly(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Header().Set("Content-Length", "5")
+		for i := range 5 {
+			// The stalled asset stops sending after the first byte
+			if i > 0 && strings.HasSuffix(r.URL.Path, "stalled.bin") {
+				<-r.Context().Done()
+				return
+			}
+			w.Write([]byte{'x'})
+			w.(http.Flusher).Flush()
+			time.Sleep(50 * time.Millisecond)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryCount = 1
+	downloader.Timeout = 200 * time.Millisecond
+
+	if err := downloader.DownloadAsset("steady.bin", "v1.0.0", t.TempDir()); err != nil {
+		t.Errorf("slow but steady download failed: %v", err)
+	}
+
+	err := downloader.DownloadAsset("stalled.bin", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrIdleTimeout) {
+		t.Errorf("expected ErrIdleTimeout, got %v", err)
+	}
+}
+
+func TestProgressHandlerReceivesEvents(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	var mu sync.Mutex
+	var kinds []libfetch.ProgressEventKind
+	handler := func(event libfetch.ProgressEvent) {
+		mu.Lock()
+		defer mu.Unlock()
+		// Periodic progress events depend on timing
+		if event.Kind != libfetch.EventProgress {
+			kinds = append(kinds, event.Kind)
+		}
+	}
+
+	install := newTestInstall(server, t.TempDir())
+	install.Downloader.ProgressTracker = libfetch.NewProgressTracker(handler)
+	install.Downloader.ProgressHandler = handler
+
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	want := []libfetch.ProgressEventKind{
+		libfetch.EventStarted,
+		libfetch.EventCompleted,
+		libfetch.EventExtractStarted,
+		libfetch.EventExtractProgress,
+		libfetch.EventExtractCompleted,
+	}
+	if fmt.Sprint(kinds) != fmt.Sprint(want) {
+		t.Errorf("received events %v, want %v", kinds, want)
+	}
+}
+
+func TestProgressWithUnknownTotal(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		// Flushing before the handler returns sends the body without a Content-Length
+		w.Write([]byte("tool"))
+		w.(http.Flusher).Flush()
+	}))
+	defer server.Close()
+
+	var mu sync.Mutex
+	var events []libfetch.ProgressEvent
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.Resume = true
+	downloader.ProgressTracker = libfetch.NewProgressTracker(func(event libfetch.ProgressEvent) {
+		mu.Lock()
+		defer mu.Unlock()
+		events = append(events, event)
+	})
+
+	if err := downloader.DownloadAsset("tool.bin", "v1.0.0", t.TempDir()); err != nil {
+		t.Fatal(err)
+	}
+
+	if len(events) == 0 {
+		t.Fatal("no progress events received")
+	}
+	for _, event := range events {
+		if event.Total != -1 || event.ETA != -1 {
+			t.Errorf("expected an unknown total and ETA, got %+v", event)
+		}
+	}
+	if last := events[len(events)-1]; last.Kind != libfetch.EventCompleted || last.Downloaded != 4 {
+		t.Errorf("unexpected final event %+v", last)
+	}
+}
+
+func TestDownloadAssetByPatternUsesPinnedVersion(t *testing.T) {
+	var downloaded atomic.Value
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch path := r.URL.Path; {
+		case path == "/repos/owner/repo/releases/latest":
+			fmt.Fprint(w, `{"tag_name": "v2.0.0", "assets": [{"name": "tool-v2.0.0-linux.bin"}]}`)
+		case path == "/repos/owner/repo/releases/tags/v1.0.0":
+			fmt.Fprint(w, `{"tag_name": "v1.0.0", "assets": [{"name": "tool-v1.0.0-darwin.bin"}, {"name": "tool-v1.0.0-linux.bin"}]}`)
+		case strings.HasPrefix(path, "/owner/repo/releases/download/"):
+			downloaded.Store(path)
+			fmt.Fprint(w, "tool")
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+
+	if err := downloader.DownloadAssetByPattern(`linux\.bin$`, "v1.0.0", t.TempDir()); err != nil {
+		t.Fatal(err)
+	}
+	if got, want := downloaded.Load(), "/owner/repo/releases/download/v1.0.0/tool-v1.0.0-linux.bin"; got != want {
+		t.Errorf("downloaded %v, want %s", got, want)
+	}
+}
+
+func TestLatestVersionSkipsDraft(t *testing.T) {
+	var published atomic.Value
+	published.Store(`{"tag_name": "v1.0.0", "published_at": "2024-01-01T00:00:00Z"}`)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/releases/latest":
+			fmt.Fprint(w, `{"tag_name": "v2.0.0", "draft": true}`)
+		case "/repos/owner/repo/releases":
+			fmt.Fprintf(w, `[{"tag_name": "v2.0.0", "draft": true}, %s]`, published.Load())
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.RetryDelay = 0
+
+	if version, err := downloader.LatestVersion(); err != nil || version != "v1.0.0" {
+		t.Errorf("LatestVersion() = %q, %v, want the published v1.0.0", version, err)
+	}
+
+	published.Store(`{"tag_name": "v1.0.0-rc1", "prerelease": true}`)
+	if _, err := downloader.LatestVersion(); !errors.Is(err, libfetch.ErrDraftRelease) {
+		t.Errorf("expected ErrDraftRelease without a published release, got %v", err)
+	}
+}
+
+func TestDownloadMatchingAll(t *testing.T) {
+	var requests atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if strings.HasSuffix(r.URL.Path, "/tags/v1.0.0") {
+			fmt.Fprint(w, `{"tag_name": "v1.0.0", "assets": [{"name": "tool-linux-x64"}, {"name": "tool-linux-x64.sha256"}, {"name": "tool-linux-x64.sig"}, {"name": "tool-darwin-x64"}]}`)
+			return
+		}
+		requests.Add(1)
+		fmt.Fprint(w, path.Base(r.URL.Path))
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+
+	dest := t.TempDir()
+	if _, err := downloader.DownloadMatchingAll([]string{"tool-linux-x64*", "*.asc"}, "v1.0.0", dest); !errors.Is(err, libfetch.ErrAssetNotFound) {
+		t.Fatalf("expected ErrAssetNotFound for a pattern without matches, got %v", err)
+	}
+	if n := requests.Load(); n != 0 {
+		t.Fatalf("downloaded %d assets although a pattern did not match", n)
+	}
+
+	names, err := downloader.DownloadMatchingAll([]string{"tool-linux-x64", "tool-linux-x64.*", "*.sig"}, "v1.0.0", dest)
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := []string{"tool-linux-x64", "tool-linux-x64.sha256", "tool-linux-x64.sig"}
+	if !slices.Equal(names, want) {
+		t.Errorf("downloaded %v, want %v", names, want)
+	}
+	if n := requests.Load(); n != int32(len(want)) {
+		t.Errorf("made %d download requests, want %d", n, len(want))
+	}
+	for _, name := range want {
+		if d, err := os.ReadFile(filepath.Join(dest, name)); err != nil || string(d) != name {
+			t.Errorf("%s = %q, %v", name, d, err)
+		}
+	}
+}
+
+func TestDownloadPrefersBrowserDownloadURL(t *testing.T) {
+	var requested atomic.Value
+	var server *httptest.Server
+	server = httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if strings.HasSuffix(r.URL.Path, "/latest") {
+			fmt.Fprintf(w, `{"tag_name": "v1.0.0", "assets": [{"name": "my tool.bin", "browser_download_url": "%s/assets/my%%20tool.bin"}]}`, server.URL)
+			return
+		}
+		requested.Store(r.URL.EscapedPath())
+		fmt.Fprint(w, "tool")
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+
+	dest := t.TempDir()
+	if err := downloader.DownloadLatestAssetGlob("my *.bin", dest); err != nil {
+		t.Fatal(err)
+	}
+	if got, want := requested.Load(), "/assets/my%20tool.bin"; got != want {
+		t.Errorf("requested %v, want %s", got, want)
+	}
+	if _, err := os.Stat(filepath.Join(dest, "my tool.bin")); err != nil {
+		t.Error(err)
+	}
+
+	// Without the asset list the URL is constructed, escaping the asset name
+	want := server.URL + "/owner/repo/releases/download/v1.0.0/my%20tool.bin"
+	if got := downloader.GetReleaseAssetURLByVersion("my tool.bin", "v1.0.0"); got != want {
+		t.Errorf("GetReleaseAssetURLByVersion() = %s, want %s", got, want)
+	}
+}
+
+func TestDownloadRetriesExpiredRedirect(t *testing.T) {
+	var signed atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Path == "/storage/tool.bin" {
+			// The first signed URL has expired by the time it is requested
+			if r.URL.Query().Get("signature") == "1" {
+				w.WriteHeader(http.StatusForbidden)
+				return
+			}
+			fmt.Fprint(w, "tool")
+			return
+		}
+		http.Redirect(w, r, fmt.Sprintf("/storage/tool.bin?signature=%d", signed.Add(1)), http.StatusFound)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+
+	dest := t.TempDir()
+	if err := downloader.DownloadAsset("tool.bin", "v1.0.0", dest); err != nil {
+		t.Fatal(err)
+	}
+	if n := signed.Load(); n != 2 {
+		t.Errorf("asset URL was resolved %d times, want 2", n)
+	}
+
+	downloader.MaxRedirects = -1
+	var serr *libfetch.DownloadStatusError
+	err := downloader.DownloadAsset("tool.bin", "v1.0.0", t.TempDir())
+	if !errors.As(err, &serr) || serr.StatusCode != http.StatusFound {
+		t.Errorf("expected the redirect to be returned as a status error, got %v", err)
+	}
+}
+
+func TestConnectionsAreReused(t *testing.T) {
+	var connections atomic.Int32
+	server := httptest.NewUnstartedServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	server.Config.ConnState = func(conn net.Conn, state http.ConnState) {
+		if state == http.StateNew {
+			connections.Add(1)
+		}
+	}
+	server.Start()
+	defer server.Close()
+
+	for range 3 {
+		downloader := libfetch.NewDownloader("owner/repo")
+		downloader.ApiURL = server.URL
+		if _, err := downloader.LatestVersion(); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if n := connections.Load(); n != 1 {
+		t.Errorf("opened %d connections, want 1", n)
+	}
+}
+
+func TestLatestVersionUsesETagCache(t *testing.T) {
+	var full, notModified atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("If-None-Match") == `"abc"` {
+			notModified.Add(1)
+			w.WriteHeader(http.StatusNotModified)
+			return
+		}
+		full.Add(1)
+		w.Header().Set("ETag", `"abc"`)
+		fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+	}))
+	defer server.Close()
+
+	cachePath := filepath.Join(t.TempDir(), "etags.json")
+	for range 2 {
+		// A new downloader only shares the cache file
+		downloader := libfetch.NewDownloader("owner/repo")
+		downloader.ApiURL = server.URL
+		downloader.ETagCachePath = cachePath
+		if version, err := downloader.LatestVersion(); err != nil || version != "v1.0.0" {
+			t.Fatalf("LatestVersion() = %q, %v", version, err)
+		}
+	}
+
+	if full.Load() != 1 || notModified.Load() != 1 {
+		t.Errorf("got %d full and %d conditional responses, want 1 each", full.Load(), notModified.Load())
+	}
+}
+
+func TestParallelChunks(t *testing.T) {
+	content := make([]byte, 3<<20)
+	for i := range content {
+		content[i] = byte(i % 251)
+	}
+
+	var ranges atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.Header.Get("Range") != "" {
+			ranges.Add(1)
+		}
+		http.ServeContent(w, r, "tool.bin", time.Time{}, bytes.NewReader(content))
+	}))
+	defer server.Close()
+
+	var mu sync.Mutex
+	var last libfetch.ProgressEvent
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ParallelChunks = 3
+	downloader.ProgressTracker = libfetch.NewProgressTracker(func(event libfetch.ProgressEvent) {
+		mu.Lock()
+		defer mu.Unlock()
+		last = event
+	})
+
+	dest := t.TempDir()
+	if err := downloader.DownloadAsset("tool.bin", "v1.0.0", dest); err != nil {
+		t.Fatal(err)
+	}
+
+	if d, err := os.ReadFile(filepath.Join(dest, "tool.bin")); err != nil || !bytes.Equal(d, content) {
+		t.Fatalf("assembled file differs from the asset: %v", err)
+	}
+	if n := ranges.Load(); n != 3 {
+		t.Errorf("sent %d range requests, want 3", n)
+	}
+	if last.Kind != libfetch.EventCompleted || last.Downloaded != int64(len(content)) || last.Total != int64(len(content)) {
+		t.Errorf("unexpected final progress event %+v", last)
+	}
+}
+
+func TestDownloadAssetOutputName(t *testing.T) {
+	content := make([]byte, 3<<20)
+	for i := range content {
+		content[i] = byte(i % 251)
+	}
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		http.ServeContent(w, r, "tool.bin", time.Time{}, bytes.NewReader(content))
+	}))
+	defer server.Close()
+
+	tests := []struct {
+		name      string
+		configure func(downloader *libfetch.Downloader)
+	}{
+		{"go-getter", func(downloader *libfetch.Downloader) {}},
+		{"resume", func(downloader *libfetch.Downloader) { downloader.Resume = true }},
+		{"chunked", func(downloader *libfetch.Downloader) { downloader.ParallelChunks = 3 }},
+	}
+	for _, tt := range tests {
+		downloader := libfetch.NewDownloader("owner/repo")
+		downloader.DownloadBaseURL = server.URL
+		downloader.ProgressTracker = nil
+		downloader.OutputName = "renamed.bin"
+		tt.configure(downloader)
+
+		dest := t.TempDir()
+		if err := downloader.DownloadAsset("tool.bin", "v1.0.0", dest); err != nil {
+			t.Fatalf("%s: %v", tt.name, err)
+		}
+
+		entries, err := os.ReadDir(dest)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if len(entries) != 1 || entries[0].Name() != "renamed.bin" {
+			t.Errorf("%s: dest contains %v, want only renamed.bin", tt.name, entries)
+			continue
+		}
+		if d, err := os.ReadFile(filepath.Join(dest, "renamed.bin")); err != nil || !bytes.Equal(d, content) {
+			t.Errorf("%s: renamed.bin differs from the asset: %v", tt.name, err)
+		}
+	}
+}
+
+// cannedTransport serves canned responses keyed by host and path, without a network.
+type cannedTransport map[string][]byte
+
+func (c cannedTransport) RoundTrip(req *http.Request) (*http.Response, error) {
+	resp := &http.Response{
+		StatusCode: http.StatusOK,
+		Header:     make(http.Header),
+		Request:    req,
+	}
+	body, ok := c[req.URL.Host+req.URL.Path]
+	if !ok {
+		resp.StatusCode = http.StatusNotFound
+		body = []byte("not found")
+	}
+	if req.Method == http.MethodHead {
+		body = nil
+	}
+	resp.Status = http.StatusText(resp.StatusCode)
+	resp.ContentLength = int64(len(body))
+	resp.Body = io.NopCloser(bytes.NewReader(body))
+	return resp, nil
+}
+
+func TestTransportServesCannedResponses(t *testing.T) {
+	var buf bytes.Buffer
+	zw := zip.NewWriter(&buf)
+	fw, err := zw.Create("bin/tool")
+	if err == nil {
+		_, err = fw.Write([]byte("v1.0.0"))
+	}
+	if err == nil {
+		err = zw.Close()
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+	downloader.Transport = cannedTransport{
+		"api.github.com/repos/owner/repo/releases/latest":                []byte(`{"tag_name": "v1.0.0"}`),
+		"github.com/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip": buf.Bytes(),
+	}
+
+	version, err := downloader.LatestVersion()
+	if err != nil || version != "v1.0.0" {
+		t.Fatalf("LatestVersion() = %q, %v", version, err)
+	}
+
+	dir := t.TempDir()
+	install := libfetch.NewInstall("owner/repo", dir)
+	install.Downloader = downloader
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatal(err)
+	}
+	if data, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(data) != "v1.0.0" {
+		t.Errorf("bin/tool = %q, %v", data, err)
+	}
+
+	if err := downloader.DownloadAsset("missing.zip", "v1.0.0", t.TempDir()); !errors.Is(err, libfetch.ErrAssetNotFound) {
+		t.Errorf("expected ErrAssetNotFound for a missing asset, got %v", err)
+	}
+}
+
+func TestRetryEvents(t *testing.T) {
+	var requests atomic.Int32
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if requests.Add(1) < 3 {
+			http.Error(w, "unavailable", http.StatusServiceUnavailable)
+			return
+		}
+		w.Write([]byte("tool"))
+	}))
+	defer server.Close()
+
+	var mu sync.Mutex
+	var retries []libfetch.ProgressEvent
+	install := libfetch.NewInstall("owner/repo", t.TempDir())
+	install.Downloader.ProgressTracker = nil
+	install.Downloader.RetryDelay = time.Millisecond
+	install.Downloader.ProgressHandler = func(event libfetch.ProgressEvent) {
+		if event.Kind == libfetch.EventRetrying {
+			mu.Lock()
+			retries = append(retries, event)
+			mu.Unlock()
+		}
+	}
+
+	if _, err := install.InstallURL(server.URL+"/tool.bin", "v1.0.0"); err != nil {
+		t.Fatal(err)
+	}
+	if len(retries) != 2 {
+		t.Fatalf("got %d retry events, want 2", len(retries))
+	}
+	for n, event := range retries {
+		if event.Attempt != n+2 || event.MaxAttempts != 3 || event.Delay != time.Millisecond || event.Err == nil {
+			t.Errorf("retry event %d = %+v", n, event)
+		}
+	}
+}
+
+func TestDetectArchiveByMagic(t *testing.T) {
+	var buf bytes.Buffer
+	gw := gzip.NewWriter(&buf)
+	tw := tar.NewWriter(gw)
+	content := []byte("tool")
+	if err := tw.WriteHeader(&tar.Header{Name: "pkg/bin/tool", Mode: 0755, Size: int64(len(content)), Typeflag: tar.TypeReg}); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := tw.Write(content); err != nil {
+		t.Fatal(err)
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	if err := gw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		w.Write(buf.Bytes())
+	}))
+	defer server.Close()
+
+	for _, detect := range []bool{false, true} {
+		dir := t.TempDir()
+		install := libfetch.NewInstall("owner/repo", dir)
+		install.Downloader.ProgressTracker = nil
+		install.Downloader.DetectArchiveByMagic = detect
+		outcome, err := install.InstallURL(server.URL+"/tool.bin", "v1.0.0")
+		if err != nil {
+			t.Fatalf("detect %v: %v", detect, err)
+		}
+
+		want := "tool.bin"
+		if detect {
+			// The tarball is extracted like one with a known extension
+			want = "bin/tool"
+		}
+		if len(outcome.Files) != 1 || outcome.Files[0] != want {
+			t.Errorf("detect %v: installed %v, want [%s]", detect, outcome.Files, want)
+		}
+		if _, err := os.Stat(filepath.Join(dir, filepath.FromSlash(want))); err != nil {
+			t.Errorf("detect %v: %v", detect, err)
+		}
+	}
+}
+
+func TestInsufficientTokenScope(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch {
+		case r.URL.Path == "/repos/owner/repo":
+			fmt.Fprint(w, `{"full_name": "owner/repo", "private": true}`)
+		case r.URL.Path == "/repos/other/repo/releases":
+			fmt.Fprint(w, `[{"tag_name": "v1.0.0"}]`)
+		case r.URL.Path == "/repos/other/repo":
+			fmt.Fprint(w, `{"full_name": "other/repo"}`)
+		default:
+			w.Header().Set("X-Accepted-GitHub-Permissions", "contents=read")
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	newDownloader := func(repo, token string) *libfetch.Downloader {
+		downloader := libfetch.NewDownloader(repo)
+		downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, repo)
+		downloader.DownloadBaseURL = server.URL
+		downloader.ProgressTracker = nil
+		downloader.RetryCount = 1
+		downloader.Token = token
+		return downloader
+	}
+
+	// The token sees the repository but none of its releases
+	_, err := newDownloader("owner/repo", "token").LatestVersion()
+	if !errors.Is(err, libfetch.ErrInsufficientTokenScope) || !strings.Contains(err.Error(), "contents=read") {
+		t.Errorf("expected ErrInsufficientTokenScope, got %v", err)
+	}
+	err = newDownloader("owner/repo", "token").DownloadAsset("tool.zip", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrInsufficientTokenScope) {
+		t.Errorf("expected ErrInsufficientTokenScope for the asset, got %v", err)
+	}
+
+	// Without a token, or with releases that are visible, a 404 is a missing asset
+	err = newDownloader("owner/repo", "").DownloadAsset("tool.zip", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrAssetNotFound) || errors.Is(err, libfetch.ErrInsufficientTokenScope) {
+		t.Errorf("expected ErrAssetNotFound without a token, got %v", err)
+	}
+	err = newDownloader("other/repo", "token").DownloadAsset("tool.zip", "v1.0.0", t.TempDir())
+	if !errors.Is(err, libfetch.ErrAssetNotFound) || errors.Is(err, libfetch.ErrInsufficientTokenScope) {
+		t.Errorf("expected ErrAssetNotFound for a missing asset, got %v", err)
+	}
+}
@@ -0,0 +1,164 @@
+package libfetch
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"strconv"
+	"time"
+)
+
+var (
+	// ErrChecksumMismatch is returned when a downloaded asset does not match its expected checksum.
+	ErrChecksumMismatch = errors.New("checksum mismatch")
+	// ErrAssetNotFound is returned when the requested asset does not exist in the release.
+	ErrAssetNotFound = errors.New("asset not found")
+	// ErrArchive is returned when a downloaded archive cannot be extracted.
+	ErrArchive = errors.New("archive error")
+	// ErrNotInstalled is returned when an operation needs an existing installation but there is none.
+	ErrNotInstalled = errors.New("not installed")
+	// ErrCancelled is returned when a download is stopped because its context was cancelled.
+	// The error also matches the cause of the cancellation, e.g. context.Canceled.
+	ErrCancelled = errors.New("download cancelled")
+	// ErrInvalidProxy is returned when the configured proxy is not a valid URL with a supported scheme.
+	ErrInvalidProxy = errors.New("invalid proxy")
+	// ErrIdleTimeout is returned when a download receives no data for longer than the timeout.
+	ErrIdleTimeout = errors.New("download stalled")
+	// ErrInvalidRepo is returned when a repository is not given in the format "owner/repo".
+	ErrInvalidRepo = errors.New("invalid repo format, expected 'owner/repo'")
+	// ErrFileCollision is returned when two files would be written to the same path.
+	ErrFileCollision = errors.New("file collision")
+	// ErrDeadlineExceeded is returned when an installation does not finish within the deadline set
+	// with Api.SetDeadline. It is returned together with ErrCancelled when a download is stopped.
+	ErrDeadlineExceeded = errors.New("install deadline exceeded")
+	// ErrInsufficientTokenScope is returned instead of a 404 when the configured token can see the
+	// repository but not its releases, e.g. a fine-grained token without the contents permission.
+	ErrInsufficientTokenScope = errors.New("token cannot read repository releases")
+	// ErrSignatureMismatch is returned when a downloaded asset does not match its signature.
+	ErrSignatureMismatch = errors.New("signature mismatch")
+	// ErrDraftRelease is returned when the latest release is a draft and no published release
+	// can be found instead.
+	ErrDraftRelease = errors.New("latest release is a draft")
+	// ErrInsufficientSpace is matched by an *InsufficientSpaceError.
+	ErrInsufficientSpace = errors.New("insufficient disk space")
+	// ErrNoFiles is returned when the assets of an installation contain no files to install, e.g.
+	// because ExtractPattern matches none of their entries.
+	ErrNoFiles = errors.New("no files to install")
+)
+
+// GitHubAPIError is returned when the GitHub API responds with an unexpected status.
+type GitHubAPIError struct {
+	// StatusCode is the HTTP status returned by the API.
+	StatusCode int
+	// Body is the response body returned by the API.
+	Body string
+}
+
+func (e *GitHubAPIError) Error() string {
+	return fmt.Sprintf("received status code %d from GitHub API: %s", e.StatusCode, e.Body)
+}
+
+// InsufficientSpaceError is returned before anything is downloaded when the filesystem of the
+// install path has less free space than the installation is estimated to need, see
+// Install.SpaceFactor. It matches ErrInsufficientSpace.
+type InsufficientSpaceError struct {
+	// Path is the directory the installation was to be written to.
+	Path string
+	// Needed is the estimated number of bytes the installation needs.
+	Needed int64
+	// Available is the number of bytes free on the filesystem.
+	Available int64
+}
+
+func (e *InsufficientSpaceError) Error() string {
+	return fmt.Sprintf("insufficient disk space in %s: %d bytes needed, %d bytes available", e.Path, e.Needed, e.Available)
+}
+
+func (e *InsufficientSpaceError) Unwrap() error {
+	return ErrInsufficientSpace
+}
+
+// DownloadStatusError is returned when an asset download responds with an unexpected status.
+// A 404 response matches ErrAssetNotFound.
+type DownloadStatusError struct {
+	// StatusCode is the HTTP status of the download response.
+	StatusCode int
+	// URL is the URL that was downloaded.
+	URL string
+}
+
+func (e *DownloadStatusError) Error() string {
+	return fmt.Sprintf("received status code %d downloading %s", e.StatusCode, e.URL)
+}
+
+func (e *DownloadStatusError) Unwrap() error {
+	if e.StatusCode == http.StatusNotFound {
+		return ErrAssetNotFound
+	}
+	return nil
+}
+
+// archiveError marks an extraction failure as ErrArchive while keeping the original message and cause.
+type archiveError struct {
+	err error
+}
+
+func (e *archiveError) Error() string {
+	return e.err.Error()
+}
+
+func (e *archiveError) Unwrap() []error {
+	return []error{ErrArchive, e.err}
+}
+
+// maxRateLimitWait is the longest LatestVersion waits for a rate limit reset before giving up.
+const maxRateLimitWait = time.Minute
+
+// RateLimitError is returned when a GitHub API request is rejected because the rate limit is exhausted.
+type RateLimitError struct {
+	// StatusCode is the HTTP status of the rejected request, 403 or 429.
+	StatusCode int
+	// Reset is when the rate limit resets. It is zero if GitHub did not report it.
+	Reset time.Time
+	// Body is the response body returned by GitHub.
+	Body string
+}
+
+func (e *RateLimitError) Error() string {
+	if e.Reset.IsZero() {
+		return fmt.Sprintf("GitHub API rate limit exceeded (status %d)", e.StatusCode)
+	}
+	return fmt.Sprintf("GitHub API rate limit exceeded (status %d), resets at %s", e.StatusCode, e.Reset.Format(time.RFC3339))
+}
+
+// apiError builds the error for a failed GitHub API response.
+func apiError(resp *http.Response) error {
+	body, _ := io.ReadAll(resp.Body)
+	if reset, limited := rateLimitReset(resp); limited {
+		return &RateLimitError{StatusCode: resp.StatusCode, Reset: reset, Body: string(body)}
+	}
+	return &GitHubAPIError{StatusCode: resp.StatusCode, Body: string(body)}
+}
+
+// rateLimitReset reports whether resp was rejected by the GitHub rate limiter and when the limit resets.
+func rateLimitReset(resp *http.Response) (time.Time, bool) {
+	if resp.StatusCode != http.StatusForbidden && resp.StatusCode != http.StatusTooManyRequests {
+		return time.Time{}, false
+	}
+
+	// Secondary rate limits report how long to wait instead of when the limit resets
+	if seconds, err := strconv.Atoi(resp.Header.Get("Retry-After")); err == nil {
+		return time.Now().Add(time.Duration(seconds) * time.Second), true
+	}
+
+	if resp.Header.Get("X-RateLimit-Remaining") != "0" {
+		return time.Time{}, false
+	}
+
+	reset, err := strconv.ParseInt(resp.Header.Get("X-RateLimit-Reset"), 10, 64)
+	if err != nil {
+		return time.Time{}, true
+	}
+	return time.Unix(reset, 0), true
+}
@@ -0,0 +1,80 @@
+package libfetch
+
+import (
+	"encoding/json"
+	"os"
+	"path/filepath"
+	"sync"
+)
+
+// etagEntry is the cached latest release of a repository.
+type etagEntry struct {
+	ETag    string `json:"etag"`
+	TagName string `json:"tag_name"`
+}
+
+// etagEntries caches the latest releases in memory, keyed by API URL.
+var etagEntries sync.Map
+
+// etagFileMu serializes the updates of ETag cache files within the process.
+var etagFileMu sync.Mutex
+
+// cachedLatestVersion returns the cached latest release for ApiURL.
+func (f *Downloader) cachedLatestVersion() (etagEntry, bool) {
+	if f.ETagCachePath == "" {
+		entry, ok := etagEntries.Load(f.ApiURL)
+		if !ok {
+			return etagEntry{}, false
+		}
+		return entry.(etagEntry), true
+	}
+
+	etagFileMu.Lock()
+	defer etagFileMu.Unlock()
+
+	entry, ok := readETagFile(f.ETagCachePath)[f.ApiURL]
+	return entry, ok
+}
+
+// cacheLatestVersion caches entry as the latest release for ApiURL. Failing to write the cache
+// file is not an error, the next lookup simply is not conditional.
+func (f *Downloader) cacheLatestVersion(entry etagEntry) {
+	if f.ETagCachePath == "" {
+		etagEntries.Store(f.ApiURL, entry)
+		return
+	}
+
+	etagFileMu.Lock()
+	defer etagFileMu.Unlock()
+
+	entries := readETagFile(f.ETagCachePath)
+	entries[f.ApiURL] = entry
+	writeETagFile(f.ETagCachePath, entries)
+}
+
+// readETagFile reads the ETag cache file at name, returning an empty cache if it does not exist
+// or cannot be read.
+func readETagFile(name string) map[string]etagEntry {
+	var entries map[string]etagEntry
+	if d, err := os.ReadFile(name); err == nil {
+		json.Unmarshal(d, &entries)
+	}
+	if entries == nil {
+		entries = make(map[string]etagEntry)
+	}
+	return entries
+}
+
+// writeETagFile replaces the ETag cache file at name with entries. The file is written next to
+// it first and renamed into place, so a concurrent reader never sees a partial file.
+func writeETagFile(name string, entries map[string]etagEntry) error {
+	d, err := json.MarshalIndent(entries, "", "  ")
+	if err != nil {
+		return err
+	}
+
+	if err := os.MkdirAll(filepath.Dir(name), 0755); err != nil {
+		return err
+	}
+	return writeFileAtomic(name, d)
+}
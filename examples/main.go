@@ -12,7 +12,7 @@ func main() {
 	api := libfetch.NewApi()
 	api.SetInstallDir("./llamalib")
 	// 下载最新版本
-	err := api.Repo("ggml-org/llama.cpp").Latest().Install(func(version string) string {
+	_, err := api.Repo("ggml-org/llama.cpp").Latest().Install(func(version string) string {
 		return fmt.Sprintf("llama-%s-bin-win-cpu-x64.zip", version)
 	})
 	if err != nil {
@@ -21,7 +21,7 @@ func main() {
 	log.Println("llama.cpp installed successfully")
 
 	// 下载指定版本
-	err = api.Repo("ggml-org/llama.cpp").Version("b7869").Install(func(version string) string {
+	_, err = api.Repo("ggml-org/llama.cpp").Version("b7869").Install(func(version string) string {
 		return fmt.Sprintf("llama-%s-bin-win-cpu-x64.zip", version)
 	})
 	if err != nil {
@@ -33,7 +33,7 @@ func main() {
 	libffiapi := libfetch.NewApi()
 	libffiapi.SetInstallDir("./libffi")
 	// 如果版本不一致，则下载当前版本替换到当前目录，如果一致则不进行下载
-	err = libffiapi.Repo("libffi/libffi").Version("v3.5.2").Install(func(version string) string {
+	_, err = libffiapi.Repo("libffi/libffi").Version("v3.5.2").Install(func(version string) string {
 		cleanVersion := strings.TrimPrefix(version, "v")
 		return fmt.Sprintf("libffi-%s-x86-32bit-msvc-binaries.zip", cleanVersion)
 	})
@@ -54,7 +54,7 @@ func main() {
 	sdApi.SetInstallDir("./sd")
 
 	// 通过 version文件判断版本是否一致， 如果版本不一致，则下载当前版本替换到当前目录，如果一致则不进行下载，下载完成后会进行解压安装，并在目录中留下version文件
-	err = sdApi.Repo("leejet/stable-diffusion.cpp").Latest().Install(func(version string) string {
+	_, err = sdApi.Repo("leejet/stable-diffusion.cpp").Latest().Install(func(version string) string {
 		return fmt.Sprintf("sd-master-%s-bin-win-rocm-x64.zip", version)
 	})
 	if err != nil {
@@ -0,0 +1,623 @@
+package libfetch
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"compress/bzip2"
+	"compress/gzip"
+	"fmt"
+	"io"
+	"maps"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+	"time"
+
+	"github.com/ulikunitz/xz"
+)
+
+// archiveExtractor extracts the archive file at name into dest.
+type archiveExtractor func(name, dest string, opts *extractOptions) error
+
+// Extractor extracts archives of one type, see Downloader.Extractors.
+type Extractor interface {
+	// Extract extracts the archive file at name into dest, which exists. The files the archive
+	// contains must be written below dest.
+	Extract(name, dest string) error
+}
+
+// ExtractorFunc adapts a function to an Extractor.
+type ExtractorFunc func(name, dest string) error
+
+// Extract calls fn(name, dest).
+func (fn ExtractorFunc) Extract(name, dest string) error {
+	return fn(name, dest)
+}
+
+// builtinExtractor is the Extractor of an archive type extracted by libfetch itself. Downloads
+// extract such archives with the StripComponents, ExtractPattern, Flatten and FailOnCollision
+// settings of the Downloader and report the extraction progress.
+type builtinExtractor archiveExtractor
+
+// Extract extracts the archive with the default settings: the top-level directory of tarballs
+// whose entries all lie below one is stripped.
+func (e builtinExtractor) Extract(name, dest string) error {
+	return e(name, dest, &extractOptions{strip: DefaultStripComponents})
+}
+
+// extractOptions controls how archives are extracted.
+type extractOptions struct {
+	// progress receives the extraction events.
+	progress ProgressHandler
+	// strip is the number of leading path components removed from entry names.
+	// Entries with no more components than that are skipped. A negative value strips the top-level
+	// directory of tarballs whose entries all share one and extracts other archives verbatim.
+	strip int
+	// pattern, when not empty, selects the entries that are extracted, see Downloader.ExtractPattern.
+	pattern string
+	// flatten extracts files directly into dest under their base name, see Downloader.Flatten.
+	flatten bool
+	// flattened maps the base names of the files extracted while flattening to their entry names.
+	flattened map[string]string
+	// failOnCollision makes writing a file to a path written before an error instead of an EventCollision.
+	failOnCollision bool
+	// written holds the paths of the files and symlinks extracted so far.
+	written map[string]bool
+	// links maps the names of the tar entries extracted as files to their targets, so hardlinks
+	// to them can be resolved.
+	links map[string]string
+	// files collects the extracted files and symlinks, relative to dest and using forward slashes.
+	files []string
+}
+
+// addFile records target, a path below dest, as extracted.
+func (o *extractOptions) addFile(dest, target string) {
+	if rel, err := filepath.Rel(dest, target); err == nil {
+		o.files = append(o.files, filepath.ToSlash(rel))
+	}
+}
+
+// addLink records that the tar entry name was extracted to the file target.
+func (o *extractOptions) addLink(name, target string) {
+	if o.links == nil {
+		o.links = make(map[string]string)
+	}
+	o.links[path.Clean(name)] = target
+}
+
+// selected reports whether the entry name, after stripping, is extracted. Directory entries are
+// skipped when a pattern is set; the parents of selected files are created as needed.
+func (o *extractOptions) selected(name string, isDir bool) bool {
+	if o.pattern == "" {
+		return true
+	}
+	if isDir {
+		return false
+	}
+	return matchEntry(o.pattern, name)
+}
+
+// entryName returns the name the archive entry name, after stripping, is extracted under, or an
+// empty string when the entry is skipped.
+func (o *extractOptions) entryName(name string, isDir bool) (string, error) {
+	if !o.selected(name, isDir) {
+		return "", nil
+	}
+	if !o.flatten {
+		return name, nil
+	}
+	if isDir {
+		return "", nil
+	}
+
+	base := path.Base(strings.TrimSuffix(name, "/"))
+	if other, ok := o.flattened[base]; ok {
+		return "", fmt.Errorf("%w: archive entries %s and %s would both be extracted as %s", ErrFileCollision, other, name, base)
+	}
+	if o.flattened == nil {
+		o.flattened = make(map[string]string)
+	}
+	o.flattened[base] = name
+	return base, nil
+}
+
+// matchEntry reports whether the archive entry name matches the glob pattern. Patterns without a
+// slash are matched against the base name of the entry, others against the whole name.
+func matchEntry(pattern, name string) bool {
+	name = strings.TrimSuffix(name, "/")
+	if !strings.Contains(pattern, "/") {
+		name = path.Base(name)
+	}
+	matched, _ := path.Match(pattern, name)
+	return matched
+}
+
+// claim records that the archive entry name is extracted to target, a path below dest. A target
+// that was extracted before is a collision: an error if failOnCollision is set, an EventCollision
+// otherwise.
+func (o *extractOptions) claim(dest, target, name string) error {
+	if !o.written[target] {
+		if o.written == nil {
+			o.written = make(map[string]bool)
+		}
+		o.written[target] = true
+		return nil
+	}
+
+	rel, err := filepath.Rel(dest, target)
+	if err != nil {
+		rel = target
+	}
+	rel = filepath.ToSlash(rel)
+	if o.failOnCollision {
+		return fmt.Errorf("%w: archive entry %s overwrites %s", ErrFileCollision, name, rel)
+	}
+	if o.progress != nil {
+		o.progress(ProgressEvent{Kind: EventCollision, EntryName: rel})
+	}
+	return nil
+}
+
+// start reports that extraction of an archive with entriesTotal entries (0 if unknown) begins.
+func (o *extractOptions) start(entriesTotal int) {
+	if o.progress != nil {
+		o.progress(ProgressEvent{Kind: EventExtractStarted, EntriesTotal: entriesTotal})
+	}
+}
+
+// report forwards extraction progress to the progress handler, if any.
+func (o *extractOptions) report(entriesDone, entriesTotal int, entryName string, complete bool) {
+	if o.progress == nil {
+		return
+	}
+
+	kind := EventExtractProgress
+	if complete {
+		kind = EventExtractCompleted
+	}
+	o.progress(ProgressEvent{Kind: kind, EntriesDone: entriesDone, EntriesTotal: entriesTotal, EntryName: entryName})
+}
+
+// hasTopLevelDir reports whether all entries of the tar stream r lie below the same top-level
+// directory, like the "tool-1.2.3/" directory most tarballs wrap their contents in. Tarballs with
+// files at their root do not.
+func hasTopLevelDir(r io.Reader) (bool, error) {
+	tr := tar.NewReader(r)
+	var top string
+	nested := false
+	for {
+		header, err := tr.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return false, fmt.Errorf("failed to read tar header: %w", err)
+		}
+		if header.Typeflag == tar.TypeXGlobalHeader {
+			continue
+		}
+
+		first, rest, found := strings.Cut(header.Name, "/")
+		if !found && header.Typeflag != tar.TypeDir {
+			// A file at the root
+			return false, nil
+		}
+		if top == "" {
+			top = first
+		} else if first != top {
+			return false, nil
+		}
+		nested = nested || rest != ""
+	}
+	return top != "" && nested, nil
+}
+
+// stripComponents removes the first n slash separated components from the archive entry name.
+// It returns an empty string when name has no more than n components.
+func stripComponents(name string, n int) string {
+	for range n {
+		idx := strings.Index(name, "/")
+		if idx == -1 {
+			return ""
+		}
+		name = name[idx+1:]
+	}
+	return name
+}
+
+// tarDecompressor wraps a compressed tarball stream in a reader for the raw tar data.
+type tarDecompressor func(r io.Reader) (io.Reader, error)
+
+// archiveExtractors maps the archive extensions extracted by libfetch itself to their extractors.
+// Other archive types are left to go-getter, unless an extractor is registered for them.
+var archiveExtractors = map[string]Extractor{
+	".zip":     builtinExtractor(extractZip),
+	".tar.gz":  builtinExtractor(tarExtractor(decompressGzip)),
+	".tgz":     builtinExtractor(tarExtractor(decompressGzip)),
+	".tar.xz":  builtinExtractor(tarExtractor(decompressXz)),
+	".txz":     builtinExtractor(tarExtractor(decompressXz)),
+	".tar.bz2": builtinExtractor(tarExtractor(decompressBzip2)),
+	".tbz2":    builtinExtractor(tarExtractor(decompressBzip2)),
+}
+
+// BuiltinExtractors returns the extractors of the archive types libfetch extracts itself, keyed
+// by extension, e.g. ".tar.gz".
+func BuiltinExtractors() map[string]Extractor {
+	return maps.Clone(archiveExtractors)
+}
+
+func decompressGzip(r io.Reader) (io.Reader, error) {
+	return gzip.NewReader(r)
+}
+
+func decompressXz(r io.Reader) (io.Reader, error) {
+	return xz.NewReader(r)
+}
+
+func decompressBzip2(r io.Reader) (io.Reader, error) {
+	return bzip2.NewReader(r), nil
+}
+
+// archiveExtension returns the archive extension of src in lower case, or an empty string if
+// src is not an archive extracted by libfetch itself. Extensions are matched case-insensitively.
+func archiveExtension(src string) string {
+	name := strings.ToLower(urlPath(src))
+
+	for ext := range archiveExtractors {
+		if strings.HasSuffix(name, ext) {
+			return ext
+		}
+	}
+	return ""
+}
+
+// urlPath returns the path of the URL src, or src itself if it is not a valid URL.
+func urlPath(src string) string {
+	if u, err := url.Parse(src); err == nil {
+		return u.Path
+	}
+	return src
+}
+
+// safeJoin joins the archive entry name onto dest, rejecting names that would escape dest.
+func safeJoin(dest, name string) (string, error) {
+	target := filepath.Join(dest, name)
+	if !withinDir(dest, target) {
+		return "", fmt.Errorf("archive entry escapes destination: %s", name)
+	}
+	return target, nil
+}
+
+// safeEntry joins the archive entry name onto dest like safeJoin and also rejects entries that
+// would be written through a symlink to a location outside of dest, such as a symlink extracted
+// before. The symlinks among the existing parents of the entry are resolved, and the entry itself
+// unless it is a symlink, which is replaced rather than written through.
+func safeEntry(dest, name string, symlink bool) (string, error) {
+	target, err := safeJoin(dest, name)
+	if err != nil {
+		return "", err
+	}
+
+	root, err := resolvePath(dest)
+	if err != nil {
+		return "", fmt.Errorf("failed to resolve destination: %w", err)
+	}
+	resolve := target
+	if symlink {
+		resolve = filepath.Dir(target)
+	}
+	resolved, err := resolvePath(resolve)
+	if err != nil || !withinDir(root, resolved) {
+		return "", fmt.Errorf("archive entry escapes destination: %s", name)
+	}
+	return target, nil
+}
+
+// resolvePath returns name with all symlinks resolved. The parts of name that do not exist yet
+// are kept as they are, they cannot be symlinks. A dangling symlink is an error.
+func resolvePath(name string) (string, error) {
+	name, err := filepath.Abs(name)
+	if err != nil {
+		return "", err
+	}
+
+	var rest []string
+	for {
+		resolved, err := filepath.EvalSymlinks(name)
+		if err == nil {
+			return filepath.Join(append([]string{resolved}, rest...)...), nil
+		}
+		if !os.IsNotExist(err) {
+			return "", err
+		}
+		if _, lerr := os.Lstat(name); lerr == nil {
+			// name exists, so a symlink on its path points nowhere
+			return "", err
+		}
+		parent := filepath.Dir(name)
+		if parent == name {
+			return "", err
+		}
+		rest = append([]string{filepath.Base(name)}, rest...)
+		name = parent
+	}
+}
+
+// withinDir reports whether target is dir itself or lies below it.
+func withinDir(dir, target string) bool {
+	rel, err := filepath.Rel(dir, target)
+	if err != nil {
+		return false
+	}
+	return rel != ".." && !strings.HasPrefix(rel, ".."+string(filepath.Separator))
+}
+
+// extractZip extracts the zip archive at name into dest.
+func extractZip(name, dest string, opts *extractOptions) error {
+	zr, err := zip.OpenReader(name)
+	if err != nil {
+		return fmt.Errorf("failed to open zip archive: %w", err)
+	}
+	defer zr.Close()
+
+	total := len(zr.File)
+	opts.start(total)
+	for i, file := range zr.File {
+		// Skip entries that are stripped completely or not selected
+		if name := stripComponents(file.Name, opts.strip); name != "" {
+			name, err := opts.entryName(name, file.FileInfo().IsDir())
+			if err != nil {
+				return err
+			}
+			if name != "" {
+				if err := extractZipEntry(file, name, dest, opts); err != nil {
+					return err
+				}
+			}
+		}
+		opts.report(i+1, total, file.Name, false)
+	}
+
+	opts.report(total, total, "", true)
+	return nil
+}
+
+// extractZipEntry extracts a single zip entry into dest under the given name.
+func extractZipEntry(file *zip.File, name, dest string, opts *extractOptions) error {
+	target, err := safeEntry(dest, name, false)
+	if err != nil {
+		return err
+	}
+
+	if file.FileInfo().IsDir() {
+		if err := os.MkdirAll(target, 0755); err != nil {
+			return fmt.Errorf("failed to create directory: %w", err)
+		}
+		return nil
+	}
+
+	if err := opts.claim(dest, target, file.Name); err != nil {
+		return err
+	}
+
+	// Ensure parent directory exists
+	if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+		return fmt.Errorf("failed to create parent directory: %w", err)
+	}
+
+	if err := writeZipFile(file, target); err != nil {
+		return err
+	}
+	if err := setModTime(target, file.Modified); err != nil {
+		return err
+	}
+	opts.addFile(dest, target)
+	return nil
+}
+
+// linkFile creates target as a hardlink to the file source, replacing an existing file. Where
+// hardlinks cannot be created, e.g. on file systems that do not support them, source is copied.
+func linkFile(source, target string) error {
+	if err := os.Remove(target); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	if err := os.Link(source, target); err == nil {
+		return nil
+	}
+	return copyFile(source, target)
+}
+
+// setModTime sets the modification and access time of the extracted file target to mtime, the
+// modification time recorded in the archive, so extracted trees have stable timestamps.
+// Nothing is changed when the archive does not record a time.
+func setModTime(target string, mtime time.Time) error {
+	if mtime.IsZero() {
+		return nil
+	}
+	if err := os.Chtimes(target, mtime, mtime); err != nil {
+		return fmt.Errorf("failed to set modification time: %w", err)
+	}
+	return nil
+}
+
+// writeZipFile writes the contents of the zip entry file to target.
+func writeZipFile(file *zip.File, target string) error {
+	rc, err := file.Open()
+	if err != nil {
+		return fmt.Errorf("failed to open zip entry: %w", err)
+	}
+	defer rc.Close()
+
+	mode := file.Mode().Perm()
+	if mode == 0 {
+		mode = 0644
+	}
+	mode = extractedFileMode(target, mode)
+
+	f, err := os.OpenFile(target, os.O_CREATE|os.O_RDWR|os.O_TRUNC, mode)
+	if err != nil {
+		return fmt.Errorf("failed to create file: %w", err)
+	}
+
+	if _, err := io.Copy(f, rc); err != nil {
+		f.Close()
+		return fmt.Errorf("failed to write file: %w", err)
+	}
+	return f.Close()
+}
+
+// tarExtractor returns an archiveExtractor for tarballs compressed with decompress.
+func tarExtractor(decompress tarDecompressor) archiveExtractor {
+	return func(name, dest string, opts *extractOptions) error {
+		file, err := os.Open(name)
+		if err != nil {
+			return fmt.Errorf("failed to open downloaded file: %w", err)
+		}
+		defer file.Close()
+
+		r, err := decompress(file)
+		if err != nil {
+			return fmt.Errorf("failed to create decompressor: %w", err)
+		}
+
+		if opts.strip < 0 {
+			// Look at the entries first, then extract them from the start
+			topLevelDir, err := hasTopLevelDir(r)
+			if err != nil {
+				return err
+			}
+			opts.strip = 0
+			if topLevelDir {
+				opts.strip = 1
+			}
+
+			if _, err := file.Seek(0, io.SeekStart); err != nil {
+				return fmt.Errorf("failed to rewind downloaded file: %w", err)
+			}
+			if r, err = decompress(file); err != nil {
+				return fmt.Errorf("failed to create decompressor: %w", err)
+			}
+		}
+
+		return extractTar(r, dest, opts)
+	}
+}
+
+// extractTar extracts the tar stream r into dest, stripping opts.strip leading path components.
+// The number of entries is not known up front, so progress reports a total of 0 until completion.
+// PAX and GNU long names are resolved by the tar reader, so entry names are complete before
+// they are stripped.
+func extractTar(r io.Reader, dest string, opts *extractOptions) error {
+	tr := tar.NewReader(r)
+	done := 0
+	opts.start(0)
+
+	// Extract files
+	for {
+		header, err := tr.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return fmt.Errorf("failed to read tar header: %w", err)
+		}
+
+		// Strip leading directories (e.g., "llama-b1234/")
+		name := stripComponents(header.Name, opts.strip)
+
+		done++
+
+		// Skip entries that are stripped completely (e.g., the top-level directory itself)
+		if name == "" {
+			continue
+		}
+		name, err = opts.entryName(name, header.Typeflag == tar.TypeDir)
+		if err != nil {
+			return err
+		}
+		if name == "" {
+			opts.report(done, 0, header.Name, false)
+			continue
+		}
+
+		target, err := safeEntry(dest, name, header.Typeflag == tar.TypeSymlink)
+		if err != nil {
+			return err
+		}
+
+		switch header.Typeflag {
+		case tar.TypeDir:
+			if err := os.MkdirAll(target, os.FileMode(header.Mode)); err != nil {
+				return fmt.Errorf("failed to create directory: %w", err)
+			}
+		case tar.TypeReg:
+			if err := opts.claim(dest, target, header.Name); err != nil {
+				return err
+			}
+
+			// Ensure parent directory exists
+			if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+				return fmt.Errorf("failed to create parent directory: %w", err)
+			}
+
+			// Create the file
+			f, err := os.OpenFile(target, os.O_CREATE|os.O_RDWR|os.O_TRUNC, extractedFileMode(target, os.FileMode(header.Mode)))
+			if err != nil {
+				return fmt.Errorf("failed to create file: %w", err)
+			}
+
+			// Copy contents
+			if _, err := io.Copy(f, tr); err != nil {
+				f.Close()
+				return fmt.Errorf("failed to write file: %w", err)
+			}
+			f.Close()
+			if err := setModTime(target, header.ModTime); err != nil {
+				return err
+			}
+			opts.addLink(header.Name, target)
+			opts.addFile(dest, target)
+		case tar.TypeLink:
+			// Hardlinks name an earlier entry of the archive, as it is stored rather than extracted
+			source, ok := opts.links[path.Clean(header.Linkname)]
+			if !ok {
+				return fmt.Errorf("archive entry %s links to %s, which was not extracted", header.Name, header.Linkname)
+			}
+			if err := opts.claim(dest, target, header.Name); err != nil {
+				return err
+			}
+			if source != target {
+				if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+					return fmt.Errorf("failed to create parent directory: %w", err)
+				}
+				if err := linkFile(source, target); err != nil {
+					return fmt.Errorf("failed to create hardlink: %w", err)
+				}
+			}
+			opts.addLink(header.Name, target)
+			opts.addFile(dest, target)
+		case tar.TypeSymlink:
+			// Handle symlinks, refusing links that point outside of dest
+			if filepath.IsAbs(header.Linkname) || !withinDir(dest, filepath.Join(filepath.Dir(target), header.Linkname)) {
+				return fmt.Errorf("archive entry escapes destination: %s", header.Name)
+			}
+			if err := opts.claim(dest, target, header.Name); err != nil {
+				return err
+			}
+			if err := os.Symlink(header.Linkname, target); err != nil {
+				// Ignore error if symlink already exists
+				if !os.IsExist(err) {
+					return fmt.Errorf("failed to create symlink: %w", err)
+				}
+			}
+			opts.addFile(dest, target)
+		}
+
+		opts.report(done, 0, header.Name, false)
+	}
+
+	opts.report(done, done, "", true)
+	return nil
+}
@@ -0,0 +1,642 @@
+package libfetch
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"bytes"
+	"compress/gzip"
+	"encoding/base64"
+	"errors"
+	"os"
+	"path/filepath"
+	"runtime"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/ulikunitz/xz"
+)
+
+func TestExtractZipRejectsPathTraversal(t *testing.T) {
+	root := t.TempDir()
+	dest := filepath.Join(root, "dest")
+
+	archive := filepath.Join(root, "evil.zip")
+	out, err := os.Create(archive)
+	if err != nil {
+		t.Fatal(err)
+	}
+	zw := zip.NewWriter(out)
+	w, err := zw.Create("../evil")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := w.Write([]byte("evil")); err != nil {
+		t.Fatal(err)
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	out.Close()
+
+	err = extractZip(archive, dest, &extractOptions{})
+	if err == nil || !strings.Contains(err.Error(), "archive entry escapes destination") {
+		t.Fatalf("expected path traversal error, got %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(root, "evil")); !os.IsNotExist(err) {
+		t.Fatalf("file was written outside of dest")
+	}
+}
+
+func TestExtractTarRejectsPathTraversal(t *testing.T) {
+	root := t.TempDir()
+	dest := filepath.Join(root, "dest")
+
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	content := []byte("evil")
+	// The top-level directory is stripped, leaving "../evil"
+	if err := tw.WriteHeader(&tar.Header{Name: "pkg/../evil", Mode: 0644, Size: int64(len(content)), Typeflag: tar.TypeReg}); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := tw.Write(content); err != nil {
+		t.Fatal(err)
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	err := extractTar(&buf, dest, &extractOptions{strip: 1})
+	if err == nil || !strings.Contains(err.Error(), "archive entry escapes destination") {
+		t.Fatalf("expected path traversal error, got %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(root, "evil")); !os.IsNotExist(err) {
+		t.Fatalf("file was written outside of dest")
+	}
+}
+
+func TestExtractTarRejectsWritesThroughSymlinks(t *testing.T) {
+	if runtime.GOOS == "windows" {
+		t.Skip("creating symlinks requires privileges on Windows")
+	}
+
+	content := []byte("evil")
+	for _, outside := range []bool{false, true} {
+		root := t.TempDir()
+		dest := filepath.Join(root, "dest")
+		if err := os.Mkdir(dest, 0755); err != nil {
+			t.Fatal(err)
+		}
+		if outside {
+			// The chained links resolve to an existing directory next to dest
+			if err := os.Mkdir(filepath.Join(root, "evil"), 0755); err != nil {
+				t.Fatal(err)
+			}
+		}
+
+		var buf bytes.Buffer
+		tw := tar.NewWriter(&buf)
+		headers := []*tar.Header{
+			{Name: "a", Linkname: ".", Typeflag: tar.TypeSymlink},
+			// Within dest as a string, but a resolves to dest itself
+			{Name: "a/l", Linkname: "../evil", Typeflag: tar.TypeSymlink},
+			{Name: "a/l/pwn", Mode: 0644, Size: int64(len(content)), Typeflag: tar.TypeReg},
+		}
+		for _, header := range headers {
+			if err := tw.WriteHeader(header); err != nil {
+				t.Fatal(err)
+			}
+		}
+		if _, err := tw.Write(content); err != nil {
+			t.Fatal(err)
+		}
+		if err := tw.Close(); err != nil {
+			t.Fatal(err)
+		}
+
+		err := extractTar(&buf, dest, &extractOptions{})
+		if err == nil || !strings.Contains(err.Error(), "archive entry escapes destination") {
+			t.Errorf("outside dir exists %v: expected path traversal error, got %v", outside, err)
+		}
+		if _, err := os.Stat(filepath.Join(root, "evil", "pwn")); !os.IsNotExist(err) {
+			t.Errorf("outside dir exists %v: file was written outside of dest", outside)
+		}
+	}
+}
+
+// stripTests lists the files extracted from an archive containing pkg/bin/tool and pkg/README
+// for different numbers of stripped components.
+var stripTests = []struct {
+	strip int
+	want  []string
+}{
+	{0, []string{"pkg/bin/tool", "pkg/README"}},
+	{1, []string{"bin/tool", "README"}},
+	{2, []string{"tool"}},
+}
+
+var stripEntries = []string{"pkg/bin/tool", "pkg/README"}
+
+func TestExtractZipStripComponents(t *testing.T) {
+	root := t.TempDir()
+	archive := filepath.Join(root, "pkg.zip")
+	out, err := os.Create(archive)
+	if err != nil {
+		t.Fatal(err)
+	}
+	zw := zip.NewWriter(out)
+	for _, name := range stripEntries {
+		w, err := zw.Create(name)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if _, err := w.Write([]byte(name)); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	out.Close()
+
+	for _, tt := range stripTests {
+		dest := t.TempDir()
+		opts := &extractOptions{strip: tt.strip}
+		if err := extractZip(archive, dest, opts); err != nil {
+			t.Fatalf("strip %d: %v", tt.strip, err)
+		}
+		checkExtracted(t, tt.strip, dest, opts.files, tt.want)
+	}
+}
+
+func TestExtractTarStripComponents(t *testing.T) {
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	for _, name := range stripEntries {
+		if err := tw.WriteHeader(&tar.Header{Name: name, Mode: 0644, Size: int64(len(name)), Typeflag: tar.TypeReg}); err != nil {
+			t.Fatal(err)
+		}
+		if _, err := tw.Write([]byte(name)); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	for _, tt := range stripTests {
+		dest := t.TempDir()
+		opts := &extractOptions{strip: tt.strip}
+		if err := extractTar(bytes.NewReader(buf.Bytes()), dest, opts); err != nil {
+			t.Fatalf("strip %d: %v", tt.strip, err)
+		}
+		checkExtracted(t, tt.strip, dest, opts.files, tt.want)
+	}
+}
+
+// checkExtracted verifies that exactly the files in want were extracted into dest.
+func checkExtracted(t *testing.T, strip int, dest string, files, want []string) {
+	t.Helper()
+	if strings.Join(files, ",") != strings.Join(want, ",") {
+		t.Errorf("strip %d: extracted %v, want %v", strip, files, want)
+	}
+	for _, name := range want {
+		if _, err := os.Stat(filepath.Join(dest, filepath.FromSlash(name))); err != nil {
+			t.Errorf("strip %d: %v", strip, err)
+		}
+	}
+}
+
+func TestExtractPattern(t *testing.T) {
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	if err := tw.WriteHeader(&tar.Header{Name: "pkg/bin/", Mode: 0755, Typeflag: tar.TypeDir}); err != nil {
+		t.Fatal(err)
+	}
+	for _, name := range stripEntries {
+		if err := tw.WriteHeader(&tar.Header{Name: name, Mode: 0644, Size: int64(len(name)), Typeflag: tar.TypeReg}); err != nil {
+			t.Fatal(err)
+		}
+		if _, err := tw.Write([]byte(name)); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	tests := []struct {
+		pattern string
+		want    []string
+	}{
+		{"tool", []string{"bin/tool"}},
+		{"bin/*", []string{"bin/tool"}},
+		{"*", []string{"bin/tool", "README"}},
+		{"*.exe", nil},
+	}
+	for _, tt := range tests {
+		dest := t.TempDir()
+		opts := &extractOptions{strip: 1, pattern: tt.pattern}
+		if err := extractTar(bytes.NewReader(buf.Bytes()), dest, opts); err != nil {
+			t.Fatalf("pattern %q: %v", tt.pattern, err)
+		}
+		if strings.Join(opts.files, ",") != strings.Join(tt.want, ",") {
+			t.Errorf("pattern %q: extracted %v, want %v", tt.pattern, opts.files, tt.want)
+		}
+		if len(tt.want) == 0 {
+			if entries, _ := os.ReadDir(dest); len(entries) != 0 {
+				t.Errorf("pattern %q: dest is not empty", tt.pattern)
+			}
+		}
+	}
+}
+
+func TestExtractFlatten(t *testing.T) {
+	root := t.TempDir()
+	archive := filepath.Join(root, "pkg.zip")
+	writeZip(t, archive, "pkg/bin/", "pkg/bin/tool", "pkg/README")
+
+	dest := t.TempDir()
+	opts := &extractOptions{flatten: true}
+	if err := extractZip(archive, dest, opts); err != nil {
+		t.Fatal(err)
+	}
+	checkExtracted(t, 0, dest, opts.files, []string{"tool", "README"})
+	if _, err := os.Stat(filepath.Join(dest, "pkg")); !os.IsNotExist(err) {
+		t.Errorf("directory structure was not discarded")
+	}
+
+	// Files with the same base name are not overwritten
+	archive = filepath.Join(root, "collision.zip")
+	writeZip(t, archive, "linux/tool", "windows/tool")
+	err := extractZip(archive, t.TempDir(), &extractOptions{flatten: true})
+	if err == nil || !strings.Contains(err.Error(), "would both be extracted as tool") {
+		t.Fatalf("expected name collision error, got %v", err)
+	}
+}
+
+// writeZip writes a zip archive with the given entries to name. Entries ending in a slash are
+// directories, the content of files is their name.
+func writeZip(t *testing.T, name string, entries ...string) {
+	t.Helper()
+	out, err := os.Create(name)
+	if err != nil {
+		t.Fatal(err)
+	}
+	defer out.Close()
+
+	zw := zip.NewWriter(out)
+	for _, entry := range entries {
+		w, err := zw.Create(entry)
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !strings.HasSuffix(entry, "/") {
+			if _, err := w.Write([]byte(entry)); err != nil {
+				t.Fatal(err)
+			}
+		}
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+}
+
+func TestExtractCollisions(t *testing.T) {
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	for _, content := range []string{"first", "second"} {
+		if err := tw.WriteHeader(&tar.Header{Name: "pkg/bin/tool", Mode: 0644, Size: int64(len(content)), Typeflag: tar.TypeReg}); err != nil {
+			t.Fatal(err)
+		}
+		if _, err := tw.Write([]byte(content)); err != nil {
+			t.Fatal(err)
+		}
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	var collisions []string
+	opts := &extractOptions{strip: 1, progress: func(event ProgressEvent) {
+		if event.Kind == EventCollision {
+			collisions = append(collisions, event.EntryName)
+		}
+	}}
+	if err := extractTar(bytes.NewReader(buf.Bytes()), t.TempDir(), opts); err != nil {
+		t.Fatal(err)
+	}
+	if len(collisions) != 1 || collisions[0] != "bin/tool" {
+		t.Errorf("reported collisions %v, want [bin/tool]", collisions)
+	}
+
+	opts = &extractOptions{strip: 1, failOnCollision: true}
+	err := extractTar(bytes.NewReader(buf.Bytes()), t.TempDir(), opts)
+	if !errors.Is(err, ErrFileCollision) || !strings.Contains(err.Error(), "bin/tool") {
+		t.Errorf("expected a collision error naming bin/tool, got %v", err)
+	}
+}
+
+func TestExtractTarHardlinksAndLongNames(t *testing.T) {
+	longDir := "pkg/" + strings.Repeat("d", 120)
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	content := []byte("tool")
+	headers := []*tar.Header{
+		{Name: "pkg/bin/tool", Mode: 0755, Size: int64(len(content)), Typeflag: tar.TypeReg},
+		{Name: "pkg/bin/tool-link", Linkname: "pkg/bin/tool", Mode: 0755, Typeflag: tar.TypeLink},
+		{Name: longDir + "/gnu", Mode: 0644, Size: int64(len(content)), Typeflag: tar.TypeReg, Format: tar.FormatGNU},
+		{Name: longDir + "/pax", Linkname: "./pkg/bin/tool", Mode: 0644, Typeflag: tar.TypeLink, Format: tar.FormatPAX},
+	}
+	for _, header := range headers {
+		if err := tw.WriteHeader(header); err != nil {
+			t.Fatal(err)
+		}
+		if header.Typeflag == tar.TypeReg {
+			if _, err := tw.Write(content); err != nil {
+				t.Fatal(err)
+			}
+		}
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	dest := t.TempDir()
+	opts := &extractOptions{strip: 1}
+	if err := extractTar(&buf, dest, opts); err != nil {
+		t.Fatal(err)
+	}
+	long := strings.TrimPrefix(longDir, "pkg/")
+	want := []string{"bin/tool", "bin/tool-link", long + "/gnu", long + "/pax"}
+	checkExtracted(t, 1, dest, opts.files, want)
+
+	for _, name := range []string{"bin/tool-link", long + "/pax"} {
+		data, err := os.ReadFile(filepath.Join(dest, filepath.FromSlash(name)))
+		if err != nil || string(data) != "tool" {
+			t.Errorf("%s = %q, %v, want the content of bin/tool", name, data, err)
+		}
+	}
+}
+
+func TestExtractTarRejectsDanglingHardlink(t *testing.T) {
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	if err := tw.WriteHeader(&tar.Header{Name: "bin/tool", Linkname: "../../etc/passwd", Mode: 0644, Typeflag: tar.TypeLink}); err != nil {
+		t.Fatal(err)
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	err := extractTar(&buf, t.TempDir(), &extractOptions{})
+	if err == nil || !strings.Contains(err.Error(), "which was not extracted") {
+		t.Fatalf("expected dangling hardlink error, got %v", err)
+	}
+}
+
+func TestExtractTarDefaultStrip(t *testing.T) {
+	tests := []struct {
+		entries []string
+		want    []string
+	}{
+		{[]string{"pkg/bin/tool", "pkg/README"}, []string{"bin/tool", "README"}},
+		{[]string{"bin/tool", "README"}, []string{"bin/tool", "README"}},
+		{[]string{"tool"}, []string{"tool"}},
+		{[]string{"a/tool", "b/tool"}, []string{"a/tool", "b/tool"}},
+	}
+	for _, tt := range tests {
+		var buf bytes.Buffer
+		gw := gzip.NewWriter(&buf)
+		tw := tar.NewWriter(gw)
+		for _, name := range tt.entries {
+			if err := tw.WriteHeader(&tar.Header{Name: name, Mode: 0644, Size: int64(len(name)), Typeflag: tar.TypeReg}); err != nil {
+				t.Fatal(err)
+			}
+			if _, err := tw.Write([]byte(name)); err != nil {
+				t.Fatal(err)
+			}
+		}
+		if err := tw.Close(); err != nil {
+			t.Fatal(err)
+		}
+		if err := gw.Close(); err != nil {
+			t.Fatal(err)
+		}
+
+		archive := filepath.Join(t.TempDir(), "pkg.tar.gz")
+		if err := os.WriteFile(archive, buf.Bytes(), 0644); err != nil {
+			t.Fatal(err)
+		}
+		dest := t.TempDir()
+		opts := &extractOptions{strip: DefaultStripComponents}
+		if err := archiveExtractors[".tar.gz"].(builtinExtractor)(archive, dest, opts); err != nil {
+			t.Fatalf("%v: %v", tt.entries, err)
+		}
+		if strings.Join(opts.files, ",") != strings.Join(tt.want, ",") {
+			t.Errorf("%v: extracted %v, want %v", tt.entries, opts.files, tt.want)
+		}
+	}
+}
+
+// bzip2TarTool is pkg/bin/tool (content "tool") as a bzip2 compressed tarball.
+// compress/bzip2 cannot write, so the archive is embedded.
+const bzip2TarTool = "QlpoOTFBWSZTWVj3pzwAAHP7gMmAAAFAAO6ABABwrd4ACAggAFQyoxGjENGTJ6EEkU0aaDQAAfbSHQgehCEW9TiTslFAhgY14nAUGEZuRFQ76fmtht5WiIEyGgql774xAkgfi7kinChILHvTngA="
+
+func TestExtractCompressedTar(t *testing.T) {
+	var plain bytes.Buffer
+	tw := tar.NewWriter(&plain)
+	if err := tw.WriteHeader(&tar.Header{Name: "pkg/bin/tool", Mode: 0755, Size: 4, Typeflag: tar.TypeReg}); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := tw.Write([]byte("tool")); err != nil {
+		t.Fatal(err)
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	var gz bytes.Buffer
+	gw := gzip.NewWriter(&gz)
+	if _, err := gw.Write(plain.Bytes()); err != nil {
+		t.Fatal(err)
+	}
+	if err := gw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	var xzBuf bytes.Buffer
+	xw, err := xz.NewWriter(&xzBuf)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := xw.Write(plain.Bytes()); err != nil {
+		t.Fatal(err)
+	}
+	if err := xw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	bz, err := base64.StdEncoding.DecodeString(bzip2TarTool)
+	if err != nil {
+		t.Fatal(err)
+	}
+
+	tests := []struct {
+		ext  string
+		data []byte
+	}{
+		{".tar.gz", gz.Bytes()},
+		{".tgz", gz.Bytes()},
+		{".tar.xz", xzBuf.Bytes()},
+		{".txz", xzBuf.Bytes()},
+		{".tar.bz2", bz},
+		{".tbz2", bz},
+	}
+	for _, tt := range tests {
+		archive := filepath.Join(t.TempDir(), "pkg"+tt.ext)
+		if err := os.WriteFile(archive, tt.data, 0644); err != nil {
+			t.Fatal(err)
+		}
+		dest := t.TempDir()
+		opts := &extractOptions{strip: DefaultStripComponents}
+		if err := archiveExtractors[tt.ext].(builtinExtractor)(archive, dest, opts); err != nil {
+			t.Fatalf("%s: %v", tt.ext, err)
+		}
+		if strings.Join(opts.files, ",") != "bin/tool" {
+			t.Errorf("%s: extracted %v, want [bin/tool]", tt.ext, opts.files)
+		}
+		got, err := os.ReadFile(filepath.Join(dest, "bin", "tool"))
+		if err != nil || string(got) != "tool" {
+			t.Errorf("%s: bin/tool = %q, %v", tt.ext, got, err)
+		}
+	}
+}
+
+func TestArchiveExtension(t *testing.T) {
+	tests := []struct {
+		src  string
+		want string
+	}{
+		{"https://example.com/tool-1.2.3.tar.gz", ".tar.gz"},
+		{"https://example.com/tool-1.2.3.tgz", ".tgz"},
+		{"https://example.com/TOOL-1.2.3.TAR.GZ", ".tar.gz"},
+		{"https://example.com/tool.ZIP?checksum=sha256:abc", ".zip"},
+		{"https://example.com/tool.exe", ""},
+	}
+
+	for _, tt := range tests {
+		if got := archiveExtension(tt.src); got != tt.want {
+			t.Errorf("archiveExtension(%q) = %q, want %q", tt.src, got, tt.want)
+		}
+	}
+}
+
+func TestRegisteredExtractor(t *testing.T) {
+	copyArchive := ExtractorFunc(func(name, dest string) error {
+		data, err := os.ReadFile(name)
+		if err != nil {
+			return err
+		}
+		return os.WriteFile(filepath.Join(dest, "tool"), data, 0644)
+	})
+	f := &Downloader{Extractors: map[string]Extractor{".TAR.ZST": copyArchive, ".gz": copyArchive}}
+
+	tests := []struct {
+		src  string
+		want string
+	}{
+		{"https://example.com/tool.tar.zst", ".tar.zst"},
+		{"https://example.com/tool.gz", ".gz"},
+		// The longer built-in extension wins
+		{"https://example.com/tool.tar.gz", ".tar.gz"},
+		{"https://example.com/tool.zip", ".zip"},
+	}
+	for _, tt := range tests {
+		if got := f.archiveExtension(tt.src); got != tt.want {
+			t.Errorf("archiveExtension(%q) = %q, want %q", tt.src, got, tt.want)
+		}
+	}
+
+	archive := filepath.Join(t.TempDir(), "tool.tar.zst")
+	if err := os.WriteFile(archive, []byte("tool"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	dest := t.TempDir()
+	files, err := f.extractArchive(archive, dest, ".tar.zst")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(files) != 1 || files[0] != "tool" {
+		t.Errorf("extracted %v, want [tool]", files)
+	}
+	if data, err := os.ReadFile(filepath.Join(dest, "tool")); err != nil || string(data) != "tool" {
+		t.Errorf("tool = %q, %v", data, err)
+	}
+}
+
+func TestExtractPreservesModTimes(t *testing.T) {
+	mtime := time.Date(2020, 1, 2, 3, 4, 6, 0, time.UTC)
+	root := t.TempDir()
+
+	archive := filepath.Join(root, "pkg.zip")
+	out, err := os.Create(archive)
+	if err != nil {
+		t.Fatal(err)
+	}
+	zw := zip.NewWriter(out)
+	w, err := zw.CreateHeader(&zip.FileHeader{Name: "tool", Method: zip.Deflate, Modified: mtime})
+	if err != nil {
+		t.Fatal(err)
+	}
+	if _, err := w.Write([]byte("tool")); err != nil {
+		t.Fatal(err)
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	out.Close()
+
+	var buf bytes.Buffer
+	tw := tar.NewWriter(&buf)
+	if err := tw.WriteHeader(&tar.Header{Name: "tool", Mode: 0644, Size: 4, ModTime: mtime, Typeflag: tar.TypeReg}); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := tw.Write([]byte("tool")); err != nil {
+		t.Fatal(err)
+	}
+	if err := tw.Close(); err != nil {
+		t.Fatal(err)
+	}
+
+	zipDest, tarDest := filepath.Join(root, "zip"), filepath.Join(root, "tar")
+	if err := extractZip(archive, zipDest, &extractOptions{}); err != nil {
+		t.Fatal(err)
+	}
+	if err := extractTar(&buf, tarDest, &extractOptions{}); err != nil {
+		t.Fatal(err)
+	}
+
+	for _, dest := range []string{zipDest, tarDest} {
+		fi, err := os.Stat(filepath.Join(dest, "tool"))
+		if err != nil {
+			t.Fatal(err)
+		}
+		if !fi.ModTime().Equal(mtime) {
+			t.Errorf("%s: modification time %v, want %v", dest, fi.ModTime(), mtime)
+		}
+	}
+}
+
+func TestExtractedFileMode(t *testing.T) {
+	if got := extractedFileMode("bin/tool", 0755); got != 0755 {
+		t.Errorf("extractedFileMode(bin/tool, 0755) = %v", got)
+	}
+
+	want := os.FileMode(0444)
+	if runtime.GOOS == "windows" {
+		// Executables stay writable so upgrades can replace them
+		want = 0644
+	}
+	if got := extractedFileMode("bin/tool.EXE", 0444); got != want {
+		t.Errorf("extractedFileMode(bin/tool.EXE, 0444) = %v, want %v", got, want)
+	}
+}
@@ -0,0 +1,11 @@
+//go:build !windows
+
+package libfetch
+
+import "os"
+
+// extractedFileMode returns the mode the archive entry name is extracted with: the permissions
+// recorded in the archive.
+func extractedFileMode(name string, mode os.FileMode) os.FileMode {
+	return mode
+}
@@ -0,0 +1,20 @@
+//go:build windows
+
+package libfetch
+
+import (
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// extractedFileMode returns the mode the archive entry name is extracted with. Windows only keeps
+// the read-only attribute of a mode, which is taken from the archive, except that executables and
+// libraries are always writable so an upgrade can replace them.
+func extractedFileMode(name string, mode os.FileMode) os.FileMode {
+	switch strings.ToLower(filepath.Ext(name)) {
+	case ".exe", ".dll":
+		return mode | 0200
+	}
+	return mode
+}
@@ -1,16 +1,181 @@
 package libfetch
 
 import (
+	"context"
 	"encoding/json"
+	"errors"
 	"fmt"
+	"net/url"
 	"os"
+	"path"
 	"path/filepath"
+	"slices"
+	"strings"
 	"time"
+
+	goversion "github.com/hashicorp/go-version"
+	"golang.org/x/sync/errgroup"
 )
 
 type VersionInfo struct {
-	TagName string `json:"tag_name"`
-	Repo    string `json:"repo"`
+	TagName   string `json:"tag_name"`
+	Repo      string `json:"repo"`
+	AssetName string `json:"asset_name,omitempty"`
+	// AssetNames lists all installed assets when more than one asset was installed.
+	AssetNames []string `json:"asset_names,omitempty"`
+	// Files lists the installed files relative to the install path, using forward slashes. It is
+	// nil for installations that predate file tracking, whose version file has no file list.
+	Files []string `json:"files"`
+	// Checksums maps the installed regular files in Files to their hex encoded SHA-256 checksums.
+	Checksums map[string]string `json:"checksums,omitempty"`
+	// InstalledAt is when the version was installed. It is zero for installations that predate it.
+	InstalledAt time.Time `json:"installed_at,omitzero"`
+	// SourceURL is the URL the asset, the first one if there are several, was downloaded from.
+	SourceURL string `json:"source_url,omitempty"`
+	// AssetSHA256 maps the downloaded assets to the hex encoded SHA-256 of their data.
+	AssetSHA256 map[string]string `json:"asset_sha256,omitempty"`
+}
+
+// Assets returns the names of the installed assets.
+func (v *VersionInfo) Assets() []string {
+	if len(v.AssetNames) > 0 {
+		return v.AssetNames
+	}
+	if v.AssetName != "" {
+		return []string{v.AssetName}
+	}
+	return nil
+}
+
+// UpgradeAvailable reports whether latest is newer than the installed tag.
+// Tags are compared as semantic versions (a leading "v" is ignored); if either tag is not a
+// valid semantic version, any difference between the tags counts as an upgrade.
+func (v *VersionInfo) UpgradeAvailable(latest string) bool {
+	installedVersion, err := goversion.NewSemver(strings.TrimPrefix(v.TagName, "v"))
+	if err != nil {
+		return latest != v.TagName
+	}
+	latestVersion, err := goversion.NewSemver(strings.TrimPrefix(latest, "v"))
+	if err != nil {
+		return latest != v.TagName
+	}
+
+	return latestVersion.GreaterThan(installedVersion)
+}
+
+// InstallAction describes what an installation call did.
+type InstallAction int
+
+const (
+	// InstallActionNone means the requested version was already installed and nothing was downloaded.
+	InstallActionNone InstallAction = iota
+	// InstallActionFresh means the asset was installed where nothing was installed before.
+	InstallActionFresh
+	// InstallActionUpgrade means an existing installation was upgraded to a newer version.
+	InstallActionUpgrade
+	// InstallActionReinstall means the installed version was downloaded again and replaced, see Install.Force.
+	InstallActionReinstall
+	// InstallActionDowngrade means an existing installation was replaced with the older version requested.
+	InstallActionDowngrade
+)
+
+func (a InstallAction) String() string {
+	switch a {
+	case InstallActionFresh:
+		return "fresh"
+	case InstallActionUpgrade:
+		return "upgrade"
+	case InstallActionReinstall:
+		return "reinstall"
+	case InstallActionDowngrade:
+		return "downgrade"
+	default:
+		return "none"
+	}
+}
+
+// InstallOutcome reports the result of an installation.
+type InstallOutcome struct {
+	// Version is the release version that is installed.
+	Version string
+	// InstallPath is the directory the asset was installed into.
+	InstallPath string
+	// AssetName is the name of the installed asset, the first one if several were installed.
+	AssetName string
+	// AssetNames are the names of all installed assets.
+	AssetNames []string
+	// Action is what the installation did, or would do for a dry run.
+	Action InstallAction
+	// DryRun reports that nothing was downloaded or written.
+	DryRun bool
+	// URL is the download URL of the asset, the first one if there are several.
+	// It is only set for dry runs.
+	URL string
+	// Size is the total size of the assets in bytes as reported by the server, -1 if unknown.
+	// It is only set for dry runs.
+	Size int64
+	// Files are the installed files relative to InstallPath, using forward slashes.
+	// It is not set for dry runs or when nothing was installed.
+	Files []string
+	// AssetSHA256 maps the downloaded assets to the hex encoded SHA-256 of their data, computed
+	// while they were downloaded. It is not set for dry runs or when nothing was installed.
+	AssetSHA256 map[string]string
+}
+
+// record sets the version, asset names and files of the outcome to those of installed.
+func (o *InstallOutcome) record(installed *VersionInfo) {
+	o.Version = installed.TagName
+	o.AssetNames = installed.Assets()
+	o.AssetName = o.AssetNames[0]
+	o.Files = installed.Files
+	o.AssetSHA256 = installed.AssetSHA256
+}
+
+// InstallContext describes an installation that is about to download its assets.
+type InstallContext struct {
+	// Repo is the GitHub repository in format "owner/repo".
+	Repo string
+	// Version is the release version that will be installed.
+	Version string
+	// InstallPath is the directory the assets will be installed into.
+	InstallPath string
+	// AssetNames are the names of the assets that will be installed.
+	AssetNames []string
+	// Action is what the installation will do.
+	Action InstallAction
+}
+
+// UpdateState is the outcome of an update check.
+type UpdateState int
+
+const (
+	// UpdateNotInstalled means nothing is installed.
+	UpdateNotInstalled UpdateState = iota
+	// UpdateUpToDate means the installed version is the latest one.
+	UpdateUpToDate
+	// UpdateAvailable means a newer version than the installed one is available.
+	UpdateAvailable
+)
+
+func (s UpdateState) String() string {
+	switch s {
+	case UpdateUpToDate:
+		return "up to date"
+	case UpdateAvailable:
+		return "update available"
+	default:
+		return "not installed"
+	}
+}
+
+// UpdateStatus reports whether a newer version than the installed one is available.
+type UpdateStatus struct {
+	// State is the outcome of the check.
+	State UpdateState
+	// Current is the installed version, empty when nothing is installed.
+	Current string
+	// Latest is the latest available version, empty when nothing is installed.
+	Latest string
 }
 
 // Install struct holds common variables for installation operations
@@ -19,6 +184,59 @@ type Install struct {
 	repo        string
 	InstallPath string
 	Downloader  *Downloader
+	// ResolveLatest resolves the latest version when checking for upgrades.
+	// The Downloader's latest release is used when it is nil.
+	ResolveLatest func() (string, error)
+	// AssetFunc generates the asset name for a version when upgrading.
+	// When it is nil the asset name recorded in the version file is used.
+	AssetFunc func(version string) string
+	// AssetsFunc generates the asset names for a version when upgrading an installation of
+	// several assets. It takes precedence over AssetFunc.
+	AssetsFunc func(version string) []string
+	// ResolveAssets resolves the asset names for a version when upgrading like AssetsFunc, but may
+	// fail, e.g. when it inspects the assets of the release. It takes precedence over AssetsFunc.
+	ResolveAssets func(version string) ([]string, error)
+	// AssetDir returns the directory, relative to the install path and using forward slashes, an
+	// asset is downloaded and extracted into, so independently packaged assets do not collide.
+	// Assets are installed into the install path itself when it is nil or returns an empty string.
+	AssetDir func(assetName string) string
+	// ChecksumAssetFunc generates the name of the release asset listing the SHA-256 checksums of
+	// the other assets (e.g. "checksums.txt") for a version. When it is set every asset is
+	// verified against its listed checksum before it is extracted.
+	ChecksumAssetFunc func(version string) string
+	// SignatureAssetFunc generates the name of the release asset holding the minisign signature of
+	// an asset (e.g. "<asset>.minisig") for a version. When it is set every asset is verified
+	// against its signature and PublicKey before it is extracted, see Downloader.VerifySignature.
+	SignatureAssetFunc func(version, assetName string) string
+	// PublicKey is the minisign public key assets are verified with when SignatureAssetFunc is set.
+	PublicKey string
+	// MaxConcurrency is the maximum number of assets downloaded at the same time.
+	// Values below 1 download one asset at a time.
+	MaxConcurrency int
+	// Context cancels the downloads of the installation. context.Background() is used when it is nil.
+	Context context.Context
+	// PreInstall is called before the assets of an installation, upgrade or reinstall are
+	// downloaded. Returning an error aborts the installation before anything is changed.
+	PreInstall func(ctx *InstallContext) error
+	// PostInstall is called after an installation, upgrade or reinstall once the version file is
+	// written. It is not called when nothing was installed. Its error is returned by the install call.
+	PostInstall func(outcome *InstallOutcome) error
+	// Force replaces an existing installation with a fresh download of the requested version,
+	// even if that version is already installed. Unlike an upgrade it does not move to a newer version.
+	Force bool
+	// KeepVersions is the number of previous versions retained in the ".versions" directory of the
+	// install path when the installation is replaced, so they can be restored with Rollback.
+	// Previous versions are removed when it is 0.
+	KeepVersions int
+	// VersionedLayout installs every version into a subdirectory of the install path named after
+	// its tag, e.g. "<install path>/v1.2.3", with its own version file. Installed versions are kept
+	// side by side: upgrading installs the latest version next to them, and GetInstalledVersion,
+	// CheckUpdate and Uninstall operate on the newest installed version.
+	VersionedLayout bool
+	// SpaceFactor is the multiple of an archive's size its download and extraction are assumed to
+	// need. Before the assets are downloaded the install path must have at least this much free
+	// space, plus the size of assets that are not archives. The check is skipped when it is 0.
+	SpaceFactor float64
 }
 
 // NewInstall creates a new Install instance with default values
@@ -28,29 +246,155 @@ func NewInstall(repo string, installPath string) *Install {
 		versionFile: "version.json",
 		InstallPath: installPath,
 		Downloader:  NewDownloaderWithConfig(repo, 3, 3*time.Second, "", DefaultProgressTracker()),
+		SpaceFactor: DefaultSpaceFactor,
 	}
 }
 
-func (i *Install) InstallAsset(assetName string, version string, allowUpgrade bool) error {
+// InstallAsset installs assetName from the given release version (empty for latest) and
+// reports what was done. When allowUpgrade is set an existing installation is upgraded if a
+// newer version is available.
+func (i *Install) InstallAsset(assetName string, version string, allowUpgrade bool) (*InstallOutcome, error) {
+	return i.InstallAssets([]string{assetName}, version, allowUpgrade)
+}
+
+// InstallAssets installs all of assetNames from the given release version (empty for latest)
+// into the install path and records them in a single version file. When a version is given and
+// another version is installed, the installation is switched to the given version.
+func (i *Install) InstallAssets(assetNames []string, version string, allowUpgrade bool) (*InstallOutcome, error) {
+	outcome, err := i.installAssets(assetNames, version, allowUpgrade)
+	if err != nil {
+		return nil, err
+	}
+	i.logOutcome(outcome)
+	if err := i.postInstall(outcome); err != nil {
+		return nil, err
+	}
+	return outcome, nil
+}
+
+// installAssets installs assetNames like InstallAssets, without running the post-install hook.
+func (i *Install) installAssets(assetNames []string, version string, allowUpgrade bool) (*InstallOutcome, error) {
+	if len(assetNames) == 0 {
+		return nil, errors.New("no assets to install")
+	}
+	if i.VersionedLayout {
+		return i.installVersioned(assetNames, version, allowUpgrade)
+	}
+
+	outcome := &InstallOutcome{
+		Version:     version,
+		InstallPath: i.InstallPath,
+		AssetName:   assetNames[0],
+		AssetNames:  assetNames,
+		Action:      InstallActionNone,
+	}
+
 	// Check if already installed
 	if i.alreadyInstalled() {
+		if i.Force {
+			installed, err := i.reinstallAssets(assetNames, version, InstallActionReinstall)
+			if err != nil {
+				return nil, err
+			}
+			outcome.record(installed)
+			outcome.Action = InstallActionReinstall
+			return outcome, nil
+		}
+
 		if !allowUpgrade {
-			return nil
+			installed, err := i.GetInstalledVersion()
+			if err != nil {
+				return nil, fmt.Errorf("error checking version: %w", err)
+			}
+			outcome.Version = installed.TagName
+
+			// A pinned version replaces any other installed version
+			if version != "" && installed.TagName != version {
+				outcome.Action = switchAction(installed, version)
+				installed, err = i.reinstallAssets(assetNames, version, outcome.Action)
+				if err != nil {
+					return nil, err
+				}
+				outcome.record(installed)
+			}
+			return outcome, nil
 		}
 
 		isLatest, versionInfo, err := i.isLatestVersion()
 		if err != nil {
-			return fmt.Errorf("error checking version: %w", err)
+			return nil, fmt.Errorf("error checking version: %w", err)
 		}
 
 		if isLatest {
-			return nil
+			outcome.Version = versionInfo.TagName
+			return outcome, nil
+		}
+
+		upgraded, err := i.upgradeAsset(versionInfo, assetNames)
+		if err != nil {
+			return nil, err
 		}
+		outcome.record(upgraded)
+		outcome.Action = InstallActionUpgrade
+		return outcome, nil
+	}
 
-		return i.upgradeAsset(versionInfo)
+	installed, err := i.initialInstallAsset(assetNames, version)
+	if err != nil {
+		return nil, err
 	}
+	outcome.record(installed)
+	outcome.Action = InstallActionFresh
+	return outcome, nil
+}
 
-	return i.initialInstallAsset(assetName, version)
+// SetVersionFile sets the name of the version info file in the install path, "version.json" by
+// default. Tools installed into the same directory need different version files.
+func (i *Install) SetVersionFile(name string) error {
+	if name == "" || name == "." || name == ".." || strings.ContainsAny(name, `/\`) {
+		return fmt.Errorf("invalid version file name %q: must be a plain file name", name)
+	}
+	i.versionFile = name
+	return nil
+}
+
+// preInstall runs the pre-install hook, if any, for an installation of assetNames from version.
+func (i *Install) preInstall(version string, assetNames []string, action InstallAction) error {
+	if i.PreInstall == nil {
+		return nil
+	}
+
+	err := i.PreInstall(&InstallContext{
+		Repo:        i.repo,
+		Version:     version,
+		InstallPath: i.InstallPath,
+		AssetNames:  assetNames,
+		Action:      action,
+	})
+	if err != nil {
+		return fmt.Errorf("pre-install hook: %w", err)
+	}
+	return nil
+}
+
+// postInstall runs the post-install hook, if any, unless nothing was installed.
+func (i *Install) postInstall(outcome *InstallOutcome) error {
+	if i.PostInstall == nil || outcome.Action == InstallActionNone {
+		return nil
+	}
+
+	if err := i.PostInstall(outcome); err != nil {
+		return fmt.Errorf("post-install hook: %w", err)
+	}
+	return nil
+}
+
+// context returns the context downloads of the installation run with.
+func (i *Install) context() context.Context {
+	if i.Context != nil {
+		return i.Context
+	}
+	return context.Background()
 }
 
 func (i *Install) alreadyInstalled() bool {
@@ -81,94 +425,563 @@ func (i *Install) isLatestVersion() (bool, *VersionInfo, error) {
 		return false, nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
 	}
 
-	latestVersion, err := i.Downloader.LatestVersion()
+	latestVersion, err := i.latestVersion()
 	if err != nil {
 		return false, nil, fmt.Errorf("error getting latest version: %w", err)
 	}
 
-	return latestVersion == versionInfo.TagName, &versionInfo, nil
+	return !versionInfo.UpgradeAvailable(latestVersion), &versionInfo, nil
 }
 
-func (i *Install) initialInstallAsset(assetName string, version string) error {
-	// Download the asset
-	if err := i.Downloader.DownloadAsset(assetName, version, i.InstallPath); err != nil {
-		return fmt.Errorf("error downloading asset: %w", err)
+// latestVersion resolves the version an installation should be upgraded to.
+func (i *Install) latestVersion() (string, error) {
+	if i.ResolveLatest != nil {
+		return i.ResolveLatest()
 	}
-	var innerVersion string
+	return i.Downloader.LatestVersion()
+}
 
+func (i *Install) initialInstallAsset(assetNames []string, version string) (*VersionInfo, error) {
 	if len(version) == 0 {
-		var err error
-		// Get latest version and create version file
-		innerVersion, err = i.Downloader.LatestVersion()
+		// Get latest version for the hooks and the version file
+		latestVersion, err := i.latestVersion()
 		if err != nil {
-			return fmt.Errorf("error getting latest version: %w", err)
+			return nil, fmt.Errorf("error getting latest version: %w", err)
 		}
-	} else {
-		innerVersion = version
+		version = latestVersion
 	}
 
-	return i.createVersionFile(innerVersion)
+	if err := i.preInstall(version, assetNames, InstallActionFresh); err != nil {
+		return nil, err
+	}
+
+	// Download the assets
+	downloaded, err := i.downloadAssets(assetNames, version, i.InstallPath)
+	if err != nil {
+		return nil, err
+	}
+
+	return i.createVersionFile(version, i.Downloader.GetReleaseAssetURLByVersion(downloaded.names[0], version), downloaded)
 }
 
-func (i *Install) upgradeAsset(versionInfo *VersionInfo) error {
-	// Clean up existing installation
-	if _, err := os.Stat(i.InstallPath); !os.IsNotExist(err) {
-		os.RemoveAll(i.InstallPath)
+// switchAction returns the action of replacing the installation described by installed with version.
+func switchAction(installed *VersionInfo, version string) InstallAction {
+	if installed.UpgradeAvailable(version) {
+		return InstallActionUpgrade
+	}
+	return InstallActionDowngrade
+}
+
+// reinstallAssets replaces the current installation with a fresh download of assetNames from
+// the given release version (empty for latest) and returns the new installation. action is
+// reported to the pre-install hook.
+func (i *Install) reinstallAssets(assetNames []string, version string, action InstallAction) (*VersionInfo, error) {
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if versionInfo.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+
+	if version == "" {
+		version, err = i.latestVersion()
+		if err != nil {
+			return nil, fmt.Errorf("error getting latest version: %w", err)
+		}
 	}
 
-	// Download the latest version
-	if err := i.Downloader.DownloadAsset(versionInfo.TagName, "", i.InstallPath); err != nil {
-		return fmt.Errorf("error downloading asset: %w", err)
+	if err := i.preInstall(version, assetNames, action); err != nil {
+		return nil, err
 	}
 
-	// Get latest version and update version file
-	version, err := i.Downloader.LatestVersion()
+	var downloaded *downloadedAssets
+	_, err = i.replaceInstallation(versionInfo, func(dest string) ([]string, error) {
+		downloaded, err = i.downloadAssets(assetNames, version, dest)
+		if err != nil {
+			return nil, err
+		}
+		return downloaded.files, nil
+	})
 	if err != nil {
-		return fmt.Errorf("error getting latest version: %w", err)
+		return nil, err
 	}
 
-	return i.createVersionFile(version)
+	return i.createVersionFile(version, i.Downloader.GetReleaseAssetURLByVersion(downloaded.names[0], version), downloaded)
 }
 
-// CreateVersionFile creates a version info file in the specified directory.
-func (i *Install) CreateVersionFile(version string) error {
-	// Ensure the directory exists
-	if err := os.MkdirAll(i.InstallPath, 0755); err != nil {
-		return fmt.Errorf("error creating install directory: %w", err)
+// downloadedAssets describes the assets downloaded by downloadAssets.
+type downloadedAssets struct {
+	// names are the names of the downloaded assets. They differ from the requested names in case
+	// only when Downloader.CaseInsensitiveAssets is set.
+	names []string
+	// files are the files the assets contain, relative to the destination using forward slashes.
+	files []string
+	// sha256 maps the names to the hex encoded SHA-256 of the downloaded data.
+	sha256 map[string]string
+}
+
+// downloadAssets downloads and extracts the assets of version into dest. Up to MaxConcurrency
+// assets are downloaded at the same time.
+func (i *Install) downloadAssets(assetNames []string, version string, dest string) (*downloadedAssets, error) {
+	checksum := i.Downloader.ExpectedSHA256
+	if checksum != "" && len(assetNames) > 1 {
+		return nil, errors.New("an expected SHA-256 can only be verified for a single asset")
 	}
 
-	versionInfoPath := filepath.Join(i.InstallPath, i.versionFile)
+	assetURLs := make([]string, 0, len(assetNames))
+	for _, assetName := range assetNames {
+		if assetURL, err := i.Downloader.assetURL(assetName, version); err == nil {
+			assetURLs = append(assetURLs, assetURL)
+		}
+	}
+	if err := i.checkDiskSpace(dest, assetURLs); err != nil {
+		return nil, err
+	}
+
+	if i.SignatureAssetFunc != nil {
+		if _, err := parseMinisignPublicKey(i.PublicKey); err != nil {
+			return nil, err
+		}
+	}
+
+	names := slices.Clone(assetNames)
+	assetFiles := make([][]string, len(assetNames))
+	digests := make([]*digest, len(assetNames))
+	g, ctx := errgroup.WithContext(i.context())
+	g.SetLimit(max(i.MaxConcurrency, 1))
+	for n, assetName := range assetNames {
+		g.Go(func() error {
+			checksum := checksum
+			if i.ChecksumAssetFunc != nil {
+				var err error
+				checksum, err = i.Downloader.lookupChecksum(ctx, i.ChecksumAssetFunc(version), assetName, version)
+				if err != nil {
+					return err
+				}
+			}
+
+			assetDest, subdir, err := i.assetDest(dest, assetName)
+			if err != nil {
+				return err
+			}
+
+			actx, d := withDigest(ctx)
+			name, files := assetName, []string(nil)
+			if i.SignatureAssetFunc != nil {
+				files, err = i.Downloader.downloadSigned(actx, assetName, version, assetDest, checksum, i.SignatureAssetFunc(version, assetName), i.PublicKey)
+			} else {
+				name, files, err = i.Downloader.downloadNamedAsset(actx, assetName, version, assetDest, checksum)
+			}
+			if err != nil {
+				return fmt.Errorf("error downloading asset %s: %w", assetName, err)
+			}
+			// Record the files relative to dest
+			for k, file := range files {
+				files[k] = path.Join(subdir, file)
+			}
+			names[n] = name
+			assetFiles[n] = files
+			digests[n] = d
+			return nil
+		})
+	}
+	if err := g.Wait(); err != nil {
+		return nil, err
+	}
+
+	// Assets may contain the same files
+	files := slices.Concat(assetFiles...)
+	slices.Sort(files)
+	for k := 1; k < len(files); k++ {
+		if files[k] != files[k-1] {
+			continue
+		}
+		if i.Downloader.FailOnCollision {
+			return nil, fmt.Errorf("%w: %s is installed by more than one asset", ErrFileCollision, files[k])
+		}
+		if i.Downloader.ProgressHandler != nil {
+			i.Downloader.ProgressHandler(ProgressEvent{Kind: EventCollision, EntryName: files[k], Dest: dest})
+		}
+	}
+
+	if len(files) == 0 {
+		return nil, fmt.Errorf("%w in %s", ErrNoFiles, strings.Join(names, ", "))
+	}
+
+	return &downloadedAssets{
+		names:  names,
+		files:  slices.Compact(files),
+		sha256: digestSums(names, digests),
+	}, nil
+}
+
+// digestSums maps names to the known checksums of the corresponding digests.
+func digestSums(names []string, digests []*digest) map[string]string {
+	var sums map[string]string
+	for n, d := range digests {
+		sum := d.checksum()
+		if sum == "" {
+			continue
+		}
+		if sums == nil {
+			sums = make(map[string]string, len(names))
+		}
+		sums[names[n]] = sum
+	}
+	return sums
+}
+
+// assetDest returns the directory below dest assetName is installed into as chosen by AssetDir,
+// together with its path relative to dest using forward slashes.
+func (i *Install) assetDest(dest, assetName string) (string, string, error) {
+	if i.AssetDir == nil {
+		return dest, ".", nil
+	}
+	subdir := i.AssetDir(assetName)
+	if subdir == "" {
+		return dest, ".", nil
+	}
+	if filepath.IsAbs(filepath.FromSlash(subdir)) {
+		return "", "", fmt.Errorf("directory of asset %s is not relative: %s", assetName, subdir)
+	}
+
+	assetDest, err := safeJoin(dest, filepath.FromSlash(subdir))
+	if err != nil {
+		return "", "", fmt.Errorf("directory of asset %s escapes the install path: %s", assetName, subdir)
+	}
+	rel, err := filepath.Rel(dest, assetDest)
+	if err != nil {
+		return "", "", err
+	}
+	return assetDest, filepath.ToSlash(rel), nil
+}
+
+// upgradeAsset replaces the installation described by versionInfo with the latest version.
+// The assets to download are taken from ResolveAssets, then AssetsFunc, then AssetFunc, then
+// assetNames, then the assets recorded at install time.
+func (i *Install) upgradeAsset(versionInfo *VersionInfo, assetNames []string) (*VersionInfo, error) {
+	// Get latest version
+	version, err := i.latestVersion()
+	if err != nil {
+		return nil, fmt.Errorf("error getting latest version: %w", err)
+	}
+
+	assetNames, err = i.upgradeAssetNames(versionInfo, assetNames, version)
+	if err != nil {
+		return nil, err
+	}
+
+	if err := i.preInstall(version, assetNames, InstallActionUpgrade); err != nil {
+		return nil, err
+	}
+
+	var downloaded *downloadedAssets
+	_, err = i.replaceInstallation(versionInfo, func(dest string) ([]string, error) {
+		downloaded, err = i.downloadAssets(assetNames, version, dest)
+		if err != nil {
+			return nil, err
+		}
+		return downloaded.files, nil
+	})
+	if err != nil {
+		return nil, err
+	}
+
+	// Update version file
+	return i.createVersionFile(version, i.Downloader.GetReleaseAssetURLByVersion(downloaded.names[0], version), downloaded)
+}
+
+// upgradeAssetNames returns the assets an upgrade of the installation described by versionInfo to
+// version installs: those generated for version, or assetNames or the recorded assets otherwise.
+func (i *Install) upgradeAssetNames(versionInfo *VersionInfo, assetNames []string, version string) ([]string, error) {
+	switch {
+	case i.ResolveAssets != nil:
+		var err error
+		assetNames, err = i.ResolveAssets(version)
+		if err != nil {
+			return nil, fmt.Errorf("error resolving assets: %w", err)
+		}
+	case i.AssetsFunc != nil:
+		assetNames = i.AssetsFunc(version)
+	case i.AssetFunc != nil:
+		assetNames = []string{i.AssetFunc(version)}
+	case len(assetNames) == 0 || assetNames[0] == "":
+		assetNames = versionInfo.Assets()
+	}
+	if len(assetNames) == 0 {
+		return nil, errors.New("unable to determine the asset to upgrade to")
+	}
+	return assetNames, nil
+}
+
+// replaceInstallation replaces the files of the installation described by versionInfo with the
+// files download writes to dest, returning them.
+func (i *Install) replaceInstallation(versionInfo *VersionInfo, download func(dest string) ([]string, error)) ([]string, error) {
+	// Download the new version next to the installation first, so a failed download
+	// or extraction leaves the current installation intact
+	stagingDir, err := i.stagingDir()
+	if err != nil {
+		return nil, fmt.Errorf("error creating staging directory: %w", err)
+	}
+	defer os.RemoveAll(stagingDir)
+
+	files, err := download(stagingDir)
+	if err != nil {
+		return nil, err
+	}
+
+	// Clean up existing installation, keeping files that were not installed by us
+	if err := i.retireInstallation(versionInfo); err != nil {
+		return nil, fmt.Errorf("error removing previous version: %w", err)
+	}
+
+	// Swap the new version into place
+	if err := moveFiles(stagingDir, i.InstallPath, files); err != nil {
+		return nil, fmt.Errorf("error installing new version: %w", err)
+	}
+
+	return files, nil
+}
+
+// urlAssetName returns the file name of the asset at rawURL.
+func urlAssetName(rawURL string) (string, error) {
+	u, err := url.Parse(rawURL)
+	if err != nil {
+		return "", fmt.Errorf("invalid download url: %w", err)
+	}
+	return path.Base(u.Path), nil
+}
 
-	f, err := os.Create(versionInfoPath)
+// InstallURL installs the asset at rawURL without consulting the GitHub API and records version
+// in the version file, or the file name of the URL when version is empty. An existing
+// installation of another version, or of the same version when Force is set, is replaced.
+func (i *Install) InstallURL(rawURL, version string) (*InstallOutcome, error) {
+	assetName, err := urlAssetName(rawURL)
 	if err != nil {
-		return fmt.Errorf("error creating version info file: %w", err)
+		return nil, err
+	}
+
+	ctx, d := withDigest(i.context())
+	return i.installSource(assetName, rawURL, version, d.checksum, func(dest string) ([]string, error) {
+		if err := i.checkDiskSpace(dest, []string{rawURL}); err != nil {
+			return nil, err
+		}
+		files, err := i.Downloader.downloadURL(ctx, rawURL, dest, i.Downloader.ExpectedSHA256)
+		if err != nil {
+			return nil, fmt.Errorf("error downloading %s: %w", rawURL, err)
+		}
+		return files, nil
+	})
+}
+
+// installSource installs the asset assetName, which download writes to the directory it is
+// given, and records version, or assetName when version is empty, and sourceURL in the version
+// file. sum returns the SHA-256 of the asset once it was downloaded.
+func (i *Install) installSource(assetName, sourceURL, version string, sum func() string, download func(dest string) ([]string, error)) (*InstallOutcome, error) {
+	if version == "" {
+		version = assetName
+	}
+	if i.VersionedLayout {
+		install, err := i.versionedInstall(version)
+		if err != nil {
+			return nil, err
+		}
+		return install.installSource(assetName, sourceURL, version, sum, download)
+	}
+
+	// An installation without files could not be told from one that predates file tracking
+	extract := download
+	download = func(dest string) ([]string, error) {
+		files, err := extract(dest)
+		if err == nil && len(files) == 0 {
+			err = fmt.Errorf("%w in %s", ErrNoFiles, assetName)
+		}
+		return files, err
+	}
+
+	outcome := &InstallOutcome{
+		Version:     version,
+		InstallPath: i.InstallPath,
+		AssetName:   assetName,
+		AssetNames:  []string{assetName},
+		Action:      InstallActionFresh,
 	}
-	defer f.Close()
 
-	versionInfo := VersionInfo{
+	var files []string
+	if i.alreadyInstalled() {
+		versionInfo, err := i.GetInstalledVersion()
+		if err != nil {
+			return nil, err
+		}
+		if versionInfo.Repo != i.repo {
+			return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+		}
+		switch {
+		case versionInfo.TagName != version:
+			outcome.Action = InstallActionUpgrade
+		case i.Force:
+			outcome.Action = InstallActionReinstall
+		default:
+			outcome.Action = InstallActionNone
+			i.logOutcome(outcome)
+			return outcome, nil
+		}
+
+		if err := i.preInstall(version, outcome.AssetNames, outcome.Action); err != nil {
+			return nil, err
+		}
+		files, err = i.replaceInstallation(versionInfo, download)
+		if err != nil {
+			return nil, err
+		}
+	} else {
+		if err := i.preInstall(version, outcome.AssetNames, outcome.Action); err != nil {
+			return nil, err
+		}
+		var err error
+		files, err = download(i.InstallPath)
+		if err != nil {
+			return nil, err
+		}
+	}
+
+	downloaded := &downloadedAssets{names: outcome.AssetNames, files: files}
+	if checksum := sum(); checksum != "" {
+		downloaded.sha256 = map[string]string{assetName: checksum}
+	}
+	installed, err := i.createVersionFile(version, sourceURL, downloaded)
+	if err != nil {
+		return nil, err
+	}
+	outcome.Files = installed.Files
+	outcome.AssetSHA256 = installed.AssetSHA256
+	i.logOutcome(outcome)
+	if err := i.postInstall(outcome); err != nil {
+		return nil, err
+	}
+	return outcome, nil
+}
+
+// stagingDirPattern is the name pattern of staging directories, created next to the install path.
+const stagingDirPattern = ".%s.staging-*"
+
+// stagingDir creates a temporary directory next to the install path. It lives on the same file
+// system, so staged files can be renamed into place.
+func (i *Install) stagingDir() (string, error) {
+	abs, err := filepath.Abs(i.InstallPath)
+	if err != nil {
+		return "", err
+	}
+
+	// Ensure the parent directory exists
+	if err := os.MkdirAll(filepath.Dir(abs), 0755); err != nil {
+		return "", err
+	}
+
+	return os.MkdirTemp(filepath.Dir(abs), fmt.Sprintf(stagingDirPattern, filepath.Base(abs)))
+}
+
+// moveFiles renames files, given relative to src using forward slashes, to the same paths below dst.
+func moveFiles(src, dst string, files []string) error {
+	for _, file := range files {
+		target, err := safeJoin(dst, filepath.FromSlash(file))
+		if err != nil {
+			return err
+		}
+
+		// Ensure parent directory exists
+		if err := os.MkdirAll(filepath.Dir(target), 0755); err != nil {
+			return err
+		}
+
+		if err := os.Rename(filepath.Join(src, filepath.FromSlash(file)), target); err != nil {
+			return err
+		}
+	}
+
+	return nil
+}
+
+// CreateVersionFile creates a version info file in the specified directory.
+func (i *Install) CreateVersionFile(version string) error {
+	return i.writeVersionInfo(&VersionInfo{
 		TagName: version,
 		Repo:    i.repo,
+	})
+}
+
+// createVersionFile creates the version info file, recording the downloaded assets and their
+// files together with the checksums of the files, the time of the installation and sourceURL,
+// the URL the first asset was downloaded from. It returns the recorded version info.
+func (i *Install) createVersionFile(version, sourceURL string, downloaded *downloadedAssets) (*VersionInfo, error) {
+	versionInfo := newVersionInfo(version, i.repo, downloaded.names, downloaded.files)
+	versionInfo.InstalledAt = time.Now().UTC().Truncate(time.Second)
+	versionInfo.SourceURL = sourceURL
+	versionInfo.AssetSHA256 = downloaded.sha256
+
+	checksums, err := fileChecksums(i.InstallPath, downloaded.files)
+	if err != nil {
+		return nil, err
+	}
+	versionInfo.Checksums = checksums
+
+	if err := i.writeVersionInfo(versionInfo); err != nil {
+		return nil, err
+	}
+	return versionInfo, nil
+}
+
+// newVersionInfo returns the version info of an installation of assetNames. AssetNames is
+// only recorded when there is more than one asset.
+func newVersionInfo(version string, repo string, assetNames []string, files []string) *VersionInfo {
+	versionInfo := &VersionInfo{
+		TagName:   version,
+		Repo:      repo,
+		AssetName: assetNames[0],
+		Files:     files,
+	}
+	if len(assetNames) > 1 {
+		versionInfo.AssetNames = assetNames
 	}
+	return versionInfo
+}
+
+// writeVersionInfo writes versionInfo to the version info file. The file is replaced atomically,
+// so an interrupted write never leaves a truncated version file behind.
+func (i *Install) writeVersionInfo(versionInfo *VersionInfo) error {
+	// Ensure the directory exists
+	if err := os.MkdirAll(i.InstallPath, 0755); err != nil {
+		return fmt.Errorf("error creating install directory: %w", err)
+	}
+
+	versionInfoPath := filepath.Join(i.InstallPath, i.versionFile)
 
 	d, err := json.Marshal(versionInfo)
 	if err != nil {
 		return fmt.Errorf("error marshalling version info: %w", err)
 	}
 
-	if _, err := f.Write(d); err != nil {
+	if err := writeFileAtomic(versionInfoPath, d); err != nil {
 		return fmt.Errorf("error writing version info: %w", err)
 	}
 
 	return nil
 }
 
-// createVersionFile is the internal version of CreateVersionFile.
-func (i *Install) createVersionFile(version string) error {
-	return i.CreateVersionFile(version)
-}
-
 // GetInstalledVersion returns the installed version information for the specified path.
 func (i *Install) GetInstalledVersion() (*VersionInfo, error) {
+	if i.VersionedLayout {
+		_, versionInfo, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if versionInfo == nil {
+			return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+		}
+		return versionInfo, nil
+	}
+
 	versionInfoPath := filepath.Join(i.InstallPath, i.versionFile)
 
 	d, err := os.ReadFile(versionInfoPath)
@@ -183,3 +996,114 @@ func (i *Install) GetInstalledVersion() (*VersionInfo, error) {
 
 	return &versionInfo, nil
 }
+
+// Uninstall removes the installed files and the version file. Other files in the install
+// directory are kept; installations that predate file tracking are removed completely.
+// It fails if nothing is installed or the installation belongs to a different repository.
+func (i *Install) Uninstall() error {
+	if i.VersionedLayout {
+		install, _, err := i.latestVersionedInstall()
+		if err != nil {
+			return err
+		}
+		if install == nil {
+			return fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+		}
+		return install.Uninstall()
+	}
+
+	if !i.alreadyInstalled() {
+		return fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+	}
+
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return err
+	}
+
+	// Verify the repo matches
+	if versionInfo.Repo != i.repo {
+		return fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+
+	if err := i.removeInstalledFiles(versionInfo); err != nil {
+		return fmt.Errorf("error removing installation: %w", err)
+	}
+
+	return nil
+}
+
+// removeInstalledFiles removes the files recorded in versionInfo and the version file, then the
+// directories they leave empty. Installations that predate file tracking are removed entirely.
+func (i *Install) removeInstalledFiles(versionInfo *VersionInfo) error {
+	if versionInfo.Files == nil {
+		return os.RemoveAll(i.InstallPath)
+	}
+
+	for _, file := range versionInfo.Files {
+		target, err := safeJoin(i.InstallPath, filepath.FromSlash(file))
+		if err != nil {
+			return err
+		}
+		if err := os.Remove(target); err != nil && !os.IsNotExist(err) {
+			return err
+		}
+		removeEmptyDirs(i.InstallPath, filepath.Dir(target))
+	}
+
+	if err := os.Remove(filepath.Join(i.InstallPath, i.versionFile)); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+	removeEmptyDirs(i.InstallPath, i.InstallPath)
+
+	return nil
+}
+
+// removeEmptyDirs removes dir and its parents up to and including root for as long as they are empty.
+func removeEmptyDirs(root, dir string) {
+	for withinDir(root, dir) {
+		// Removing a directory that is not empty fails, which ends the walk
+		if err := os.Remove(dir); err != nil {
+			return
+		}
+		dir = filepath.Dir(dir)
+	}
+}
+
+// CheckUpdate reports whether a newer version than the installed one is available,
+// without downloading anything.
+func (i *Install) CheckUpdate() (*UpdateStatus, error) {
+	if i.VersionedLayout {
+		install, _, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if install == nil {
+			return &UpdateStatus{State: UpdateNotInstalled}, nil
+		}
+		return install.CheckUpdate()
+	}
+
+	if !i.alreadyInstalled() {
+		return &UpdateStatus{State: UpdateNotInstalled}, nil
+	}
+
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if versionInfo.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+
+	latest, err := i.latestVersion()
+	if err != nil {
+		return nil, fmt.Errorf("error getting latest version: %w", err)
+	}
+
+	status := &UpdateStatus{State: UpdateUpToDate, Current: versionInfo.TagName, Latest: latest}
+	if versionInfo.UpgradeAvailable(latest) {
+		status.State = UpdateAvailable
+	}
+	return status, nil
+}
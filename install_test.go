@@ -0,0 +1,1109 @@
+package libfetch_test
+
+import (
+	"archive/zip"
+	"bytes"
+	"crypto/ed25519"
+	"crypto/sha256"
+	"encoding/base64"
+	"encoding/hex"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/http/httptest"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/Cyberhan123/libfetch"
+	"golang.org/x/crypto/blake2b"
+)
+
+func TestVersionInfoUpgradeAvailable(t *testing.T) {
+	tests := []struct {
+		installed string
+		latest    string
+		want      bool
+	}{
+		{"v1.9.0", "v1.10.0", true},
+		{"v1.10.0", "v1.9.0", false},
+		{"v1.10.0", "v1.10.0", false},
+		{"1.2.3", "v1.2.4", true},
+		{"b7869", "b7870", true},
+		{"b7869", "b7869", false},
+	}
+
+	for _, tt := range tests {
+		info := libfetch.VersionInfo{TagName: tt.installed}
+		if got := info.UpgradeAvailable(tt.latest); got != tt.want {
+			t.Errorf("UpgradeAvailable(%q) with %q installed = %v, want %v", tt.latest, tt.installed, got, tt.want)
+		}
+	}
+}
+
+// newReleaseServer serves a fake GitHub for owner/repo. The latest release is the tag stored in
+// latest and every release has "tool-<tag>.zip" and "data-<tag>.zip" assets containing bin/tool and
+// bin/data respectively, with the tag as content.
+func newReleaseServer(t *testing.T, latest *atomic.Value) *httptest.Server {
+	mux := http.NewServeMux()
+	mux.HandleFunc("/repos/owner/repo/releases/latest", func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprintf(w, `{"tag_name": %q}`, latest.Load())
+	})
+	mux.HandleFunc("/owner/repo/releases/download/", func(w http.ResponseWriter, r *http.Request) {
+		// /owner/repo/releases/download/<tag>/<asset>
+		parts := strings.Split(r.URL.Path, "/")
+		if len(parts) != 7 {
+			http.NotFound(w, r)
+			return
+		}
+		name, ok := strings.CutSuffix(parts[6], "-"+parts[5]+".zip")
+		if !ok || (name != "tool" && name != "data") {
+			http.NotFound(w, r)
+			return
+		}
+
+		var buf bytes.Buffer
+		zw := zip.NewWriter(&buf)
+		fw, err := zw.Create("bin/" + name)
+		if err == nil {
+			_, err = fw.Write([]byte(parts[5]))
+		}
+		if err == nil {
+			err = zw.Close()
+		}
+		if err != nil {
+			http.Error(w, err.Error(), http.StatusInternalServerError)
+			return
+		}
+		w.Write(buf.Bytes())
+	})
+
+	server := httptest.NewServer(mux)
+	t.Cleanup(server.Close)
+	return server
+}
+
+// newTestInstall returns an Install of owner/repo into dir that talks to server.
+func newTestInstall(server *httptest.Server, dir string) *libfetch.Install {
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+	downloader.DownloadBaseURL = server.URL
+	downloader.ProgressTracker = nil
+	downloader.RetryDelay = 0
+
+	install := libfetch.NewInstall("owner/repo", dir)
+	install.Downloader = downloader
+	install.AssetFunc = func(version string) string {
+		return "tool-" + version + ".zip"
+	}
+	return install
+}
+
+func TestUpgradeKeepsUntrackedFiles(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	userFile := filepath.Join(dir, "user.conf")
+	if err := os.WriteFile(userFile, []byte("keep me"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	latest.Store("v1.1.0")
+	outcome, err := install.InstallAsset("tool-v1.1.0.zip", "", true)
+	if err != nil {
+		t.Fatalf("error upgrading: %v", err)
+	}
+	if outcome.Action != libfetch.InstallActionUpgrade || outcome.Version != "v1.1.0" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+
+	if d, err := os.ReadFile(userFile); err != nil || string(d) != "keep me" {
+		t.Errorf("user file did not survive the upgrade: %q, %v", d, err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.1.0" {
+		t.Errorf("tool was not upgraded: %q, %v", d, err)
+	}
+}
+
+func TestCheckUpdate(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	status, err := install.CheckUpdate()
+	if err != nil || status.State != libfetch.UpdateNotInstalled {
+		t.Fatalf("CheckUpdate() before install = %+v, %v", status, err)
+	}
+
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	status, err = install.CheckUpdate()
+	if err != nil || status.State != libfetch.UpdateUpToDate || status.Current != "v1.0.0" {
+		t.Fatalf("CheckUpdate() after install = %+v, %v", status, err)
+	}
+
+	latest.Store("v1.1.0")
+	status, err = install.CheckUpdate()
+	if err != nil || status.State != libfetch.UpdateAvailable || status.Current != "v1.0.0" || status.Latest != "v1.1.0" {
+		t.Fatalf("CheckUpdate() with a new release = %+v, %v", status, err)
+	}
+
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("CheckUpdate changed the installation: %q, %v", d, err)
+	}
+}
+
+func TestInstallAssetsRecordsAllAssets(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.AssetsFunc = func(version string) []string {
+		return []string{"tool-" + version + ".zip", "data-" + version + ".zip"}
+	}
+
+	outcome, err := install.InstallAssets([]string{"tool-v1.0.0.zip", "data-v1.0.0.zip"}, "", true)
+	if err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+	if len(outcome.AssetNames) != 2 || outcome.AssetName != "tool-v1.0.0.zip" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+
+	installed, err := install.GetInstalledVersion()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := installed.Assets(); len(got) != 2 || got[0] != "tool-v1.0.0.zip" || got[1] != "data-v1.0.0.zip" {
+		t.Errorf("version file records assets %v", got)
+	}
+
+	latest.Store("v1.1.0")
+	if _, err := install.InstallAssets([]string{"tool-v1.1.0.zip", "data-v1.1.0.zip"}, "", true); err != nil {
+		t.Fatalf("error upgrading: %v", err)
+	}
+	for _, name := range []string{"tool", "data"} {
+		if d, err := os.ReadFile(filepath.Join(dir, "bin", name)); err != nil || string(d) != "v1.1.0" {
+			t.Errorf("bin/%s was not upgraded: %q, %v", name, d, err)
+		}
+	}
+}
+
+func TestInstallAssetsConcurrently(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.MaxConcurrency = 2
+
+	if _, err := install.InstallAssets([]string{"tool-v1.0.0.zip", "data-v1.0.0.zip"}, "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	installed, err := install.GetInstalledVersion()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if len(installed.Files) != 2 || installed.Files[0] != "bin/data" || installed.Files[1] != "bin/tool" {
+		t.Errorf("version file records files %v", installed.Files)
+	}
+}
+
+func TestInstallAssetsIntoSubdirectories(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.AssetDir = func(assetName string) string {
+		if strings.HasPrefix(assetName, "data-") {
+			return "models"
+		}
+		return ""
+	}
+
+	outcome, err := install.InstallAssets([]string{"tool-v1.0.0.zip", "data-v1.0.0.zip"}, "", false)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := strings.Join(outcome.Files, ","); got != "bin/tool,models/bin/data" {
+		t.Errorf("installed files %s", got)
+	}
+	for _, name := range []string{"bin/tool", "models/bin/data"} {
+		if _, err := os.Stat(filepath.Join(dir, filepath.FromSlash(name))); err != nil {
+			t.Error(err)
+		}
+	}
+
+	install = newTestInstall(server, t.TempDir())
+	install.AssetDir = func(assetName string) string {
+		return "../outside"
+	}
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err == nil {
+		t.Error("installing into a directory outside of the install path succeeded")
+	}
+}
+
+func TestCaseInsensitiveAssets(t *testing.T) {
+	var server *httptest.Server
+	server = httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/releases/latest":
+			fmt.Fprintf(w, `{"tag_name": "v1.0.0", "assets": [{"name": "Tool.bin", "browser_download_url": %q}]}`, server.URL+"/assets/Tool.bin")
+		case "/assets/Tool.bin":
+			fmt.Fprint(w, "tool")
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	if _, err := install.InstallAsset("tool.bin", "", false); !errors.Is(err, libfetch.ErrAssetNotFound) {
+		t.Fatalf("expected ErrAssetNotFound without case-insensitive matching, got %v", err)
+	}
+
+	install.Downloader.CaseInsensitiveAssets = true
+	outcome, err := install.InstallAsset("tool.bin", "", false)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if outcome.AssetName != "Tool.bin" {
+		t.Errorf("installed asset %s, want Tool.bin", outcome.AssetName)
+	}
+	if installed, err := install.GetInstalledVersion(); err != nil || installed.AssetName != "Tool.bin" {
+		t.Errorf("version file = %+v, %v", installed, err)
+	}
+}
+
+func TestSetVersionFile(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	for _, name := range []string{"", "..", "sub/tool.json", `sub\tool.json`} {
+		if err := install.SetVersionFile(name); err == nil {
+			t.Errorf("SetVersionFile(%q) succeeded", name)
+		}
+	}
+
+	if err := install.SetVersionFile("tool.version.json"); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "tool.version.json")); err != nil {
+		t.Errorf("version file was not written: %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "version.json")); !os.IsNotExist(err) {
+		t.Errorf("default version file was written: %v", err)
+	}
+}
+
+func TestVersionFileLeavesNoTemporaryFiles(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	for _, version := range []string{"v1.0.0", "v1.1.0"} {
+		latest.Store(version)
+		if _, err := install.InstallAsset("tool-"+version+".zip", "", true); err != nil {
+			t.Fatalf("error installing %s: %v", version, err)
+		}
+	}
+
+	if installed, err := install.GetInstalledVersion(); err != nil || installed.TagName != "v1.1.0" {
+		t.Errorf("installed version = %+v, %v", installed, err)
+	}
+	entries, err := os.ReadDir(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	for _, entry := range entries {
+		if entry.Name() != "bin" && entry.Name() != "version.json" {
+			t.Errorf("unexpected file %s in install directory", entry.Name())
+		}
+	}
+}
+
+func TestVersionInfoRecordsSourceAndTime(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	before := time.Now().Add(-time.Second)
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatal(err)
+	}
+
+	installed, err := install.GetInstalledVersion()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if want := server.URL + "/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip"; installed.SourceURL != want {
+		t.Errorf("source URL = %q, want %q", installed.SourceURL, want)
+	}
+	if installed.InstalledAt.Before(before) || installed.InstalledAt.After(time.Now()) {
+		t.Errorf("installed at %v", installed.InstalledAt)
+	}
+
+	// Version files written before these fields existed are still read
+	if err := os.WriteFile(filepath.Join(dir, "version.json"), []byte(`{"tag_name":"v1.0.0","repo":"owner/repo"}`), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if installed, err := install.GetInstalledVersion(); err != nil || !installed.InstalledAt.IsZero() || installed.SourceURL != "" {
+		t.Errorf("old version file = %+v, %v", installed, err)
+	}
+}
+
+func TestInstallURL(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := libfetch.NewInstall("", dir)
+	install.Downloader.ProgressTracker = nil
+
+	outcome, err := install.InstallURL(server.URL+"/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip", "")
+	if err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+	if outcome.Action != libfetch.InstallActionFresh || outcome.Version != "tool-v1.0.0.zip" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+
+	outcome, err = install.InstallURL(server.URL+"/owner/repo/releases/download/v1.1.0/tool-v1.1.0.zip", "1.1.0")
+	if err != nil {
+		t.Fatalf("error replacing: %v", err)
+	}
+	if outcome.Action != libfetch.InstallActionUpgrade || outcome.Version != "1.1.0" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.1.0" {
+		t.Errorf("tool was not replaced: %q, %v", d, err)
+	}
+}
+
+func TestForceReinstallsSameVersion(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "v1.0.0", false); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+	tool := filepath.Join(dir, "bin", "tool")
+	if err := os.WriteFile(tool, []byte("corrupt"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	install.Force = true
+	outcome, err := install.InstallAsset("tool-v1.0.0.zip", "v1.0.0", false)
+	if err != nil {
+		t.Fatalf("error reinstalling: %v", err)
+	}
+	if outcome.Action != libfetch.InstallActionReinstall || outcome.Version != "v1.0.0" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+	if d, err := os.ReadFile(tool); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("tool was not reinstalled: %q, %v", d, err)
+	}
+}
+
+func TestPinnedVersionReplacesOtherVersion(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.1.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	if _, err := install.InstallAsset("tool-v1.1.0.zip", "v1.1.0", false); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	outcome, err := install.InstallAsset("tool-v1.0.0.zip", "v1.0.0", false)
+	if err != nil {
+		t.Fatalf("error downgrading: %v", err)
+	}
+	if outcome.Action != libfetch.InstallActionDowngrade || outcome.Version != "v1.0.0" {
+		t.Fatalf("unexpected outcome: %+v", outcome)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("tool was not downgraded: %q, %v", d, err)
+	}
+
+	// Requesting the pinned version again does nothing
+	outcome, err = install.InstallAsset("tool-v1.0.0.zip", "v1.0.0", false)
+	if err != nil || outcome.Action != libfetch.InstallActionNone {
+		t.Errorf("repeated install = %+v, %v", outcome, err)
+	}
+}
+
+func TestRollbackRestoresRetainedVersion(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.KeepVersions = 1
+
+	for _, version := range []string{"v1.0.0", "v1.1.0", "v1.2.0"} {
+		latest.Store(version)
+		if _, err := install.InstallAsset("tool-"+version+".zip", "", true); err != nil {
+			t.Fatalf("error installing %s: %v", version, err)
+		}
+	}
+
+	retained, err := install.RetainedVersions()
+	if err != nil || len(retained) != 1 || retained[0].TagName != "v1.1.0" {
+		t.Fatalf("RetainedVersions() = %+v, %v", retained, err)
+	}
+
+	restored, err := install.Rollback()
+	if err != nil {
+		t.Fatalf("error rolling back: %v", err)
+	}
+	if restored.TagName != "v1.1.0" {
+		t.Errorf("restored %s, want v1.1.0", restored.TagName)
+	}
+	if installed, err := install.GetInstalledVersion(); err != nil || installed.TagName != "v1.1.0" {
+		t.Errorf("installed version after rollback = %+v, %v", installed, err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.1.0" {
+		t.Errorf("tool was not rolled back: %q, %v", d, err)
+	}
+
+	if _, err := install.Rollback(); err == nil {
+		t.Error("second rollback without a retained version succeeded")
+	}
+}
+
+func TestVersionedLayout(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.VersionedLayout = true
+
+	for _, version := range []string{"v1.0.0", "v1.1.0"} {
+		latest.Store(version)
+		outcome, err := install.InstallAsset("tool-"+version+".zip", "", true)
+		if err != nil {
+			t.Fatalf("error installing %s: %v", version, err)
+		}
+		if outcome.InstallPath != filepath.Join(dir, version) || outcome.Action != libfetch.InstallActionFresh {
+			t.Errorf("installing %s: outcome %+v", version, outcome)
+		}
+	}
+
+	// Both versions are installed side by side
+	for _, version := range []string{"v1.0.0", "v1.1.0"} {
+		if d, err := os.ReadFile(filepath.Join(dir, version, "bin", "tool")); err != nil || string(d) != version {
+			t.Errorf("%s: tool = %q, %v", version, d, err)
+		}
+	}
+	if installed, err := install.GetInstalledVersion(); err != nil || installed.TagName != "v1.1.0" {
+		t.Errorf("installed version = %+v, %v", installed, err)
+	}
+
+	outcome, err := install.InstallAsset("tool-v1.1.0.zip", "", true)
+	if err != nil || outcome.Action != libfetch.InstallActionNone {
+		t.Errorf("installing the latest version again = %+v, %v", outcome, err)
+	}
+
+	if err := install.Uninstall(); err != nil {
+		t.Fatal(err)
+	}
+	if installed, err := install.GetInstalledVersion(); err != nil || installed.TagName != "v1.0.0" {
+		t.Errorf("installed version after uninstalling the newest = %+v, %v", installed, err)
+	}
+}
+
+func TestVerifyDetectsCorruptedFiles(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatal(err)
+	}
+
+	if mismatched, err := install.Verify(); err != nil || len(mismatched) != 0 {
+		t.Fatalf("Verify() of an intact installation = %v, %v", mismatched, err)
+	}
+
+	if err := os.WriteFile(filepath.Join(dir, "bin", "tool"), []byte("corrupt"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if mismatched, err := install.Verify(); err != nil || len(mismatched) != 1 || mismatched[0] != "bin/tool" {
+		t.Fatalf("Verify() of a corrupted installation = %v, %v", mismatched, err)
+	}
+
+	// Reinstalling repairs the installation
+	install.Force = true
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatal(err)
+	}
+	if mismatched, err := install.Verify(); err != nil || len(mismatched) != 0 {
+		t.Errorf("Verify() after reinstalling = %v, %v", mismatched, err)
+	}
+}
+
+func TestInstallReportsAssetDigest(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	resp, err := http.Get(server.URL + "/owner/repo/releases/download/v1.0.0/tool-v1.0.0.zip")
+	if err != nil {
+		t.Fatal(err)
+	}
+	data, err := io.ReadAll(resp.Body)
+	resp.Body.Close()
+	if err != nil {
+		t.Fatal(err)
+	}
+	sum := sha256.Sum256(data)
+	want := hex.EncodeToString(sum[:])
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	outcome, err := install.InstallAsset("tool-v1.0.0.zip", "", false)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := outcome.AssetSHA256["tool-v1.0.0.zip"]; got != want {
+		t.Errorf("AssetSHA256 = %q, want %q", got, want)
+	}
+
+	versionInfo, err := install.GetInstalledVersion()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := versionInfo.AssetSHA256["tool-v1.0.0.zip"]; got != want {
+		t.Errorf("recorded AssetSHA256 = %q, want %q", got, want)
+	}
+}
+
+func TestCleanupTemp(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := filepath.Join(t.TempDir(), "tool")
+	install := newTestInstall(server, dir)
+	if n, err := install.CleanupTemp(); err != nil || n != 0 {
+		t.Fatalf("CleanupTemp() before installing = %d, %v", n, err)
+	}
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatal(err)
+	}
+
+	stale := []string{
+		filepath.Join(dir, "tool-v1.0.1.zip.part"),
+		filepath.Join(dir, ".version.json.tmp-123"),
+		filepath.Join(filepath.Dir(dir), ".tool.staging-456", "bin", "tool"),
+	}
+	for _, name := range stale {
+		if err := os.MkdirAll(filepath.Dir(name), 0755); err != nil {
+			t.Fatal(err)
+		}
+		if err := os.WriteFile(name, []byte("stale"), 0644); err != nil {
+			t.Fatal(err)
+		}
+	}
+
+	if n, err := install.CleanupTemp(); err != nil || n != len(stale) {
+		t.Fatalf("CleanupTemp() = %d, %v, want %d", n, err, len(stale))
+	}
+	for _, name := range stale {
+		if _, err := os.Stat(name); !os.IsNotExist(err) {
+			t.Errorf("%s was not removed: %v", name, err)
+		}
+	}
+	if mismatched, err := install.Verify(); err != nil || len(mismatched) != 0 {
+		t.Errorf("Verify() after cleaning up = %v, %v", mismatched, err)
+	}
+}
+
+func TestInstallFile(t *testing.T) {
+	var buf bytes.Buffer
+	zw := zip.NewWriter(&buf)
+	fw, err := zw.Create("bin/tool")
+	if err == nil {
+		_, err = fw.Write([]byte("v1.0.0"))
+	}
+	if err == nil {
+		err = zw.Close()
+	}
+	if err != nil {
+		t.Fatal(err)
+	}
+	archive := filepath.Join(t.TempDir(), "tool.zip")
+	if err := os.WriteFile(archive, buf.Bytes(), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	dir := t.TempDir()
+	install := libfetch.NewInstall("owner/repo", dir)
+	install.Downloader.ProgressTracker = nil
+	outcome, err := install.InstallFile(archive, "v1.0.0")
+	if err != nil {
+		t.Fatal(err)
+	}
+	if outcome.Action != libfetch.InstallActionFresh || outcome.Version != "v1.0.0" || outcome.AssetName != "tool.zip" {
+		t.Errorf("outcome = %+v", outcome)
+	}
+	if data, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(data) != "v1.0.0" {
+		t.Fatalf("bin/tool = %q, %v", data, err)
+	}
+
+	versionInfo, err := install.GetInstalledVersion()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if versionInfo.TagName != "v1.0.0" || !strings.HasPrefix(versionInfo.SourceURL, "file://") {
+		t.Errorf("version info = %+v", versionInfo)
+	}
+
+	if outcome, err := install.InstallFile(archive, "v1.0.0"); err != nil || outcome.Action != libfetch.InstallActionNone {
+		t.Errorf("installing the same version again = %+v, %v", outcome, err)
+	}
+
+	install.Downloader.ExpectedSHA256 = strings.Repeat("0", 64)
+	install.Force = true
+	if _, err := install.InstallFile(archive, "v1.0.0"); !errors.Is(err, libfetch.ErrChecksumMismatch) {
+		t.Errorf("expected ErrChecksumMismatch, got %v", err)
+	}
+}
+
+func TestKeepArchive(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.Downloader.KeepArchive = true
+	outcome, err := install.InstallAsset("tool-v1.0.0.zip", "", false)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if got := strings.Join(outcome.Files, ","); got != "bin/tool,tool-v1.0.0.zip" {
+		t.Errorf("installed %s, want the extracted file and the archive", got)
+	}
+	r, err := zip.OpenReader(filepath.Join(dir, "tool-v1.0.0.zip"))
+	if err != nil {
+		t.Fatalf("archive was not kept: %v", err)
+	}
+	r.Close()
+
+	// The archive belongs to the installation
+	if err := install.Uninstall(); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "tool-v1.0.0.zip")); !os.IsNotExist(err) {
+		t.Errorf("archive was not uninstalled: %v", err)
+	}
+}
+
+func TestInstallChecksDiskSpace(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+	defer server.Close()
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.SpaceFactor = 1e14
+
+	_, err := install.InstallAsset("tool-v1.0.0.zip", "", false)
+	var serr *libfetch.InsufficientSpaceError
+	if !errors.As(err, &serr) || !errors.Is(err, libfetch.ErrInsufficientSpace) {
+		t.Fatalf("expected an insufficient space error, got %v", err)
+	}
+	if serr.Needed <= serr.Available {
+		t.Errorf("needed %d bytes, available %d", serr.Needed, serr.Available)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "bin", "tool")); !os.IsNotExist(err) {
+		t.Errorf("expected nothing to be installed, got %v", err)
+	}
+
+	install.SpaceFactor = 0
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+		t.Fatalf("error installing without the disk space check: %v", err)
+	}
+}
+
+// minisign returns a minisign public key and the prehashed minisign signature of data made with it.
+func minisign(t *testing.T, data []byte) (publicKey string, signature []byte) {
+	t.Helper()
+	pub, priv, err := ed25519.GenerateKey(nil)
+	if err != nil {
+		t.Fatal(err)
+	}
+	keyID := []byte("libfetch")
+
+	hash := blake2b.Sum512(data)
+	sig := ed25519.Sign(priv, hash[:])
+	comment := "timestamp:0\tfile:tool.zip"
+	global := ed25519.Sign(priv, append(slices.Clone(sig), comment...))
+
+	publicKey = base64.StdEncoding.EncodeToString(slices.Concat([]byte("Ed"), keyID, pub))
+	signature = fmt.Appendf(nil, "untrusted comment: signature from minisign secret key\n%s\ntrusted comment: %s\n%s\n",
+		base64.StdEncoding.EncodeToString(slices.Concat([]byte("ED"), keyID, sig)), comment, base64.StdEncoding.EncodeToString(global))
+	return publicKey, signature
+}
+
+func TestInstallVerifiesSignature(t *testing.T) {
+	var archive bytes.Buffer
+	zw := zip.NewWriter(&archive)
+	if fw, err := zw.Create("bin/tool"); err != nil {
+		t.Fatal(err)
+	} else if _, err := fw.Write([]byte("tool")); err != nil {
+		t.Fatal(err)
+	}
+	if err := zw.Close(); err != nil {
+		t.Fatal(err)
+	}
+	publicKey, signature := minisign(t, archive.Bytes())
+	_, otherSignature := minisign(t, archive.Bytes())
+
+	var served atomic.Value
+	served.Store(signature)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/releases/latest":
+			fmt.Fprint(w, `{"tag_name": "v1.0.0"}`)
+		case "/owner/repo/releases/download/v1.0.0/tool.zip":
+			w.Write(archive.Bytes())
+		case "/owner/repo/releases/download/v1.0.0/tool.zip.minisig":
+			w.Write(served.Load().([]byte))
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	install := func(dir string) (*libfetch.InstallOutcome, error) {
+		return libfetch.NewApi().
+			SetInstallDir(dir).
+			SetGitHubBase(server.URL, server.URL).
+			SetProgressTracker(nil).
+			Repo("owner/repo").
+			Latest().
+			InstallVerified(func(version string) string {
+				return "tool.zip"
+			}, func(version string) string {
+				return "tool.zip.minisig"
+			}, publicKey)
+	}
+
+	// A signature made with another key is rejected before anything is extracted
+	served.Store(otherSignature)
+	dir := t.TempDir()
+	if _, err := install(dir); !errors.Is(err, libfetch.ErrSignatureMismatch) {
+		t.Fatalf("expected ErrSignatureMismatch, got %v", err)
+	}
+	if entries, _ := os.ReadDir(dir); len(entries) != 0 {
+		t.Errorf("extracted %d entries despite the bad signature", len(entries))
+	}
+
+	served.Store(signature)
+	outcome, err := install(dir)
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !slices.Equal(outcome.Files, []string{"bin/tool"}) {
+		t.Errorf("installed %v, want bin/tool", outcome.Files)
+	}
+	sum := sha256.Sum256(archive.Bytes())
+	if got := outcome.AssetSHA256["tool.zip"]; got != hex.EncodeToString(sum[:]) {
+		t.Errorf("recorded SHA-256 %s, want the digest of the archive", got)
+	}
+}
+
+func TestUpgradePlan(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+	defer server.Close()
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	if _, err := install.UpgradePlan(); !errors.Is(err, libfetch.ErrNotInstalled) {
+		t.Fatalf("expected ErrNotInstalled, got %v", err)
+	}
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	plan, err := install.UpgradePlan()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if plan.UpgradeAvailable || plan.Remove != nil {
+		t.Errorf("expected an empty plan without a newer version, got %+v", plan)
+	}
+
+	latest.Store("v1.1.0")
+	plan, err = install.UpgradePlan()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if !plan.UpgradeAvailable || plan.Current != "v1.0.0" || plan.Target != "v1.1.0" {
+		t.Errorf("unexpected plan: %+v", plan)
+	}
+	if !slices.Equal(plan.Assets, []string{"tool-v1.1.0.zip"}) || !slices.Equal(plan.Remove, []string{"bin/tool"}) {
+		t.Errorf("plan upgrades %v removing %v, want tool-v1.1.0.zip removing bin/tool", plan.Assets, plan.Remove)
+	}
+	// The test server does not list release assets
+	if plan.Add != nil {
+		t.Errorf("expected no added assets without an asset list, got %v", plan.Add)
+	}
+
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("planning changed the installation: %q, %v", d, err)
+	}
+}
+
+func TestDiffLatest(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+	defer server.Close()
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	if _, err := install.DiffLatest(); !errors.Is(err, libfetch.ErrNotInstalled) {
+		t.Fatalf("expected ErrNotInstalled, got %v", err)
+	}
+	if _, err := install.InstallAssets([]string{"tool-v1.0.0.zip", "data-v1.0.0.zip"}, "", true); err != nil {
+		t.Fatalf("error installing: %v", err)
+	}
+
+	latest.Store("v1.1.0")
+	install.AssetsFunc = func(version string) []string {
+		return []string{"tool-" + version + ".zip", "data-" + version + ".zip"}
+	}
+	install.AssetDir = func(assetName string) string {
+		if strings.HasPrefix(assetName, "data-") {
+			return "extra"
+		}
+		return ""
+	}
+	diff, err := install.DiffLatest()
+	if err != nil {
+		t.Fatal(err)
+	}
+	if diff.Current != "v1.0.0" || diff.Target != "v1.1.0" {
+		t.Errorf("compared %s with %s, want v1.0.0 with v1.1.0", diff.Current, diff.Target)
+	}
+	if !slices.Equal(diff.Added, []string{"extra/bin/data"}) || !slices.Equal(diff.Removed, []string{"bin/data"}) || !slices.Equal(diff.Unchanged, []string{"bin/tool"}) {
+		t.Errorf("diff adds %v, removes %v and keeps %v", diff.Added, diff.Removed, diff.Unchanged)
+	}
+
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+		t.Errorf("diffing changed the installation: %q, %v", d, err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "extra")); !os.IsNotExist(err) {
+		t.Errorf("diffing extracted the latest version: %v", err)
+	}
+}
+
+func TestInstallWithoutFilesFails(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+	install.Downloader.ExtractPattern = "no-such-file"
+	if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); !errors.Is(err, libfetch.ErrNoFiles) {
+		t.Fatalf("expected ErrNoFiles, got %v", err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "version.json")); !os.IsNotExist(err) {
+		t.Errorf("an installation without files was recorded: %v", err)
+	}
+}
+
+func TestUninstallWithoutFilesKeepsUntrackedFiles(t *testing.T) {
+	dir := t.TempDir()
+	// A tracked installation that recorded no files, unlike one that predates file tracking
+	versionInfo := `{"tag_name": "v1.0.0", "repo": "owner/repo", "files": []}`
+	if err := os.WriteFile(filepath.Join(dir, "version.json"), []byte(versionInfo), 0644); err != nil {
+		t.Fatal(err)
+	}
+	if err := os.WriteFile(filepath.Join(dir, "notes.txt"), []byte("mine"), 0644); err != nil {
+		t.Fatal(err)
+	}
+
+	install := libfetch.NewInstall("owner/repo", dir)
+	if err := install.Uninstall(); err != nil {
+		t.Fatal(err)
+	}
+	if _, err := os.Stat(filepath.Join(dir, "version.json")); !os.IsNotExist(err) {
+		t.Errorf("version file was kept: %v", err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "notes.txt")); err != nil || string(d) != "mine" {
+		t.Errorf("untracked file = %q, %v", d, err)
+	}
+}
+
+func TestFailedUpgradeKeepsInstallation(t *testing.T) {
+	tests := []struct {
+		name     string
+		asset    string
+		checksum string
+	}{
+		{"checksum mismatch", "tool", strings.Repeat("0", 64)},
+		{"missing asset", "missing", ""},
+	}
+	for _, tt := range tests {
+		var latest atomic.Value
+		latest.Store("v1.0.0")
+		server := newReleaseServer(t, &latest)
+
+		dir := filepath.Join(t.TempDir(), "tool")
+		install := newTestInstall(server, dir)
+		if _, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err != nil {
+			t.Fatal(err)
+		}
+		versionFile, err := os.ReadFile(filepath.Join(dir, "version.json"))
+		if err != nil {
+			t.Fatal(err)
+		}
+
+		latest.Store("v1.1.0")
+		install.AssetsFunc = func(version string) []string {
+			return []string{tt.asset + "-" + version + ".zip"}
+		}
+		install.Downloader.ExpectedSHA256 = tt.checksum
+		if _, err := install.InstallAsset("tool-v1.0.0.zip", "", true); err == nil {
+			t.Fatalf("%s: expected the upgrade to fail", tt.name)
+		}
+
+		if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.0.0" {
+			t.Errorf("%s: tool = %q, %v, want the installed version", tt.name, d, err)
+		}
+		if d, err := os.ReadFile(filepath.Join(dir, "version.json")); err != nil || !bytes.Equal(d, versionFile) {
+			t.Errorf("%s: version file changed: %s, %v", tt.name, d, err)
+		}
+		staging, err := filepath.Glob(filepath.Join(filepath.Dir(dir), ".tool.staging-*"))
+		if err != nil || len(staging) != 0 {
+			t.Errorf("%s: staging directories left behind: %v, %v", tt.name, staging, err)
+		}
+	}
+}
+
+func TestInstallReportsCorruptVersionFile(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	if err := os.WriteFile(filepath.Join(dir, "version.json"), []byte("{not json"), 0644); err != nil {
+		t.Fatal(err)
+	}
+	install := newTestInstall(server, dir)
+	if outcome, err := install.InstallAsset("tool-v1.0.0.zip", "", false); err == nil {
+		t.Fatalf("expected an error for a corrupt version file, got %+v", outcome)
+	}
+}
+
+func TestInstallHooks(t *testing.T) {
+	var latest atomic.Value
+	latest.Store("v1.0.0")
+	server := newReleaseServer(t, &latest)
+
+	dir := t.TempDir()
+	install := newTestInstall(server, dir)
+
+	var calls []string
+	install.PreInstall = func(ctx *libfetch.InstallContext) error {
+		if _, err := os.Stat(filepath.Join(dir, "bin", "tool")); err == nil && ctx.Action == libfetch.InstallActionFresh {
+			t.Error("pre-install hook called after the download")
+		}
+		calls = append(calls, fmt.Sprintf("pre %s %s %v", ctx.Action, ctx.Version, ctx.AssetNames))
+		return nil
+	}
+	install.PostInstall = func(outcome *libfetch.InstallOutcome) error {
+		if _, err := install.GetInstalledVersion(); err != nil {
+			t.Errorf("post-install hook called before the version file was written: %v", err)
+		}
+		calls = append(calls, fmt.Sprintf("post %s %s %s", outcome.Action, outcome.Version, outcome.InstallPath))
+		return nil
+	}
+
+	for _, version := range []string{"v1.0.0", "v1.0.0", "v1.1.0"} {
+		latest.Store(version)
+		if _, err := install.InstallAsset("tool-"+version+".zip", "", true); err != nil {
+			t.Fatalf("error installing %s: %v", version, err)
+		}
+	}
+
+	want := []string{
+		"pre fresh v1.0.0 [tool-v1.0.0.zip]",
+		"post fresh v1.0.0 " + dir,
+		"pre upgrade v1.1.0 [tool-v1.1.0.zip]",
+		"post upgrade v1.1.0 " + dir,
+	}
+	if strings.Join(calls, "\n") != strings.Join(want, "\n") {
+		t.Errorf("hook calls:\n%s\nwant:\n%s", strings.Join(calls, "\n"), strings.Join(want, "\n"))
+	}
+
+	// A failing pre-install hook leaves the installation untouched
+	errAbort := errors.New("abort")
+	install.PreInstall = func(ctx *libfetch.InstallContext) error {
+		return errAbort
+	}
+	latest.Store("v1.2.0")
+	if _, err := install.InstallAsset("tool-v1.2.0.zip", "", true); !errors.Is(err, errAbort) {
+		t.Fatalf("expected the hook error, got %v", err)
+	}
+	if d, err := os.ReadFile(filepath.Join(dir, "bin", "tool")); err != nil || string(d) != "v1.1.0" {
+		t.Errorf("aborted upgrade changed the installation: %q, %v", d, err)
+	}
+}
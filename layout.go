@@ -0,0 +1,92 @@
+package libfetch
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// versionedInstall returns a copy of i that installs version into the subdirectory of the
+// install path named after it, as used by the versioned layout.
+func (i *Install) versionedInstall(version string) (*Install, error) {
+	if version == "" || version == "." || version == ".." || version == versionsDir || strings.ContainsAny(version, `/\`) {
+		return nil, fmt.Errorf("version %q cannot be used as an install directory name", version)
+	}
+
+	install := *i
+	install.InstallPath = filepath.Join(i.InstallPath, version)
+	install.VersionedLayout = false
+	return &install, nil
+}
+
+// latestVersionedInstall returns a copy of i for the newest version installed in a subdirectory
+// of the install path, together with its version info. It returns nil if no version of the
+// repository is installed.
+func (i *Install) latestVersionedInstall() (*Install, *VersionInfo, error) {
+	entries, err := os.ReadDir(i.InstallPath)
+	if os.IsNotExist(err) {
+		return nil, nil, nil
+	}
+	if err != nil {
+		return nil, nil, fmt.Errorf("error reading install directory: %w", err)
+	}
+
+	var latest *Install
+	var latestInfo *VersionInfo
+	for _, entry := range entries {
+		if !entry.IsDir() {
+			continue
+		}
+		install, err := i.versionedInstall(entry.Name())
+		if err != nil || !install.alreadyInstalled() {
+			continue
+		}
+
+		versionInfo, err := install.GetInstalledVersion()
+		if err != nil {
+			return nil, nil, err
+		}
+		// Other tools may share the install directory
+		if versionInfo.Repo != i.repo {
+			continue
+		}
+		if latestInfo == nil || latestInfo.UpgradeAvailable(versionInfo.TagName) {
+			latest, latestInfo = install, versionInfo
+		}
+	}
+
+	return latest, latestInfo, nil
+}
+
+// installVersioned installs assetNames into the subdirectory of the install path named after
+// the installed version. Other installed versions are left in place, so an upgrade installs the
+// latest version next to them.
+func (i *Install) installVersioned(assetNames []string, version string, allowUpgrade bool) (*InstallOutcome, error) {
+	if version == "" {
+		current, versionInfo, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if current != nil && !allowUpgrade && !i.Force {
+			return &InstallOutcome{
+				Version:     versionInfo.TagName,
+				InstallPath: current.InstallPath,
+				AssetName:   assetNames[0],
+				AssetNames:  assetNames,
+				Action:      InstallActionNone,
+			}, nil
+		}
+
+		version, err = i.latestVersion()
+		if err != nil {
+			return nil, fmt.Errorf("error getting latest version: %w", err)
+		}
+	}
+
+	install, err := i.versionedInstall(version)
+	if err != nil {
+		return nil, err
+	}
+	return install.installAssets(assetNames, version, false)
+}
@@ -0,0 +1,95 @@
+package libfetch
+
+import (
+	"fmt"
+	"io"
+	"net/url"
+	"os"
+	"path/filepath"
+	"strings"
+)
+
+// InstallFile installs the asset at name, a file on the local disk, without any network access
+// and records version in the version file, or the file name when version is empty. Archives are
+// extracted like downloaded ones, other files are copied into the install path. When
+// Downloader.ExpectedSHA256 is set the file must match it. An existing installation of another
+// version, or of the same version when Force is set, is replaced.
+func (i *Install) InstallFile(name, version string) (*InstallOutcome, error) {
+	abs, err := filepath.Abs(name)
+	if err != nil {
+		return nil, err
+	}
+	info, err := os.Stat(abs)
+	if err != nil {
+		return nil, fmt.Errorf("error reading local asset: %w", err)
+	}
+	if !info.Mode().IsRegular() {
+		return nil, fmt.Errorf("local asset is not a regular file: %s", name)
+	}
+
+	checksum, err := fileSHA256(abs)
+	if err != nil {
+		return nil, fmt.Errorf("error hashing local asset: %w", err)
+	}
+	if expected := i.Downloader.ExpectedSHA256; expected != "" && !strings.EqualFold(checksum, expected) {
+		return nil, checksumMismatch(expected, checksum)
+	}
+
+	sourceURL := (&url.URL{Scheme: "file", Path: filepath.ToSlash(abs)}).String()
+	sum := func() string { return checksum }
+	return i.installSource(filepath.Base(abs), sourceURL, version, sum, func(dest string) ([]string, error) {
+		files, err := i.Downloader.extractFile(abs, dest)
+		if err != nil {
+			return nil, fmt.Errorf("error installing %s: %w", name, err)
+		}
+		return files, nil
+	})
+}
+
+// extractFile extracts the local archive at name into dest like a downloaded archive, or copies
+// name into dest if it is not an archive libfetch extracts, and returns the files written
+// relative to dest.
+func (f *Downloader) extractFile(name, dest string) ([]string, error) {
+	// Archive extensions are matched against URLs, so escape the characters URLs treat specially
+	if ext := f.archiveExtension(url.PathEscape(filepath.Base(name))); ext != "" {
+		return f.extractArchive(name, dest, ext)
+	}
+
+	if err := os.MkdirAll(dest, 0755); err != nil {
+		return nil, fmt.Errorf("failed to create destination directory: %w", err)
+	}
+	base := filepath.Base(name)
+	if f.OutputName != "" {
+		base = f.OutputName
+	}
+	if err := copyFile(name, filepath.Join(dest, base)); err != nil {
+		return nil, fmt.Errorf("failed to copy file: %w", err)
+	}
+	return []string{base}, nil
+}
+
+// copyFile copies the file source to target, keeping its permissions and modification time.
+func copyFile(source, target string) error {
+	in, err := os.Open(source)
+	if err != nil {
+		return err
+	}
+	defer in.Close()
+	info, err := in.Stat()
+	if err != nil {
+		return err
+	}
+
+	out, err := os.OpenFile(target, os.O_CREATE|os.O_WRONLY|os.O_TRUNC, info.Mode().Perm())
+	if err != nil {
+		return err
+	}
+	if _, err := io.Copy(out, in); err != nil {
+		out.Close()
+		return err
+	}
+	if err := out.Close(); err != nil {
+		return err
+	}
+	return os.Chtimes(target, info.ModTime(), info.ModTime())
+}
@@ -0,0 +1,23 @@
+package libfetch
+
+import "log/slog"
+
+// discardLogger is used when no Logger is set, so nothing is logged by default.
+var discardLogger = slog.New(slog.DiscardHandler)
+
+// logger returns Logger, or a logger that discards its records when Logger is nil.
+func (f *Downloader) logger() *slog.Logger {
+	if f.Logger == nil {
+		return discardLogger
+	}
+	return f.Logger
+}
+
+// logOutcome logs what an installation did.
+func (i *Install) logOutcome(outcome *InstallOutcome) {
+	if outcome.Action == InstallActionNone {
+		i.Downloader.logger().Info("already installed", "repo", i.repo, "version", outcome.Version, "path", outcome.InstallPath)
+		return
+	}
+	i.Downloader.logger().Info("installed", "repo", i.repo, "action", outcome.Action.String(), "version", outcome.Version, "path", outcome.InstallPath, "assets", outcome.AssetNames)
+}
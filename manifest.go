@@ -0,0 +1,255 @@
+package libfetch
+
+import (
+	"archive/tar"
+	"archive/zip"
+	"fmt"
+	"io"
+	"os"
+	"path"
+	"path/filepath"
+	"slices"
+)
+
+// ManifestDiff compares the files of an installation with those the latest version would
+// install, see Install.DiffLatest. File names are sorted, relative to the install path and use
+// forward slashes.
+type ManifestDiff struct {
+	// Current is the installed version.
+	Current string
+	// Target is the latest version the installation is compared with.
+	Target string
+	// Added lists the files only the latest version installs.
+	Added []string
+	// Removed lists the installed files the latest version no longer installs.
+	Removed []string
+	// Unchanged lists the files both versions install. Their contents are not compared.
+	Unchanged []string
+}
+
+// tarDecompressors maps the tarball extensions libfetch extracts itself to their decompressors.
+var tarDecompressors = map[string]tarDecompressor{
+	".tar.gz":  decompressGzip,
+	".tgz":     decompressGzip,
+	".tar.xz":  decompressXz,
+	".txz":     decompressXz,
+	".tar.bz2": decompressBzip2,
+	".tbz2":    decompressBzip2,
+}
+
+// DiffLatest compares the files recorded for the installation with the files the assets of the
+// latest version contain, e.g. to show what an upgrade changes before it is made. The assets are
+// downloaded to a temporary directory and archives are listed without extracting them, applying
+// StripComponents, ExtractPattern and Flatten like an extraction would, so only archive types
+// libfetch extracts itself can be compared. It returns ErrNotInstalled if nothing is installed.
+func (i *Install) DiffLatest() (*ManifestDiff, error) {
+	if i.VersionedLayout {
+		install, _, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if install == nil {
+			return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+		}
+		return install.DiffLatest()
+	}
+
+	if !i.alreadyInstalled() {
+		return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+	}
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if versionInfo.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+
+	latest, err := i.latestVersion()
+	if err != nil {
+		return nil, fmt.Errorf("error getting latest version: %w", err)
+	}
+	assetNames, err := i.upgradeAssetNames(versionInfo, nil, latest)
+	if err != nil {
+		return nil, err
+	}
+
+	spoolDir, err := os.MkdirTemp("", "libfetch-*")
+	if err != nil {
+		return nil, fmt.Errorf("failed to create temporary directory: %w", err)
+	}
+	defer os.RemoveAll(spoolDir)
+
+	var target []string
+	for n, assetName := range assetNames {
+		_, subdir, err := i.assetDest(i.InstallPath, assetName)
+		if err != nil {
+			return nil, err
+		}
+		files, err := i.Downloader.listAsset(assetName, latest, filepath.Join(spoolDir, fmt.Sprint(n)))
+		if err != nil {
+			return nil, fmt.Errorf("error listing asset %s: %w", assetName, err)
+		}
+		for _, file := range files {
+			target = append(target, path.Join(subdir, file))
+		}
+	}
+
+	current := i.installedFiles(versionInfo)
+	diff := &ManifestDiff{Current: versionInfo.TagName, Target: latest}
+	for _, file := range compactSorted(target) {
+		if slices.Contains(current, file) {
+			diff.Unchanged = append(diff.Unchanged, file)
+		} else {
+			diff.Added = append(diff.Added, file)
+		}
+	}
+	for _, file := range compactSorted(current) {
+		if !slices.Contains(target, file) {
+			diff.Removed = append(diff.Removed, file)
+		}
+	}
+	return diff, nil
+}
+
+// compactSorted returns a sorted copy of files without duplicates.
+func compactSorted(files []string) []string {
+	files = slices.Clone(files)
+	slices.Sort(files)
+	return slices.Compact(files)
+}
+
+// listAsset downloads assetName of the given release version into dir without extracting it and
+// returns the files its installation writes, relative to the destination.
+func (f *Downloader) listAsset(assetName, version, dir string) ([]string, error) {
+	assetURL, err := f.assetURL(assetName, version)
+	if err != nil {
+		return nil, err
+	}
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		return nil, fmt.Errorf("failed to create temporary directory: %w", err)
+	}
+	name, err := f.downloadUnextracted(f.context(), assetURL, dir, "")
+	if err != nil {
+		return nil, err
+	}
+
+	ext := f.archiveExtension(assetURL)
+	if ext == "" && f.DetectArchiveByMagic && !f.isArchive(assetURL) {
+		if ext, err = sniffArchive(name); err != nil {
+			return nil, fmt.Errorf("failed to inspect download: %w", err)
+		}
+	}
+	if ext == "" {
+		if f.isArchive(assetURL) {
+			return nil, fmt.Errorf("the contents of %s archives cannot be listed", assetName)
+		}
+		if f.OutputName != "" {
+			return []string{f.OutputName}, nil
+		}
+		return []string{filepath.Base(name)}, nil
+	}
+
+	files, err := f.listArchive(name, ext)
+	if err != nil {
+		return nil, err
+	}
+	if f.KeepArchive {
+		files = append(files, filepath.Base(name))
+	}
+	return files, nil
+}
+
+// listArchive returns the files the extraction of the archive at name, of type ext, writes,
+// relative to the destination and using forward slashes, without extracting it.
+func (f *Downloader) listArchive(name, ext string) ([]string, error) {
+	if _, ok := f.extractor(ext).(builtinExtractor); !ok {
+		return nil, fmt.Errorf("the contents of %s archives extracted by a registered extractor cannot be listed", ext)
+	}
+	opts := &extractOptions{
+		strip:   f.StripComponents,
+		pattern: f.ExtractPattern,
+		flatten: f.Flatten,
+	}
+
+	if ext == ".zip" {
+		zr, err := zip.OpenReader(name)
+		if err != nil {
+			return nil, fmt.Errorf("failed to open zip archive: %w", err)
+		}
+		defer zr.Close()
+
+		var files []string
+		for _, file := range zr.File {
+			if !file.FileInfo().IsDir() {
+				files, err = opts.listEntry(files, file.Name)
+				if err != nil {
+					return nil, err
+				}
+			}
+		}
+		return files, nil
+	}
+
+	decompress := tarDecompressors[ext]
+	file, err := os.Open(name)
+	if err != nil {
+		return nil, fmt.Errorf("failed to open downloaded file: %w", err)
+	}
+	defer file.Close()
+	r, err := decompress(file)
+	if err != nil {
+		return nil, fmt.Errorf("failed to create decompressor: %w", err)
+	}
+
+	if opts.strip < 0 {
+		topLevelDir, err := hasTopLevelDir(r)
+		if err != nil {
+			return nil, err
+		}
+		opts.strip = 0
+		if topLevelDir {
+			opts.strip = 1
+		}
+		if _, err := file.Seek(0, io.SeekStart); err != nil {
+			return nil, fmt.Errorf("failed to rewind downloaded file: %w", err)
+		}
+		if r, err = decompress(file); err != nil {
+			return nil, fmt.Errorf("failed to create decompressor: %w", err)
+		}
+	}
+
+	// Only the headers are read, the tar reader skips the file contents
+	tr := tar.NewReader(r)
+	var files []string
+	for {
+		header, err := tr.Next()
+		if err == io.EOF {
+			break
+		}
+		if err != nil {
+			return nil, fmt.Errorf("failed to read tar header: %w", err)
+		}
+		switch header.Typeflag {
+		case tar.TypeReg, tar.TypeLink, tar.TypeSymlink:
+			if files, err = opts.listEntry(files, header.Name); err != nil {
+				return nil, err
+			}
+		}
+	}
+	return files, nil
+}
+
+// listEntry appends the name the file entry of an archive is extracted under to files, unless
+// the entry is skipped.
+func (o *extractOptions) listEntry(files []string, entry string) ([]string, error) {
+	name := stripComponents(entry, o.strip)
+	if name == "" {
+		return files, nil
+	}
+	name, err := o.entryName(name, false)
+	if err != nil || name == "" {
+		return files, err
+	}
+	return append(files, path.Clean(name)), nil
+}
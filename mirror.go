@@ -0,0 +1,73 @@
+package libfetch
+
+import (
+	"context"
+	"strings"
+)
+
+// mirrorURLs returns the URLs of the same asset as src below each of Mirrors, in order. URLs that
+// do not lie below the download base URL, such as assets of a custom URL, have no mirrors.
+func (f *Downloader) mirrorURLs(src string) []string {
+	rest, ok := strings.CutPrefix(src, f.downloadBaseURL()+"/")
+	if !ok {
+		return nil
+	}
+	urls := make([]string, 0, len(f.Mirrors))
+	for _, mirror := range f.Mirrors {
+		urls = append(urls, strings.TrimSuffix(mirror, "/")+"/"+rest)
+	}
+	return urls
+}
+
+// withMirrors runs attempt, which requests src with the given downloader, with retries like
+// withRetry. When src keeps failing with an error a retry could fix, such as a network failure or
+// a 5xx response, the same asset is requested from each of Mirrors in turn, without Token and
+// Header, and the mirror that serves it is reported as an EventMirror. The error of src is
+// returned when every mirror fails too.
+func (f *Downloader) withMirrors(ctx context.Context, src string, attempt func(f *Downloader, src string) error) error {
+	err := f.withRetry(ctx, src, func() error {
+		return attempt(f, src)
+	})
+	if err == nil || !isRetryable(ctx, err) {
+		return err
+	}
+
+	urls := f.mirrorURLs(src)
+	if len(urls) > 0 {
+		f.logger().Warn("download failed, trying mirrors", "url", src, "mirrors", len(urls), "error", err)
+	}
+
+	// The token and extra headers are for GitHub, mirrors are not trusted with them
+	mirror := *f
+	mirror.Token = ""
+	mirror.Header = nil
+	for _, mirrorURL := range urls {
+		mirrorErr := mirror.withRetry(ctx, mirrorURL, func() error {
+			return attempt(&mirror, mirrorURL)
+		})
+		if mirrorErr == nil {
+			f.logger().Info("downloaded from mirror", "url", mirrorURL)
+			f.reportMirror(ctx, mirrorURL, err)
+			return nil
+		}
+		if ctx.Err() != nil {
+			return mirrorErr
+		}
+		f.logger().Warn("mirror failed", "url", mirrorURL, "error", mirrorErr)
+	}
+	return err
+}
+
+// reportMirror sends an EventMirror to ProgressHandler after mirrorURL served a download
+// requested with ctx whose primary URL failed with err.
+func (f *Downloader) reportMirror(ctx context.Context, mirrorURL string, err error) {
+	if f.ProgressHandler == nil {
+		return
+	}
+	f.ProgressHandler(ProgressEvent{
+		Kind: EventMirror,
+		URL:  mirrorURL,
+		Dest: destinationFrom(ctx),
+		Err:  err,
+	})
+}
@@ -0,0 +1,107 @@
+package libfetch
+
+import (
+	"fmt"
+	"os"
+	"path/filepath"
+	"slices"
+	"strings"
+)
+
+// UpgradePlan describes what upgrading an installation to the latest version would change,
+// see Install.UpgradePlan.
+type UpgradePlan struct {
+	// Current is the installed version.
+	Current string
+	// Target is the latest version, which the upgrade installs.
+	Target string
+	// UpgradeAvailable reports that Target is newer than Current. Nothing is removed or added
+	// when it is not.
+	UpgradeAvailable bool
+	// Assets are the assets of Target the upgrade downloads.
+	Assets []string
+	// Remove lists the installed files the upgrade deletes, relative to the install path and using
+	// forward slashes. When KeepVersions is set they are retained for Rollback instead.
+	Remove []string
+	// Add lists the assets of Target, as named in the asset list of its release, that will be
+	// downloaded. Archives among them are extracted, so the files they contain are only known once
+	// they are downloaded. It is nil when the asset list of the release cannot be fetched.
+	Add []string
+}
+
+// UpgradePlan reports which files an upgrade to the latest version would remove and which assets
+// it would add, without changing anything, e.g. to ask for confirmation before upgrading. It
+// returns ErrNotInstalled if nothing is installed.
+func (i *Install) UpgradePlan() (*UpgradePlan, error) {
+	if i.VersionedLayout {
+		install, _, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if install == nil {
+			return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+		}
+		plan, err := install.UpgradePlan()
+		if err != nil {
+			return nil, err
+		}
+		// Versions are installed side by side, an upgrade removes nothing
+		plan.Remove = nil
+		return plan, nil
+	}
+
+	if !i.alreadyInstalled() {
+		return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+	}
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if versionInfo.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+
+	latest, err := i.latestVersion()
+	if err != nil {
+		return nil, fmt.Errorf("error getting latest version: %w", err)
+	}
+	plan := &UpgradePlan{Current: versionInfo.TagName, Target: latest, UpgradeAvailable: versionInfo.UpgradeAvailable(latest)}
+	if !plan.UpgradeAvailable {
+		return plan, nil
+	}
+
+	if plan.Assets, err = i.upgradeAssetNames(versionInfo, nil, latest); err != nil {
+		return nil, err
+	}
+	plan.Remove = i.installedFiles(versionInfo)
+
+	if assets, err := i.Downloader.GetReleaseAssetInfo(latest); err == nil {
+		plan.Add = []string{}
+		for _, name := range plan.Assets {
+			k := slices.IndexFunc(assets, func(asset AssetInfo) bool {
+				return asset.Name == name || i.Downloader.CaseInsensitiveAssets && strings.EqualFold(asset.Name, name)
+			})
+			if k < 0 {
+				return nil, noMatchingAsset(name, assetNamesOf(assets))
+			}
+			plan.Add = append(plan.Add, assets[k].Name)
+		}
+	}
+	return plan, nil
+}
+
+// installedFiles returns the files of the installation described by versionInfo that exist,
+// relative to the install path and using forward slashes. Installations that predate file
+// tracking are replaced as a whole, so every file of the install path but the version file is
+// returned for them.
+func (i *Install) installedFiles(versionInfo *VersionInfo) []string {
+	if versionInfo.Files == nil {
+		return slices.DeleteFunc(listFiles(i.InstallPath), func(file string) bool {
+			return file == filepath.ToSlash(i.versionFile)
+		})
+	}
+	return slices.DeleteFunc(slices.Clone(versionInfo.Files), func(file string) bool {
+		_, err := os.Lstat(filepath.Join(i.InstallPath, filepath.FromSlash(file)))
+		return err != nil
+	})
+}
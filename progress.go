@@ -1,6 +1,7 @@
 package libfetch
 
 import (
+	"context"
 	"fmt"
 	"io"
 	"time"
@@ -8,32 +9,173 @@ import (
 	getter "github.com/hashicorp/go-getter"
 )
 
+// ProgressEventKind identifies what a ProgressEvent reports.
+type ProgressEventKind int
+
+const (
+	// EventStarted is sent when a download starts.
+	EventStarted ProgressEventKind = iota
+	// EventProgress is sent periodically while a download is running.
+	EventProgress
+	// EventCompleted is sent when a download finishes.
+	EventCompleted
+	// EventExtractStarted is sent before the entries of an archive are extracted.
+	EventExtractStarted
+	// EventExtractProgress is sent after every extracted archive entry.
+	EventExtractProgress
+	// EventExtractCompleted is sent when the extraction of an archive finishes.
+	EventExtractCompleted
+	// EventCollision is sent when a file overwrites a file written before by the same archive or
+	// by another asset of the same installation. EntryName is the path of the file relative to the
+	// destination. It is not sent when collisions fail the download, see Downloader.FailOnCollision.
+	EventCollision
+	// EventRetrying is sent when a failed request is about to be retried. Attempt, MaxAttempts,
+	// Delay and Err describe the retry.
+	EventRetrying
+	// EventMirror is sent when a download that failed on its primary URL was served by a mirror,
+	// see Downloader.Mirrors. URL is the URL of the mirror and Err the error of the primary URL.
+	EventMirror
+)
+
+// ProgressEvent describes the progress of a download or of an archive extraction.
+type ProgressEvent struct {
+	// Kind is what the event reports.
+	Kind ProgressEventKind
+	// URL identifies the download, by its URL or by the file name being written. It is set for
+	// download events.
+	URL string
+	// Dest is the directory the asset is downloaded or extracted to, telling apart the events of
+	// concurrent installations into different directories. It is set for the download, retry,
+	// extraction and collision events of asset downloads; download events only carry it when
+	// they are reported by a tracker created with NewProgressTracker.
+	Dest string
+	// Downloaded is the number of bytes downloaded so far, including the bytes of a resumed download.
+	Downloaded int64
+	// Total is the size of the download in bytes, or -1 when the server did not report it.
+	Total int64
+	// Speed is the download speed in MiB/s.
+	Speed float64
+	// ETA is the estimated time until the download completes, or -1 when it cannot be estimated
+	// because the total size or the speed is not known yet.
+	ETA time.Duration
+	// EntriesDone is the number of archive entries extracted so far.
+	EntriesDone int
+	// EntriesTotal is the number of entries in the archive, 0 when the archive format does not
+	// know it up front.
+	EntriesTotal int
+	// EntryName is the name of the archive entry that was just extracted.
+	EntryName string
+	// Attempt is the number of the attempt that is about to be made, starting at 2 for the first
+	// retry, out of MaxAttempts.
+	Attempt     int
+	MaxAttempts int
+	// Delay is how long is waited before the next attempt.
+	Delay time.Duration
+	// Err is the error the previous attempt failed with.
+	Err error
+}
+
+// ProgressHandler receives progress events. Concurrent downloads call it from several goroutines
+// at the same time.
+type ProgressHandler func(event ProgressEvent)
+
+// NewProgressTracker returns a ProgressTracker that reports download progress to handler.
+func NewProgressTracker(handler ProgressHandler) getter.ProgressTracker {
+	return &progressReader{
+		handler:  handler,
+		interval: defaultProgressInterval,
+	}
+}
+
 // DefaultProgressTracker returns the default ProgressTracker that prints download progress to stdout.
 func DefaultProgressTracker() getter.ProgressTracker {
-	progFunc := func(src string, currentSize int64, totalSize int64, mibPerSec float64, complete bool) {
-		fmt.Printf("\r\x1b[Kdownloading %s... %d MiB of %d MiB (%.2f MiB/s)", src, currentSize/(1024*1024), totalSize/(1024*1024), mibPerSec)
-		if complete {
-			fmt.Println()
+	return NewProgressTracker(func(event ProgressEvent) {
+		if event.Kind != EventProgress && event.Kind != EventCompleted {
+			return
+		}
+		if event.Total < 0 {
+			fmt.Printf("\r\x1b[Kdownloading %s... %d MiB downloaded (unknown total) (%.2f MiB/s)", event.URL, event.Downloaded/(1024*1024), event.Speed)
+			if event.Kind == EventCompleted {
+				fmt.Println()
+			}
+			return
 		}
+		fmt.Printf("\r\x1b[Kdownloading %s... %d MiB of %d MiB (%.2f MiB/s", event.URL, event.Downloaded/(1024*1024), event.Total/(1024*1024), event.Speed)
+		if event.Kind == EventCompleted {
+			fmt.Println(")")
+			return
+		}
+		if event.ETA >= 0 {
+			fmt.Printf(", ETA %s", event.ETA)
+		}
+		fmt.Print(")")
+	})
+}
+
+// ExtractProgressFunc reports archive extraction progress. It is called after every entry with the
+// number of entries extracted so far, the total number of entries (0 when the archive format does
+// not know it up front) and the entry name, then once more with complete set when extraction finishes.
+//
+// Deprecated: use a ProgressHandler, which receives the same information as extraction events.
+type ExtractProgressFunc func(entriesDone, entriesTotal int, entryName string, complete bool)
+
+// extractHandler returns a ProgressHandler forwarding events to handler and extraction events to
+// the legacy callback extractProgress. Either may be nil.
+func extractHandler(handler ProgressHandler, extractProgress ExtractProgressFunc) ProgressHandler {
+	if extractProgress == nil {
+		return handler
 	}
 
-	pr := progressReader{
-		progress: progFunc,
+	return func(event ProgressEvent) {
+		if handler != nil {
+			handler(event)
+		}
+		switch event.Kind {
+		case EventExtractProgress:
+			extractProgress(event.EntriesDone, event.EntriesTotal, event.EntryName, false)
+		case EventExtractCompleted:
+			extractProgress(event.EntriesDone, event.EntriesTotal, "", true)
+		}
 	}
+}
+
+// destinationKey is the context key of the destination of the downloads made with a context.
+type destinationKey struct{}
 
-	return getter.ProgressTracker(&pr)
+// withDestination returns a context whose downloads report dest as their destination.
+func withDestination(ctx context.Context, dest string) context.Context {
+	return context.WithValue(ctx, destinationKey{}, dest)
 }
 
-type progressFunc func(src string, currentSize int64, totalSize int64, mibPerSec float64, complete bool)
+// destinationFrom returns the destination of the downloads made with ctx, or an empty string.
+func destinationFrom(ctx context.Context) string {
+	dest, _ := ctx.Value(destinationKey{}).(string)
+	return dest
+}
+
+// destinationHandler returns a ProgressHandler forwarding events to handler with their Dest set
+// to dest. It returns nil when handler is nil.
+func destinationHandler(handler ProgressHandler, dest string) ProgressHandler {
+	if handler == nil {
+		return nil
+	}
+	return func(event ProgressEvent) {
+		event.Dest = dest
+		handler(event)
+	}
+}
 
 type progressReader struct {
-	src          string
-	currentSize  int64
-	totalSize    int64
-	lastReported int64
-	startTime    time.Time
-	reader       io.ReadCloser
-	progress     progressFunc
+	src         string
+	dest        string
+	startSize   int64
+	currentSize int64
+	totalSize   int64
+	lastReport  time.Time
+	interval    time.Duration
+	startTime   time.Time
+	reader      io.ReadCloser
+	handler     ProgressHandler
 }
 
 func (pr *progressReader) TrackProgress(src string, currentSize, totalSize int64, stream io.ReadCloser) io.ReadCloser {
@@ -41,45 +183,103 @@ func (pr *progressReader) TrackProgress(src string, currentSize, totalSize int64
 		return nil
 	}
 
-	pr.src = src
-	pr.currentSize = currentSize
-	pr.totalSize = totalSize
-	pr.startTime = time.Now()
-	pr.reader = stream
+	// Servers that do not send a Content-Length report a negative size
+	if totalSize < 0 {
+		totalSize = -1
+	}
 
-	return pr
+	// Track every download with its own reader, so concurrent downloads do not share state
+	r := &progressReader{
+		src:         src,
+		dest:        pr.dest,
+		startSize:   currentSize,
+		currentSize: currentSize,
+		totalSize:   totalSize,
+		interval:    pr.interval,
+		startTime:   time.Now(),
+		reader:      stream,
+		handler:     pr.handler,
+	}
+	r.report(EventStarted)
+	return r
 }
 
 const (
-	mib    = 1024 * 1024
-	mib100 = mib * 100
+	mib = 1024 * 1024
+
+	// defaultProgressInterval is the minimum time between two progress reports of a download.
+	defaultProgressInterval = 500 * time.Millisecond
 )
 
+// withDest returns a tracker like pr whose downloads report dest as their destination.
+func (pr *progressReader) withDest(dest string) *progressReader {
+	return &progressReader{dest: dest, interval: pr.interval, handler: pr.handler}
+}
+
+// setProgressInterval changes the minimum time between progress reports of pt, if pt is a
+// tracker created by DefaultProgressTracker or NewProgressTracker. It reports whether the
+// interval was applied.
+func setProgressInterval(pt getter.ProgressTracker, interval time.Duration) bool {
+	pr, ok := pt.(*progressReader)
+	if ok {
+		pr.interval = interval
+	}
+	return ok
+}
+
 func (pr *progressReader) Read(p []byte) (int, error) {
 	n, err := pr.reader.Read(p)
 	pr.currentSize += int64(n)
 
-	if pr.progress != nil && pr.currentSize-pr.lastReported >= mib100 {
-		pr.lastReported = pr.currentSize
-		pr.progress(pr.src, pr.currentSize, pr.totalSize, pr.mibPerSec(), false)
+	if time.Since(pr.lastReport) >= pr.interval {
+		pr.lastReport = time.Now()
+		pr.report(EventProgress)
 	}
 
 	return n, err
 }
 
 func (pr *progressReader) Close() error {
-	if pr.progress != nil {
-		pr.progress(pr.src, pr.currentSize, pr.totalSize, pr.mibPerSec(), true)
-	}
+	pr.report(EventCompleted)
 
 	return pr.reader.Close()
 }
 
+// report sends a download event of the given kind to the handler, if any.
+func (pr *progressReader) report(kind ProgressEventKind) {
+	if pr.handler == nil {
+		return
+	}
+
+	pr.handler(ProgressEvent{
+		Kind:       kind,
+		URL:        pr.src,
+		Dest:       pr.dest,
+		Downloaded: pr.currentSize,
+		Total:      pr.totalSize,
+		Speed:      pr.mibPerSec(),
+		ETA:        pr.eta(),
+	})
+}
+
+// mibPerSec returns the speed of the download so far, not counting bytes of a resumed download.
 func (pr *progressReader) mibPerSec() float64 {
 	elapsed := time.Since(pr.startTime).Seconds()
 	if elapsed == 0 {
 		return 0
 	}
 
-	return float64(pr.currentSize) / mib / elapsed
+	return float64(pr.currentSize-pr.startSize) / mib / elapsed
+}
+
+// eta estimates the time remaining as (total - downloaded) / speed, or returns -1 when the total
+// size or the speed is unknown.
+func (pr *progressReader) eta() time.Duration {
+	speed := pr.mibPerSec() * mib
+	if pr.totalSize <= 0 || speed <= 0 {
+		return -1
+	}
+
+	remaining := float64(max(pr.totalSize-pr.currentSize, 0)) / speed
+	return time.Duration(remaining * float64(time.Second)).Round(time.Second)
 }
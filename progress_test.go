@@ -0,0 +1,24 @@
+package libfetch
+
+import (
+	"testing"
+	"time"
+)
+
+func TestProgressReaderETA(t *testing.T) {
+	pr := &progressReader{
+		// A resumed download: the first MiB was downloaded before, the second during the last second
+		startSize:   mib,
+		currentSize: 2 * mib,
+		totalSize:   4 * mib,
+		startTime:   time.Now().Add(-time.Second),
+	}
+	if eta := pr.eta(); eta != 2*time.Second {
+		t.Errorf("eta() = %v, want 2s", eta)
+	}
+
+	pr.totalSize = 0
+	if eta := pr.eta(); eta != -1 {
+		t.Errorf("eta() with an unknown total = %v, want -1", eta)
+	}
+}
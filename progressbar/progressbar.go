@@ -0,0 +1,162 @@
+// Package progressbar renders libfetch download progress as a progress bar on a terminal.
+//
+// It lives in its own package so programs that do not use it do not carry it.
+package progressbar
+
+import (
+	"fmt"
+	"io"
+	"os"
+	"slices"
+	"strings"
+	"sync"
+
+	"github.com/Cyberhan123/libfetch"
+	"github.com/hashicorp/go-getter"
+)
+
+// width is the number of cells of the bar itself.
+const width = 30
+
+// Tracker returns a ProgressTracker that draws download progress bars on stderr.
+func Tracker() getter.ProgressTracker {
+	return libfetch.NewProgressTracker(New(os.Stderr))
+}
+
+// New returns a ProgressHandler that draws a progress bar for every download to w, showing the
+// downloaded and total size, the speed and the estimated time remaining. A bar is created by the
+// first event of a download and finished by its completion event. Retries are announced on a
+// line of their own, extraction events are ignored.
+func New(w io.Writer) libfetch.ProgressHandler {
+	b := &bars{w: w, started: make(map[string]bool)}
+	return b.handle
+}
+
+// bars tracks the downloads that have a bar on screen.
+type bars struct {
+	mu      sync.Mutex
+	w       io.Writer
+	started map[string]bool
+}
+
+func (b *bars) handle(event libfetch.ProgressEvent) {
+	b.mu.Lock()
+	defer b.mu.Unlock()
+
+	switch event.Kind {
+	case libfetch.EventStarted, libfetch.EventProgress:
+		b.started[event.URL] = true
+		fmt.Fprint(b.w, "\r\x1b[K"+render(event))
+	case libfetch.EventCompleted:
+		// Only finish bars that were drawn
+		if b.started[event.URL] {
+			delete(b.started, event.URL)
+			fmt.Fprintln(b.w, "\r\x1b[K"+render(event))
+		}
+	case libfetch.EventRetrying:
+		// The retry starts a new bar
+		delete(b.started, event.URL)
+		fmt.Fprintf(b.w, "\r\x1b[K%s attempt %d/%d in %s after %v\n", event.URL, event.Attempt, event.MaxAttempts, event.Delay, event.Err)
+	}
+}
+
+// MultiTracker returns a ProgressTracker that draws the progress of concurrent downloads on
+// stderr, one line per download.
+func MultiTracker() getter.ProgressTracker {
+	return libfetch.NewProgressTracker(NewMulti(os.Stderr))
+}
+
+// NewMulti returns a ProgressHandler that draws one line per running download to w, like the bar
+// drawn by New. The lines stay in place while concurrent downloads progress, as the display is
+// redrawn with cursor movements; completed downloads are removed from it. Extraction events are
+// ignored.
+func NewMulti(w io.Writer) libfetch.ProgressHandler {
+	m := &multi{w: w, events: make(map[string]libfetch.ProgressEvent)}
+	return m.handle
+}
+
+// multi tracks the downloads shown by a multi-line display.
+type multi struct {
+	mu sync.Mutex
+	w  io.Writer
+	// order lists the URLs of the running downloads in the order they started.
+	order []string
+	// events holds the last event of every running download.
+	events map[string]libfetch.ProgressEvent
+	// drawn is the number of lines on screen, the cursor is at the start of the line below them.
+	drawn int
+}
+
+func (m *multi) handle(event libfetch.ProgressEvent) {
+	m.mu.Lock()
+	defer m.mu.Unlock()
+
+	switch event.Kind {
+	case libfetch.EventStarted, libfetch.EventProgress:
+		if _, ok := m.events[event.URL]; !ok {
+			m.order = append(m.order, event.URL)
+		}
+		m.events[event.URL] = event
+	case libfetch.EventCompleted, libfetch.EventRetrying:
+		if _, ok := m.events[event.URL]; !ok {
+			return
+		}
+		delete(m.events, event.URL)
+		m.order = slices.DeleteFunc(m.order, func(url string) bool { return url == event.URL })
+	default:
+		return
+	}
+	m.redraw()
+}
+
+// redraw replaces the lines on screen with one line per running download.
+func (m *multi) redraw() {
+	var sb strings.Builder
+	if m.drawn > 0 {
+		fmt.Fprintf(&sb, "\x1b[%dA", m.drawn)
+	}
+	for _, url := range m.order {
+		sb.WriteString("\r\x1b[K" + render(m.events[url]) + "\n")
+	}
+	// Clear the lines of downloads that completed and return to below the remaining lines
+	if cleared := m.drawn - len(m.order); cleared > 0 {
+		sb.WriteString(strings.Repeat("\r\x1b[K\n", cleared))
+		fmt.Fprintf(&sb, "\x1b[%dA", cleared)
+	}
+	m.drawn = len(m.order)
+	fmt.Fprint(m.w, sb.String())
+}
+
+// render formats a single progress line for event.
+func render(event libfetch.ProgressEvent) string {
+	var sb strings.Builder
+	sb.WriteString(event.URL)
+
+	if event.Total > 0 {
+		filled := int(min(event.Downloaded, event.Total) * width / event.Total)
+		fmt.Fprintf(&sb, " [%s%s] %s / %s", strings.Repeat("=", filled), strings.Repeat(" ", width-filled), formatBytes(event.Downloaded), formatBytes(event.Total))
+	} else {
+		fmt.Fprintf(&sb, " %s (unknown total)", formatBytes(event.Downloaded))
+	}
+	fmt.Fprintf(&sb, "  %.2f MiB/s", event.Speed)
+
+	if event.Kind != libfetch.EventCompleted && event.ETA >= 0 {
+		fmt.Fprintf(&sb, "  ETA %s", event.ETA)
+	}
+	return sb.String()
+}
+
+// formatBytes formats n bytes with a binary unit, e.g. "12.3 MiB".
+func formatBytes(n int64) string {
+	const unit = 1024
+	if n < unit {
+		return fmt.Sprintf("%d B", n)
+	}
+
+	div, exp := int64(unit), 0
+	for m := n / unit; m >= unit; m /= unit {
+		div *= unit
+		exp++
+	}
+	return fmt.Sprintf("%.1f %ciB", float64(n)/float64(div), "KMGTPE"[exp])
+}
@@ -0,0 +1,84 @@
+package progressbar
+
+import (
+	"bytes"
+	"errors"
+	"strings"
+	"testing"
+	"time"
+
+	"github.com/Cyberhan123/libfetch"
+)
+
+func TestNewDrawsAndFinishesBar(t *testing.T) {
+	var buf bytes.Buffer
+	handler := New(&buf)
+
+	const total = 4 * 1024 * 1024
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventStarted, URL: "tool.zip", Total: total, ETA: -1})
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventProgress, URL: "tool.zip", Downloaded: total / 2, Total: total, Speed: 1, ETA: 2 * time.Second})
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventCompleted, URL: "tool.zip", Downloaded: total, Total: total, Speed: 1})
+	// Completion of a download without a bar draws nothing
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventCompleted, URL: "other.zip"})
+
+	lines := strings.Split(buf.String(), "\r\x1b[K")
+	if len(lines) != 4 {
+		t.Fatalf("expected 3 redraws, got %q", buf.String())
+	}
+	if want := "tool.zip [===============               ] 2.0 MiB / 4.0 MiB  1.00 MiB/s  ETA 2s"; lines[2] != want {
+		t.Errorf("progress line = %q, want %q", lines[2], want)
+	}
+	if !strings.HasSuffix(lines[3], "4.0 MiB / 4.0 MiB  1.00 MiB/s\n") {
+		t.Errorf("bar was not finished: %q", lines[3])
+	}
+}
+
+func TestFormatBytes(t *testing.T) {
+	tests := map[int64]string{
+		512:                    "512 B",
+		1536:                   "1.5 KiB",
+		5 * 1024 * 1024:        "5.0 MiB",
+		3 * 1024 * 1024 * 1024: "3.0 GiB",
+	}
+	for n, want := range tests {
+		if got := formatBytes(n); got != want {
+			t.Errorf("formatBytes(%d) = %q, want %q", n, got, want)
+		}
+	}
+}
+
+func TestNewAnnouncesRetries(t *testing.T) {
+	var buf bytes.Buffer
+	handler := New(&buf)
+
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventStarted, URL: "tool.zip", Total: -1, ETA: -1})
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventRetrying, URL: "tool.zip", Attempt: 2, MaxAttempts: 3, Delay: 3 * time.Second, Err: errors.New("connection reset")})
+
+	if want := "\r\x1b[Ktool.zip attempt 2/3 in 3s after connection reset\n"; !strings.HasSuffix(buf.String(), want) {
+		t.Errorf("output = %q, want it to end with %q", buf.String(), want)
+	}
+}
+
+func TestNewMultiKeepsLinesInPlace(t *testing.T) {
+	var buf bytes.Buffer
+	handler := NewMulti(&buf)
+
+	a := libfetch.ProgressEvent{Kind: libfetch.EventStarted, URL: "a.zip", Total: -1, ETA: -1}
+	b := libfetch.ProgressEvent{Kind: libfetch.EventStarted, URL: "b.zip", Total: -1, ETA: -1}
+	handler(a)
+	handler(b)
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventCompleted, URL: "a.zip"})
+	handler(libfetch.ProgressEvent{Kind: libfetch.EventCompleted, URL: "b.zip"})
+
+	line := func(event libfetch.ProgressEvent) string {
+		return "\r\x1b[K" + render(event) + "\n"
+	}
+	want := line(a) +
+		"\x1b[1A" + line(a) + line(b) +
+		// a completed: b moves up and the second line is cleared
+		"\x1b[2A" + line(b) + "\r\x1b[K\n" + "\x1b[1A" +
+		"\x1b[1A" + "\r\x1b[K\n" + "\x1b[1A"
+	if buf.String() != want {
+		t.Errorf("output = %q, want %q", buf.String(), want)
+	}
+}
@@ -0,0 +1,56 @@
+package libfetch
+
+import (
+	"fmt"
+	"net/http"
+	"net/url"
+	"strings"
+
+	"golang.org/x/net/http/httpproxy"
+)
+
+// supportedProxySchemes are the proxy URL schemes understood by net/http. Host names are resolved
+// by the proxy for both SOCKS5 schemes.
+var supportedProxySchemes = []string{"http", "https", "socks5", "socks5h"}
+
+// parseProxy parses a proxy URL such as "http://proxy:8080" or "socks5h://proxy:1080".
+// A proxy without a scheme is an HTTP proxy.
+func parseProxy(proxy string) (*url.URL, error) {
+	if !strings.Contains(proxy, "://") {
+		proxy = "http://" + proxy
+	}
+
+	proxyURL, err := url.Parse(proxy)
+	if err != nil {
+		return nil, fmt.Errorf("%w: %w", ErrInvalidProxy, err)
+	}
+	for _, scheme := range supportedProxySchemes {
+		if strings.EqualFold(proxyURL.Scheme, scheme) {
+			proxyURL.Scheme = scheme
+			return proxyURL, nil
+		}
+	}
+	return nil, fmt.Errorf("%w: unsupported scheme %q, use one of %s", ErrInvalidProxy, proxyURL.Scheme, strings.Join(supportedProxySchemes, ", "))
+}
+
+// proxyFunc returns the Transport.Proxy function for proxy. Hosts matching noProxy, a comma
+// separated list in the format of the NO_PROXY environment variable, and localhost are connected
+// to directly. An invalid proxy fails every request instead of silently connecting directly.
+func proxyFunc(proxy, noProxy string) func(*http.Request) (*url.URL, error) {
+	proxyURL, err := parseProxy(proxy)
+	if err != nil {
+		return func(*http.Request) (*url.URL, error) {
+			return nil, err
+		}
+	}
+
+	config := httpproxy.Config{
+		HTTPProxy:  proxyURL.String(),
+		HTTPSProxy: proxyURL.String(),
+		NoProxy:    noProxy,
+	}
+	proxyForURL := config.ProxyFunc()
+	return func(req *http.Request) (*url.URL, error) {
+		return proxyForURL(req.URL)
+	}
+}
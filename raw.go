@@ -0,0 +1,225 @@
+package libfetch
+
+import (
+	"bufio"
+	"context"
+	"crypto/sha256"
+	"encoding/hex"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"os"
+	"path"
+	"path/filepath"
+	"strings"
+
+	"github.com/hashicorp/go-getter"
+)
+
+// isArchive reports whether src names a file that libfetch or go-getter would decompress.
+func (f *Downloader) isArchive(src string) bool {
+	if f.archiveExtension(src) != "" {
+		return true
+	}
+
+	name := urlPath(src)
+	for ext := range getter.Decompressors {
+		if strings.HasSuffix(name, "."+ext) {
+			return true
+		}
+	}
+	return false
+}
+
+// downloadUnextracted downloads src into dir with retries without extracting it, and returns the
+// path of the downloaded file. The file is named after src regardless of OutputName, so the
+// archive type can be told from its name.
+func (f *Downloader) downloadUnextracted(ctx context.Context, src, dir, checksum string) (string, error) {
+	u, err := url.Parse(src)
+	if err != nil {
+		return "", fmt.Errorf("invalid download url: %w", err)
+	}
+
+	var downloaded []string
+	err = f.withMirrors(ctx, src, func(f *Downloader, src string) error {
+		var err error
+		downloaded, err = f.downloadRaw(ctx, src, dir, checksum)
+		return err
+	})
+	if err != nil {
+		if ctx.Err() != nil {
+			return "", cancelledError(ctx)
+		}
+		return "", f.tokenScopeError(err)
+	}
+
+	name := filepath.Join(dir, path.Base(u.Path))
+	if err := os.Rename(filepath.Join(dir, downloaded[0]), name); err != nil {
+		return "", fmt.Errorf("failed to move download: %w", err)
+	}
+	return name, nil
+}
+
+// downloadRaw downloads src into dest without extracting it. The data is written to
+// "<filename>.part" first; if that file already exists the download resumes from its end using
+// an HTTP Range request. The part file is renamed to its final name once it is complete.
+// It returns the name of the downloaded file.
+func (f *Downloader) downloadRaw(ctx context.Context, src, dest, checksum string) ([]string, error) {
+	u, err := url.Parse(src)
+	if err != nil {
+		return nil, fmt.Errorf("invalid download url: %w", err)
+	}
+
+	if err := os.MkdirAll(dest, 0755); err != nil {
+		return nil, fmt.Errorf("failed to create destination directory: %w", err)
+	}
+
+	name := path.Base(u.Path)
+	if f.OutputName != "" {
+		name = f.OutputName
+	}
+	target := filepath.Join(dest, name)
+	partFile := target + ".part"
+
+	var offset int64
+	if fi, err := os.Stat(partFile); err == nil {
+		offset = fi.Size()
+	}
+
+	req, err := http.NewRequestWithContext(ctx, "GET", src, nil)
+	if err != nil {
+		return nil, err
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+	if offset > 0 {
+		req.Header.Set("Range", fmt.Sprintf("bytes=%d-", offset))
+	}
+
+	resp, err := f.createDownloadClient().Do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+
+	flags := os.O_CREATE | os.O_WRONLY
+	switch resp.StatusCode {
+	case http.StatusPartialContent:
+		flags |= os.O_APPEND
+	case http.StatusOK:
+		// The server ignored the range, start over
+		flags |= os.O_TRUNC
+		offset = 0
+	case http.StatusRequestedRangeNotSatisfiable:
+		// The part file is not a prefix of the asset, discard it and start over
+		if err := os.Remove(partFile); err != nil {
+			return nil, fmt.Errorf("failed to remove partial download: %w", err)
+		}
+		return f.downloadRaw(ctx, src, dest, checksum)
+	default:
+		return nil, &DownloadStatusError{StatusCode: resp.StatusCode, URL: src}
+	}
+
+	// Hash the data as it arrives, the checksum is then known as soon as the download completes
+	d := digestFrom(ctx)
+	if d == nil && checksum != "" {
+		d = newDigest()
+	}
+	if d != nil && offset > 0 {
+		if err := d.resume(partFile); err != nil {
+			return nil, err
+		}
+	}
+
+	file, err := os.OpenFile(partFile, flags, 0644)
+	if err != nil {
+		return nil, fmt.Errorf("failed to create file: %w", err)
+	}
+
+	// Check for cancellation before every chunk, the part file is kept for resuming later
+	tracker := newCancelTracker(ctx, f.ProgressTracker)
+	tracker.digest = d
+	total := int64(-1)
+	if resp.ContentLength >= 0 {
+		total = offset + resp.ContentLength
+	}
+	body := tracker.TrackProgress(src, offset, total, resp.Body)
+
+	_, err = copyBuffered(file, body, f.WriteBufferSize)
+	body.Close()
+	if closeErr := file.Close(); err == nil {
+		err = closeErr
+	}
+	if err != nil {
+		return nil, fmt.Errorf("failed to write file: %w", err)
+	}
+
+	if checksum != "" {
+		if actual := d.checksum(); !strings.EqualFold(actual, checksum) {
+			os.Remove(partFile)
+			return nil, checksumMismatch(checksum, actual)
+		}
+	}
+
+	if err := os.Rename(partFile, target); err != nil {
+		return nil, fmt.Errorf("failed to rename partial download: %w", err)
+	}
+
+	return []string{name}, nil
+}
+
+// copyBuffered copies src to dst through a buffer of size bytes, DefaultWriteBufferSize if zero,
+// and flushes it, also when the copy fails, so everything read is written. A negative size
+// copies without buffering.
+func copyBuffered(dst io.Writer, src io.Reader, size int) (int64, error) {
+	if size < 0 {
+		return io.Copy(dst, src)
+	}
+	if size == 0 {
+		size = DefaultWriteBufferSize
+	}
+
+	// Hide the ReadFrom method of files, bufio.Writer would hand the copy over to it unbuffered
+	w := bufio.NewWriterSize(struct{ io.Writer }{dst}, size)
+	n, err := io.Copy(w, src)
+	if flushErr := w.Flush(); err == nil {
+		err = flushErr
+	}
+	return n, err
+}
+
+// verifySHA256 checks that the file at name matches checksum, a hex encoded SHA-256.
+func verifySHA256(name, checksum string) error {
+	actual, err := fileSHA256(name)
+	if err != nil {
+		return err
+	}
+	if !strings.EqualFold(actual, checksum) {
+		return checksumMismatch(checksum, actual)
+	}
+	return nil
+}
+
+// checksumMismatch returns the error reported when a download with the SHA-256 actual does not
+// match the expected checksum.
+func checksumMismatch(expected, actual string) error {
+	return fmt.Errorf("%w: expected %s, got %s", ErrChecksumMismatch, strings.ToLower(expected), actual)
+}
+
+// fileSHA256 returns the hex encoded SHA-256 of the file at name.
+func fileSHA256(name string) (string, error) {
+	file, err := os.Open(name)
+	if err != nil {
+		return "", fmt.Errorf("failed to open downloaded file: %w", err)
+	}
+	defer file.Close()
+
+	hash := sha256.New()
+	if _, err := io.Copy(hash, file); err != nil {
+		return "", fmt.Errorf("failed to hash downloaded file: %w", err)
+	}
+
+	return hex.EncodeToString(hash.Sum(nil)), nil
+}
@@ -0,0 +1,85 @@
+package libfetch
+
+import (
+	"bytes"
+	"io"
+	"os"
+	"path/filepath"
+	"testing"
+	"testing/iotest"
+)
+
+// countingWriter counts the writes made to it.
+type countingWriter struct {
+	buf    bytes.Buffer
+	writes int
+}
+
+func (w *countingWriter) Write(p []byte) (int, error) {
+	w.writes++
+	return w.buf.Write(p)
+}
+
+func TestCopyBufferedCoalescesSmallChunks(t *testing.T) {
+	data := bytes.Repeat([]byte("0123456789"), 1000)
+
+	tests := []struct {
+		size       int
+		wantWrites int
+	}{
+		{-1, len(data)},
+		{1024, len(data)/1024 + 1},
+		{0, 1},
+	}
+	for _, tt := range tests {
+		var w countingWriter
+		n, err := copyBuffered(&w, iotest.OneByteReader(bytes.NewReader(data)), tt.size)
+		if err != nil || n != int64(len(data)) {
+			t.Fatalf("size %d: copyBuffered() = %d, %v", tt.size, n, err)
+		}
+		if !bytes.Equal(w.buf.Bytes(), data) {
+			t.Errorf("size %d: copied data differs", tt.size)
+		}
+		if w.writes != tt.wantWrites {
+			t.Errorf("size %d: %d writes, want %d", tt.size, w.writes, tt.wantWrites)
+		}
+	}
+}
+
+func TestCopyBufferedFlushesOnError(t *testing.T) {
+	var w countingWriter
+	src := io.MultiReader(bytes.NewReader([]byte("partial")), iotest.ErrReader(io.ErrUnexpectedEOF))
+	if _, err := copyBuffered(&w, src, 0); err != io.ErrUnexpectedEOF {
+		t.Fatalf("expected the read error, got %v", err)
+	}
+	if w.buf.String() != "partial" {
+		t.Errorf("wrote %q, want the data read before the error", w.buf.String())
+	}
+}
+
+func BenchmarkCopyBufferedTinyChunks(b *testing.B) {
+	data := bytes.Repeat([]byte{'x'}, 1<<12)
+	for _, size := range []int{-1, DefaultWriteBufferSize} {
+		name := "unbuffered"
+		if size > 0 {
+			name = "buffered"
+		}
+		b.Run(name, func(b *testing.B) {
+			file, err := os.Create(filepath.Join(b.TempDir(), "download"))
+			if err != nil {
+				b.Fatal(err)
+			}
+			defer file.Close()
+
+			b.SetBytes(int64(len(data)))
+			for b.Loop() {
+				if _, err := file.Seek(0, io.SeekStart); err != nil {
+					b.Fatal(err)
+				}
+				if _, err := copyBuffered(file, iotest.OneByteReader(bytes.NewReader(data)), size); err != nil {
+					b.Fatal(err)
+				}
+			}
+		})
+	}
+}
@@ -0,0 +1,51 @@
+package libfetch
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"net/http"
+	"sync/atomic"
+)
+
+type redirectsKey struct{}
+
+// redirects records whether the download whose requests carry it was redirected.
+type redirects struct {
+	followed atomic.Bool
+}
+
+// withRedirectTracking returns a context recording whether requests made with it are redirected.
+func withRedirectTracking(ctx context.Context) (context.Context, *redirects) {
+	r := &redirects{}
+	return context.WithValue(ctx, redirectsKey{}, r), r
+}
+
+// checkRedirect is the redirect policy of all clients: it follows up to MaxRedirects redirects
+// and records them for downloads tracked by withRedirectTracking.
+func (f *Downloader) checkRedirect(req *http.Request, via []*http.Request) error {
+	limit := f.MaxRedirects
+	if limit == 0 {
+		limit = DefaultMaxRedirects
+	}
+	if limit < 0 {
+		return http.ErrUseLastResponse
+	}
+	if len(via) > limit {
+		return fmt.Errorf("stopped after %d redirects", limit)
+	}
+
+	if r, ok := req.Context().Value(redirectsKey{}).(*redirects); ok {
+		r.followed.Store(true)
+	}
+	// Redirect targets are often signed, so their query is not logged
+	f.logger().Debug("following redirect", "from", via[len(via)-1].URL.Host, "to", req.URL.Host+req.URL.Path)
+	return nil
+}
+
+// expiredRedirect reports whether err is a 403 returned by the target of a redirect. GitHub
+// redirects asset downloads to signed storage URLs, which are rejected once the signature expires.
+func expiredRedirect(err error, r *redirects) bool {
+	var serr *DownloadStatusError
+	return r.followed.Load() && errors.As(err, &serr) && serr.StatusCode == http.StatusForbidden
+}
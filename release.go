@@ -0,0 +1,254 @@
+package libfetch
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"net/url"
+	"sort"
+	"strings"
+	"time"
+
+	goversion "github.com/hashicorp/go-version"
+)
+
+// ReleaseInfo describes a single GitHub release.
+type ReleaseInfo struct {
+	TagName     string    `json:"tag_name"`
+	Name        string    `json:"name"`
+	Prerelease  bool      `json:"prerelease"`
+	Draft       bool      `json:"draft"`
+	PublishedAt time.Time `json:"published_at"`
+	// Body is the release notes, usually Markdown.
+	Body string `json:"body"`
+}
+
+// AssetInfo describes a single asset of a GitHub release.
+type AssetInfo struct {
+	Name          string `json:"name"`
+	Size          int64  `json:"size"`
+	ContentType   string `json:"content_type"`
+	DownloadCount int    `json:"download_count"`
+	// BrowserDownloadURL is the URL the asset is downloaded from.
+	BrowserDownloadURL string `json:"browser_download_url"`
+}
+
+// releasesURL returns the GitHub API URL listing all releases of the repository.
+func (f *Downloader) releasesURL() string {
+	return strings.TrimSuffix(f.ApiURL, "/latest")
+}
+
+// releaseURL returns the GitHub API URL of the release tagged version, or of the latest release
+// when version is empty.
+func (f *Downloader) releaseURL(version string) string {
+	if version == "" {
+		return f.ApiURL
+	}
+	return f.releasesURL() + "/tags/" + url.PathEscape(version)
+}
+
+// GetRelease returns the release tagged version, or the latest release when version is empty.
+func (f *Downloader) GetRelease(version string) (*ReleaseInfo, error) {
+	var release ReleaseInfo
+	if _, err := f.getJSON(f.releaseURL(version), &release); err != nil {
+		return nil, err
+	}
+	return &release, nil
+}
+
+// ReleaseNotes returns the release notes of the release tagged version, or of the latest release
+// when version is empty.
+func (f *Downloader) ReleaseNotes(version string) (string, error) {
+	release, err := f.GetRelease(version)
+	if err != nil {
+		return "", err
+	}
+	return release.Body, nil
+}
+
+// ListReleases returns all releases of the repository, newest first.
+// It follows the pagination links returned by GitHub so every release is enumerated.
+func (f *Downloader) ListReleases() ([]ReleaseInfo, error) {
+	var releases []ReleaseInfo
+
+	pageURL := f.releasesURL() + "?per_page=100"
+	for pageURL != "" {
+		var page []ReleaseInfo
+		header, err := f.getJSON(pageURL, &page)
+		if err != nil {
+			return nil, err
+		}
+
+		releases = append(releases, page...)
+		pageURL = nextPageURL(header.Get("Link"))
+	}
+
+	return releases, nil
+}
+
+// LatestPrerelease returns the tag of the most recently published prerelease.
+func (f *Downloader) LatestPrerelease() (string, error) {
+	return f.latestReleaseMatching(func(release ReleaseInfo) bool {
+		return release.Prerelease
+	})
+}
+
+// LatestVersionIncludingPrereleases returns the tag of the most recently published release,
+// whether or not it is a prerelease.
+func (f *Downloader) LatestVersionIncludingPrereleases() (string, error) {
+	return f.latestReleaseMatching(func(release ReleaseInfo) bool {
+		return true
+	})
+}
+
+// LatestVersionMatching returns the tag of the highest non-draft release whose version satisfies
+// constraint, a version constraint like ">= 2.0, < 3.0". A leading "v" of tags is ignored and tags
+// that are not versions are skipped. Prereleases only satisfy constraints that name a prerelease.
+func (f *Downloader) LatestVersionMatching(constraint string) (string, error) {
+	constraints, err := goversion.NewConstraint(constraint)
+	if err != nil {
+		return "", fmt.Errorf("invalid version constraint %q: %w", constraint, err)
+	}
+
+	releases, err := f.ListReleases()
+	if err != nil {
+		return "", err
+	}
+
+	var tag string
+	var highest *goversion.Version
+	for _, release := range releases {
+		if release.Draft {
+			continue
+		}
+		v, err := goversion.NewVersion(strings.TrimPrefix(release.TagName, "v"))
+		if err != nil || !constraints.Check(v) {
+			continue
+		}
+		if highest == nil || v.GreaterThan(highest) {
+			tag, highest = release.TagName, v
+		}
+	}
+	if highest == nil {
+		return "", fmt.Errorf("no release matches version constraint %q", constraint)
+	}
+	return tag, nil
+}
+
+// LatestVersionOffset returns the tag of the n-th most recently published release, 0 being the
+// latest, e.g. 1 for the release before a broken latest one. Prereleases are only counted when
+// includePrereleases is set; drafts never are. It fails if there are not more than n such releases.
+func (f *Downloader) LatestVersionOffset(n int, includePrereleases bool) (string, error) {
+	if n < 0 {
+		return "", fmt.Errorf("invalid release offset %d", n)
+	}
+	candidates, err := f.releasesMatching(func(release ReleaseInfo) bool {
+		return includePrereleases || !release.Prerelease
+	})
+	if err != nil {
+		return "", err
+	}
+	if n >= len(candidates) {
+		return "", fmt.Errorf("release offset %d requested but only %d matching releases found", n, len(candidates))
+	}
+	return candidates[n].TagName, nil
+}
+
+// latestReleaseMatching returns the tag of the most recently published non-draft release accepted by keep.
+func (f *Downloader) latestReleaseMatching(keep func(release ReleaseInfo) bool) (string, error) {
+	candidates, err := f.releasesMatching(keep)
+	if err != nil {
+		return "", err
+	}
+	if len(candidates) == 0 {
+		return "", errors.New("no matching release found")
+	}
+	return candidates[0].TagName, nil
+}
+
+// releasesMatching returns the non-draft releases accepted by keep, most recently published first.
+func (f *Downloader) releasesMatching(keep func(release ReleaseInfo) bool) ([]ReleaseInfo, error) {
+	releases, err := f.ListReleases()
+	if err != nil {
+		return nil, err
+	}
+
+	var candidates []ReleaseInfo
+	for _, release := range releases {
+		if !release.Draft && keep(release) {
+			candidates = append(candidates, release)
+		}
+	}
+
+	sort.SliceStable(candidates, func(i, j int) bool {
+		return candidates[i].PublishedAt.After(candidates[j].PublishedAt)
+	})
+
+	return candidates, nil
+}
+
+// getJSON performs a GitHub API request and decodes the JSON response into v.
+// The response headers are returned so callers can inspect pagination links.
+func (f *Downloader) getJSON(apiURL string, v any) (http.Header, error) {
+	header, _, err := f.getJSONIfModified(apiURL, "", v)
+	return header, err
+}
+
+// getJSONIfModified performs a GitHub API request like getJSON. When etag is not empty it is sent
+// in If-None-Match, and a 304 Not Modified response, which does not count against the rate limit,
+// is reported as notModified without decoding anything into v.
+func (f *Downloader) getJSONIfModified(apiURL, etag string, v any) (header http.Header, notModified bool, err error) {
+	req, err := f.newAPIRequest(apiURL)
+	if err != nil {
+		return nil, false, err
+	}
+	if etag != "" {
+		req.Header.Set("If-None-Match", etag)
+	}
+
+	// Create HTTP client with proxy support
+	client := f.createHTTPClient()
+	resp, err := client.Do(req)
+	if err != nil {
+		if ctx := req.Context(); ctx.Err() != nil {
+			return nil, false, cancelledError(ctx)
+		}
+		return nil, false, err
+	}
+	defer resp.Body.Close()
+
+	if etag != "" && resp.StatusCode == http.StatusNotModified {
+		return resp.Header, true, nil
+	}
+	if resp.StatusCode != http.StatusOK {
+		return nil, false, f.tokenScopeError(apiError(resp))
+	}
+
+	if err := json.NewDecoder(resp.Body).Decode(v); err != nil {
+		return nil, false, fmt.Errorf("failed to decode GitHub API response: %w", err)
+	}
+	// Read the rest of the body so the connection can be reused
+	io.Copy(io.Discard, resp.Body)
+
+	return resp.Header, false, nil
+}
+
+// nextPageURL returns the rel="next" target of a GitHub Link header, or an empty string on the last page.
+func nextPageURL(link string) string {
+	for _, part := range strings.Split(link, ",") {
+		segments := strings.Split(part, ";")
+		if len(segments) < 2 {
+			continue
+		}
+
+		target := strings.Trim(strings.TrimSpace(segments[0]), "<>")
+		for _, param := range segments[1:] {
+			if strings.TrimSpace(param) == `rel="next"` {
+				return target
+			}
+		}
+	}
+	return ""
+}
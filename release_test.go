@@ -0,0 +1,309 @@
+package libfetch_test
+
+import (
+	"fmt"
+	"net/http"
+	"net/http/httptest"
+	"sync/atomic"
+	"testing"
+	"time"
+
+	"github.com/Cyberhan123/libfetch"
+)
+
+func TestListReleasesFollowsPagination(t *testing.T) {
+	var server *httptest.Server
+	server = httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		if r.URL.Query().Get("page") == "2" {
+			fmt.Fprint(w, `[{"tag_name": "v1.0.0", "prerelease": false, "draft": false}]`)
+			return
+		}
+		w.Header().Set("Link", fmt.Sprintf(`<%s/releases?per_page=100&page=2>; rel="next", <%s/releases?per_page=100&page=2>; rel="last"`, server.URL, server.URL))
+		fmt.Fprint(w, `[{"tag_name": "v2.0.0-rc1", "prerelease": true, "draft": false}, {"tag_name": "v1.1.0", "prerelease": false, "draft": false}]`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL + "/releases/latest"
+
+	releases, err := downloader.ListReleases()
+	if err != nil {
+		t.Fatalf("error listing releases: %v", err)
+	}
+
+	want := []libfetch.ReleaseInfo{
+		{TagName: "v2.0.0-rc1", Prerelease: true},
+		{TagName: "v1.1.0"},
+		{TagName: "v1.0.0"},
+	}
+	if len(releases) != len(want) {
+		t.Fatalf("got %d releases, want %d", len(releases), len(want))
+	}
+	for i := range want {
+		if releases[i] != want[i] {
+			t.Errorf("release %d = %+v, want %+v", i, releases[i], want[i])
+		}
+	}
+}
+
+func TestLatestVersionMatching(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `[
+			{"tag_name": "v3.0.0"},
+			{"tag_name": "v2.5.0-rc1", "prerelease": true},
+			{"tag_name": "v2.4.0", "draft": true},
+			{"tag_name": "nightly"},
+			{"tag_name": "v2.3.1"},
+			{"tag_name": "v2.10.0"},
+			{"tag_name": "v1.9.0"}
+		]`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL + "/releases/latest"
+
+	tests := []struct {
+		constraint string
+		want       string
+	}{
+		{">= 2.0, < 3.0", "v2.10.0"},
+		{"~> 2.3.0", "v2.3.1"},
+		{"< 2.0", "v1.9.0"},
+		{">= 2.5.0-rc1, < 3.0", "v2.10.0"},
+	}
+	for _, tt := range tests {
+		if tag, err := downloader.LatestVersionMatching(tt.constraint); err != nil || tag != tt.want {
+			t.Errorf("LatestVersionMatching(%q) = %q, %v, want %q", tt.constraint, tag, err, tt.want)
+		}
+	}
+
+	if _, err := downloader.LatestVersionMatching(">= 4.0"); err == nil {
+		t.Error("expected an error when no release matches")
+	}
+	if _, err := downloader.LatestVersionMatching("not a constraint"); err == nil {
+		t.Error("expected an error for an invalid constraint")
+	}
+}
+
+func TestLatestVersionOffset(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `[
+			{"tag_name": "v1.1.0", "published_at": "2024-02-01T00:00:00Z"},
+			{"tag_name": "v1.3.0-rc1", "prerelease": true, "published_at": "2024-04-01T00:00:00Z"},
+			{"tag_name": "v1.3.0", "draft": true},
+			{"tag_name": "v1.2.0", "published_at": "2024-03-01T00:00:00Z"},
+			{"tag_name": "v1.0.0", "published_at": "2024-01-01T00:00:00Z"}
+		]`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL + "/releases/latest"
+
+	tests := []struct {
+		n                  int
+		includePrereleases bool
+		want               string
+	}{
+		{0, false, "v1.2.0"},
+		{1, false, "v1.1.0"},
+		{2, false, "v1.0.0"},
+		{0, true, "v1.3.0-rc1"},
+		{1, true, "v1.2.0"},
+	}
+	for _, tt := range tests {
+		if tag, err := downloader.LatestVersionOffset(tt.n, tt.includePrereleases); err != nil || tag != tt.want {
+			t.Errorf("LatestVersionOffset(%d, %t) = %q, %v, want %q", tt.n, tt.includePrereleases, tag, err, tt.want)
+		}
+	}
+
+	if _, err := downloader.LatestVersionOffset(3, false); err == nil {
+		t.Error("expected an error for an offset beyond the releases")
+	}
+}
+
+func TestLatestPrerelease(t *testing.T) {
+	var body atomic.Value
+	body.Store(`[
+		{"tag_name": "v1.1.0", "published_at": "2024-02-01T00:00:00Z"},
+		{"tag_name": "v1.2.0-rc2", "prerelease": true, "published_at": "2024-04-01T00:00:00Z"},
+		{"tag_name": "v1.3.0-rc1", "prerelease": true, "draft": true, "published_at": "2024-06-01T00:00:00Z"},
+		{"tag_name": "v1.2.0", "published_at": "2024-05-01T00:00:00Z"},
+		{"tag_name": "v1.2.0-rc1", "prerelease": true, "published_at": "2024-03-01T00:00:00Z"}
+	]`)
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, body.Load().(string))
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL + "/releases/latest"
+
+	if tag, err := downloader.LatestPrerelease(); err != nil || tag != "v1.2.0-rc2" {
+		t.Errorf("LatestPrerelease() = %q, %v, want v1.2.0-rc2", tag, err)
+	}
+	if tag, err := downloader.LatestVersionIncludingPrereleases(); err != nil || tag != "v1.2.0" {
+		t.Errorf("LatestVersionIncludingPrereleases() = %q, %v, want v1.2.0", tag, err)
+	}
+
+	body.Store(`[
+		{"tag_name": "v1.0.0", "published_at": "2024-01-01T00:00:00Z"},
+		{"tag_name": "v1.1.0-rc1", "prerelease": true, "published_at": "2024-03-01T00:00:00Z"},
+		{"tag_name": "v1.1.0", "published_at": "2024-02-01T00:00:00Z"}
+	]`)
+	if tag, err := downloader.LatestVersionIncludingPrereleases(); err != nil || tag != "v1.1.0-rc1" {
+		t.Errorf("LatestVersionIncludingPrereleases() = %q, %v, want v1.1.0-rc1", tag, err)
+	}
+
+	body.Store(`[
+		{"tag_name": "v1.0.0", "published_at": "2024-01-01T00:00:00Z"},
+		{"tag_name": "v1.1.0-rc1", "prerelease": true, "draft": true, "published_at": "2024-02-01T00:00:00Z"}
+	]`)
+	if tag, err := downloader.LatestPrerelease(); err == nil {
+		t.Errorf("LatestPrerelease() = %q, expected an error when only a draft is a prerelease", tag)
+	}
+	if tag, err := downloader.LatestVersionIncludingPrereleases(); err != nil || tag != "v1.0.0" {
+		t.Errorf("LatestVersionIncludingPrereleases() = %q, %v, want v1.0.0", tag, err)
+	}
+}
+
+func TestReleaseNotes(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/releases/latest":
+			fmt.Fprint(w, `{"tag_name": "v1.1.0", "name": "1.1", "body": "latest notes"}`)
+		case "/repos/owner/repo/releases/tags/v1.0.0":
+			fmt.Fprint(w, `{"tag_name": "v1.0.0", "name": "1.0", "body": "pinned notes"}`)
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+
+	if notes, err := downloader.ReleaseNotes(""); err != nil || notes != "latest notes" {
+		t.Errorf("ReleaseNotes(\"\") = %q, %v", notes, err)
+	}
+	if notes, err := downloader.ReleaseNotes("v1.0.0"); err != nil || notes != "pinned notes" {
+		t.Errorf("ReleaseNotes(\"v1.0.0\") = %q, %v", notes, err)
+	}
+}
+
+func TestGetReleaseAssetInfo(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		fmt.Fprint(w, `{"tag_name": "v1.0.0", "assets": [{
+			"name": "tool.zip",
+			"size": 1024,
+			"content_type": "application/zip",
+			"download_count": 42,
+			"browser_download_url": "https://github.com/owner/repo/releases/download/v1.0.0/tool.zip"
+		}]}`)
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = server.URL
+
+	assets, err := downloader.GetLatestReleaseAssetInfo()
+	if err != nil {
+		t.Fatal(err)
+	}
+	want := libfetch.AssetInfo{
+		Name:               "tool.zip",
+		Size:               1024,
+		ContentType:        "application/zip",
+		DownloadCount:      42,
+		BrowserDownloadURL: "https://github.com/owner/repo/releases/download/v1.0.0/tool.zip",
+	}
+	if len(assets) != 1 || assets[0] != want {
+		t.Errorf("GetLatestReleaseAssetInfo() = %+v, want [%+v]", assets, want)
+	}
+
+	names, err := downloader.GetLatestReleaseAssets()
+	if err != nil || len(names) != 1 || names[0] != "tool.zip" {
+		t.Errorf("GetLatestReleaseAssets() = %v, %v", names, err)
+	}
+}
+
+func TestResolveTag(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/git/ref/tags/v1.0.0":
+			fmt.Fprint(w, `{"ref": "refs/tags/v1.0.0", "object": {"sha": "c1", "type": "commit"}}`)
+		case "/repos/owner/repo/git/commits/c1":
+			fmt.Fprint(w, `{"sha": "c1", "committer": {"date": "2024-01-02T03:04:05Z"}}`)
+		case "/repos/owner/repo/git/ref/tags/v2.0.0":
+			fmt.Fprint(w, `{"ref": "refs/tags/v2.0.0", "object": {"sha": "t2", "type": "tag"}}`)
+		case "/repos/owner/repo/git/tags/t2":
+			fmt.Fprint(w, `{"sha": "t2", "tagger": {"date": "2024-02-03T04:05:06Z"}, "object": {"sha": "c2", "type": "commit"}}`)
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+
+	tests := []struct {
+		tag  string
+		want libfetch.TagRef
+	}{
+		{"v1.0.0", libfetch.TagRef{Tag: "v1.0.0", SHA: "c1", Date: time.Date(2024, 1, 2, 3, 4, 5, 0, time.UTC)}},
+		{"v2.0.0", libfetch.TagRef{Tag: "v2.0.0", SHA: "c2", Date: time.Date(2024, 2, 3, 4, 5, 6, 0, time.UTC), Annotated: true}},
+	}
+	for _, tt := range tests {
+		got, err := downloader.ResolveTag(tt.tag)
+		if err != nil {
+			t.Fatalf("%s: %v", tt.tag, err)
+		}
+		if got.Tag != tt.want.Tag || got.SHA != tt.want.SHA || !got.Date.Equal(tt.want.Date) || got.Annotated != tt.want.Annotated {
+			t.Errorf("ResolveTag(%s) = %+v, want %+v", tt.tag, got, tt.want)
+		}
+	}
+
+	if _, err := downloader.ResolveTag("v3.0.0"); err == nil {
+		t.Error("expected an error resolving a missing tag")
+	}
+}
+
+func TestResolveMovingTag(t *testing.T) {
+	server := httptest.NewServer(http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		switch r.URL.Path {
+		case "/repos/owner/repo/releases":
+			fmt.Fprint(w, `[
+				{"tag_name": "nightly", "published_at": "2024-03-01T00:00:00Z"},
+				{"tag_name": "nightly-2024-02-28", "published_at": "2024-02-28T00:00:00Z"},
+				{"tag_name": "nightly-2024-02-29", "published_at": "2024-02-29T00:00:00Z"},
+				{"tag_name": "nightly-2024-03-01", "draft": true}
+			]`)
+		case "/repos/owner/repo/git/ref/tags/nightly", "/repos/owner/repo/git/ref/tags/nightly-2024-02-28", "/repos/owner/repo/git/ref/tags/nightly-2024-03-01":
+			fmt.Fprint(w, `{"object": {"sha": "c3", "type": "commit"}}`)
+		case "/repos/owner/repo/git/ref/tags/nightly-2024-02-29":
+			fmt.Fprint(w, `{"object": {"sha": "c2", "type": "commit"}}`)
+		case "/repos/owner/repo/git/commits/c2", "/repos/owner/repo/git/commits/c3":
+			fmt.Fprint(w, `{"committer": {"date": "2024-02-29T00:00:00Z"}}`)
+		default:
+			http.NotFound(w, r)
+		}
+	}))
+	defer server.Close()
+
+	downloader := libfetch.NewDownloader("owner/repo")
+	downloader.ApiURL = libfetch.LatestReleaseURL(server.URL, "owner/repo")
+
+	if tag, err := downloader.ResolveVersion("nightly"); err != nil || tag != "nightly" {
+		t.Errorf("ResolveVersion(nightly) without moving tags = %q, %v", tag, err)
+	}
+
+	downloader.MovingTags = []string{"nightly"}
+	if tag, err := downloader.ResolveVersion("nightly"); err != nil || tag != "nightly-2024-02-28" {
+		t.Errorf("ResolveVersion(nightly) = %q, %v, want nightly-2024-02-28", tag, err)
+	}
+	if tag, err := downloader.ResolveVersion("nightly-2024-02-29"); err != nil || tag != "nightly-2024-02-29" {
+		t.Errorf("ResolveVersion(nightly-2024-02-29) = %q, %v", tag, err)
+	}
+}
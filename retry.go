@@ -0,0 +1,82 @@
+package libfetch
+
+import (
+	"context"
+	"errors"
+	"net/http"
+	"time"
+)
+
+// withRetry runs attempt, which requests src, up to RetryCount times, waiting RetryDelay between
+// attempts. Errors that a retry cannot fix, such as a 404 or a checksum mismatch, are returned
+// immediately.
+func (f *Downloader) withRetry(ctx context.Context, src string, attempt func() error) error {
+	attempts := max(f.RetryCount, 1)
+	var err error
+	for i := range attempts {
+		if i > 0 {
+			f.reportRetry(ctx, src, i+1, attempts, f.RetryDelay, err)
+			select {
+			case <-ctx.Done():
+				return err
+			case <-time.After(f.RetryDelay):
+			}
+		}
+
+		err = attempt()
+		if err == nil || !isRetryable(ctx, err) {
+			return err
+		}
+	}
+
+	return err
+}
+
+// reportRetry sends an EventRetrying to ProgressHandler before attempt of attempts to request
+// src with ctx is made in delay, after the previous one failed with err.
+func (f *Downloader) reportRetry(ctx context.Context, src string, attempt, attempts int, delay time.Duration, err error) {
+	f.logger().Warn("request failed, retrying", "url", src, "attempt", attempt, "max_attempts", attempts, "delay", delay, "error", err)
+	if f.ProgressHandler == nil {
+		return
+	}
+	f.ProgressHandler(ProgressEvent{
+		Kind:        EventRetrying,
+		URL:         src,
+		Dest:        destinationFrom(ctx),
+		Attempt:     attempt,
+		MaxAttempts: attempts,
+		Delay:       delay,
+		Err:         err,
+	})
+}
+
+// permanentErrors are the failures a download of the same data would fail with again.
+var permanentErrors = []error{
+	ErrChecksumMismatch,
+	ErrSignatureMismatch,
+	ErrInvalidProxy,
+	ErrArchive,
+	ErrFileCollision,
+	ErrNoFiles,
+	ErrInsufficientSpace,
+}
+
+// isRetryable reports whether a failed download is worth retrying. Network failures and 5xx
+// responses are; client errors, mismatching checksums and signatures, archives that cannot be
+// extracted, collisions, a lack of files or disk space, an invalid proxy and cancellation are not.
+func isRetryable(ctx context.Context, err error) bool {
+	if ctx.Err() != nil {
+		return false
+	}
+	for _, permanent := range permanentErrors {
+		if errors.Is(err, permanent) {
+			return false
+		}
+	}
+
+	var serr *DownloadStatusError
+	if errors.As(err, &serr) && serr.StatusCode >= 400 && serr.StatusCode < 500 {
+		return serr.StatusCode == http.StatusTooManyRequests || serr.StatusCode == http.StatusRequestTimeout
+	}
+	return true
+}
@@ -0,0 +1,213 @@
+package libfetch
+
+import (
+	"bytes"
+	"context"
+	"crypto/ed25519"
+	"encoding/base64"
+	"encoding/binary"
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+	"os"
+	"path/filepath"
+	"strings"
+
+	"golang.org/x/crypto/blake2b"
+)
+
+// maxSignatureSize limits how much of a signature asset is read.
+const maxSignatureSize = 64 << 10
+
+// minisignPublicKey is a minisign public key.
+type minisignPublicKey struct {
+	keyID [8]byte
+	key   ed25519.PublicKey
+}
+
+// minisignSignature is a parsed minisign signature file.
+type minisignSignature struct {
+	// prehashed reports that the signature covers the BLAKE2b-512 hash of the file rather than
+	// the file itself, as made by minisign by default.
+	prehashed       bool
+	keyID           [8]byte
+	signature       []byte
+	trustedComment  string
+	globalSignature []byte
+}
+
+// parseMinisignPublicKey parses a minisign public key, either the base64 encoded key itself or
+// the contents of a ".pub" file including its comment line.
+func parseMinisignPublicKey(s string) (*minisignPublicKey, error) {
+	var encoded string
+	for _, line := range strings.Split(s, "\n") {
+		if line = strings.TrimSpace(line); line != "" && !strings.HasPrefix(line, "untrusted comment:") {
+			encoded = line
+		}
+	}
+
+	d, err := base64.StdEncoding.DecodeString(encoded)
+	if err != nil || len(d) != 2+8+ed25519.PublicKeySize || string(d[:2]) != "Ed" {
+		return nil, errors.New("invalid minisign public key")
+	}
+	key := &minisignPublicKey{key: ed25519.PublicKey(d[10:])}
+	copy(key.keyID[:], d[2:10])
+	return key, nil
+}
+
+// parseMinisignSignature parses the contents of a minisign signature file.
+func parseMinisignSignature(data []byte) (*minisignSignature, error) {
+	lines := strings.Split(strings.ReplaceAll(string(data), "\r\n", "\n"), "\n")
+	if len(lines) < 4 || !strings.HasPrefix(lines[0], "untrusted comment:") {
+		return nil, errors.New("not a minisign signature")
+	}
+
+	d, err := base64.StdEncoding.DecodeString(strings.TrimSpace(lines[1]))
+	if err != nil || len(d) != 2+8+ed25519.SignatureSize {
+		return nil, errors.New("invalid minisign signature")
+	}
+	sig := &minisignSignature{signature: d[10:]}
+	switch string(d[:2]) {
+	case "Ed":
+	case "ED":
+		sig.prehashed = true
+	default:
+		return nil, fmt.Errorf("unsupported minisign signature algorithm %q", d[:2])
+	}
+	copy(sig.keyID[:], d[2:10])
+
+	comment, ok := strings.CutPrefix(lines[2], "trusted comment: ")
+	if !ok {
+		return nil, errors.New("minisign signature has no trusted comment")
+	}
+	sig.trustedComment = comment
+	sig.globalSignature, err = base64.StdEncoding.DecodeString(strings.TrimSpace(lines[3]))
+	if err != nil || len(sig.globalSignature) != ed25519.SignatureSize {
+		return nil, errors.New("invalid minisign global signature")
+	}
+	return sig, nil
+}
+
+// verify checks that the file name was signed with key, along with the trusted comment.
+func (s *minisignSignature) verify(key *minisignPublicKey, name string) error {
+	if s.keyID != key.keyID {
+		return fmt.Errorf("%w: signed with key %016X, not %016X", ErrSignatureMismatch, binary.LittleEndian.Uint64(s.keyID[:]), binary.LittleEndian.Uint64(key.keyID[:]))
+	}
+
+	var message []byte
+	if s.prehashed {
+		file, err := os.Open(name)
+		if err != nil {
+			return err
+		}
+		defer file.Close()
+		h, _ := blake2b.New512(nil)
+		if _, err := io.Copy(h, file); err != nil {
+			return fmt.Errorf("error hashing %s: %w", name, err)
+		}
+		message = h.Sum(nil)
+	} else {
+		var err error
+		if message, err = os.ReadFile(name); err != nil {
+			return err
+		}
+	}
+
+	if !ed25519.Verify(key.key, message, s.signature) {
+		return fmt.Errorf("%w: %s", ErrSignatureMismatch, filepath.Base(name))
+	}
+	signed := append(bytes.Clone(s.signature), s.trustedComment...)
+	if !ed25519.Verify(key.key, signed, s.globalSignature) {
+		return fmt.Errorf("%w: the trusted comment of the signature of %s was altered", ErrSignatureMismatch, filepath.Base(name))
+	}
+	return nil
+}
+
+// VerifySignature verifies the file name, e.g. a downloaded asset, against the minisign signature
+// in the release asset signatureAssetName (usually "<asset>.minisig") of the release tagged
+// version (empty string for latest). publicKey is the project's minisign public key, either the
+// base64 encoded key or the contents of its ".pub" file. Detached GPG signatures are not
+// supported. A signature that does not match returns ErrSignatureMismatch.
+func (f *Downloader) VerifySignature(name, signatureAssetName, version, publicKey string) error {
+	return f.verifySignature(f.context(), name, signatureAssetName, version, publicKey)
+}
+
+func (f *Downloader) verifySignature(ctx context.Context, name, signatureAssetName, version, publicKey string) error {
+	key, err := parseMinisignPublicKey(publicKey)
+	if err != nil {
+		return err
+	}
+	signatureURL, err := f.assetURL(signatureAssetName, version)
+	if err != nil {
+		return err
+	}
+
+	var data []byte
+	err = f.withMirrors(ctx, signatureURL, func(f *Downloader, src string) error {
+		var err error
+		data, err = f.getSignature(ctx, src)
+		return err
+	})
+	if err != nil {
+		return fmt.Errorf("error downloading signature asset %s: %w", signatureAssetName, err)
+	}
+
+	sig, err := parseMinisignSignature(data)
+	if err != nil {
+		return fmt.Errorf("error reading signature asset %s: %w", signatureAssetName, err)
+	}
+	return sig.verify(key, name)
+}
+
+// getSignature downloads the signature file at signatureURL.
+func (f *Downloader) getSignature(ctx context.Context, signatureURL string) ([]byte, error) {
+	req, err := http.NewRequestWithContext(ctx, "GET", signatureURL, nil)
+	if err != nil {
+		return nil, err
+	}
+	for name, values := range f.requestHeader() {
+		req.Header[name] = values
+	}
+
+	resp, err := f.createHTTPClient().Do(req)
+	if err != nil {
+		return nil, err
+	}
+	defer resp.Body.Close()
+
+	if resp.StatusCode != http.StatusOK {
+		return nil, &DownloadStatusError{StatusCode: resp.StatusCode, URL: signatureURL}
+	}
+	return io.ReadAll(io.LimitReader(resp.Body, maxSignatureSize))
+}
+
+// downloadSigned downloads assetName of the given release version without extracting it,
+// verifies it against the minisign signature in signatureAssetName and only then extracts it into
+// dest, so nothing is written to dest when the signature does not match. It returns the files
+// written relative to dest.
+func (f *Downloader) downloadSigned(ctx context.Context, assetName, version, dest, checksum, signatureAssetName, publicKey string) ([]string, error) {
+	assetURL, err := f.assetURL(assetName, version)
+	if err != nil {
+		return nil, err
+	}
+	f.logger().Info("downloading asset", "repo", f.Repo, "asset", assetName, "version", version, "dest", dest, "signature", signatureAssetName)
+
+	spoolDir, err := os.MkdirTemp("", "libfetch-*")
+	if err != nil {
+		return nil, fmt.Errorf("failed to create temporary directory: %w", err)
+	}
+	defer os.RemoveAll(spoolDir)
+
+	// Report the events of the download into the spool directory for dest
+	ctx = withDestination(ctx, dest)
+	name, err := f.downloadUnextracted(ctx, assetURL, spoolDir, checksum)
+	if err != nil {
+		return nil, err
+	}
+
+	if err := f.verifySignature(ctx, name, signatureAssetName, version, publicKey); err != nil {
+		return nil, err
+	}
+	return f.extractFile(name, dest)
+}
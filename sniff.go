@@ -0,0 +1,100 @@
+package libfetch
+
+import (
+	"bytes"
+	"fmt"
+	"io"
+	"os"
+	"path/filepath"
+)
+
+// archiveMagic lists the leading bytes of the archive types libfetch extracts itself. Compressed
+// data only counts as a tarball if it decompresses to a tar archive.
+var archiveMagic = []struct {
+	magic      []byte
+	ext        string
+	decompress tarDecompressor
+}{
+	{[]byte("PK\x03\x04"), ".zip", nil},
+	{[]byte{0x1f, 0x8b}, ".tar.gz", decompressGzip},
+	{[]byte{0xfd, '7', 'z', 'X', 'Z', 0x00}, ".tar.xz", decompressXz},
+	{[]byte("BZh"), ".tar.bz2", decompressBzip2},
+}
+
+// sniffArchive returns the extension of the archive type libfetch extracts itself that the file
+// at name contains judging by its leading bytes, or an empty string if it contains none.
+func sniffArchive(name string) (string, error) {
+	file, err := os.Open(name)
+	if err != nil {
+		return "", err
+	}
+	defer file.Close()
+
+	header := make([]byte, 8)
+	n, err := io.ReadFull(file, header)
+	if err != nil && err != io.EOF && err != io.ErrUnexpectedEOF {
+		return "", err
+	}
+	header = header[:n]
+
+	for _, m := range archiveMagic {
+		if !bytes.HasPrefix(header, m.magic) {
+			continue
+		}
+		if m.decompress == nil {
+			return m.ext, nil
+		}
+
+		if _, err := file.Seek(0, io.SeekStart); err != nil {
+			return "", err
+		}
+		r, err := m.decompress(file)
+		if err != nil || !isTar(r) {
+			// Compressed data that is not a tarball is left to the caller as it is
+			return "", nil
+		}
+		return m.ext, nil
+	}
+	return "", nil
+}
+
+// isTar reports whether r starts with a POSIX or GNU tar header.
+func isTar(r io.Reader) bool {
+	block := make([]byte, 512)
+	if _, err := io.ReadFull(r, block); err != nil {
+		return false
+	}
+	return bytes.Equal(block[257:262], []byte("ustar"))
+}
+
+// extractSniffed extracts the file name, downloaded to dest, into dest if it contains an archive
+// libfetch extracts itself, see DetectArchiveByMagic, and returns the files written relative to
+// dest. Other files are left in place.
+func (f *Downloader) extractSniffed(dest, name string) ([]string, error) {
+	downloadFile := filepath.Join(dest, filepath.FromSlash(name))
+	ext, err := sniffArchive(downloadFile)
+	if err != nil {
+		return nil, fmt.Errorf("failed to inspect download: %w", err)
+	}
+	if ext == "" {
+		return []string{name}, nil
+	}
+	f.logger().Debug("detected archive by its leading bytes", "file", name, "type", ext)
+
+	// Move the archive out of the way, as its entries may have its name
+	spoolDir, err := os.MkdirTemp(dest, ".libfetch-*")
+	if err != nil {
+		return nil, fmt.Errorf("failed to create temporary directory: %w", err)
+	}
+	defer os.RemoveAll(spoolDir)
+
+	archive := filepath.Join(spoolDir, filepath.Base(downloadFile))
+	if err := os.Rename(downloadFile, archive); err != nil {
+		return nil, fmt.Errorf("failed to move download: %w", err)
+	}
+	files, err := f.extractArchive(archive, dest, ext)
+	if err != nil {
+		return files, err
+	}
+	return f.keepArchive(archive, dest, files)
+}
@@ -0,0 +1,139 @@
+package libfetch
+
+import (
+	"fmt"
+	"net/url"
+	"slices"
+	"sort"
+	"strings"
+	"time"
+)
+
+// maxTagDepth limits how many annotated tags pointing at other tags are dereferenced.
+const maxTagDepth = 10
+
+// TagRef describes the commit a tag points to.
+type TagRef struct {
+	// Tag is the name of the tag.
+	Tag string
+	// SHA is the commit the tag points to, dereferencing annotated tags.
+	SHA string
+	// Date is the tagger date of annotated tags and the committer date of the commit for
+	// lightweight tags.
+	Date time.Time
+	// Annotated reports that the tag is an annotated tag object rather than a plain reference.
+	Annotated bool
+}
+
+// gitObject is the object a git reference or tag points to.
+type gitObject struct {
+	SHA  string `json:"sha"`
+	Type string `json:"type"`
+}
+
+// gitSignature is the author, committer or tagger of a git object.
+type gitSignature struct {
+	Date time.Time `json:"date"`
+}
+
+// repoAPIURL returns the GitHub API URL of the repository.
+func (f *Downloader) repoAPIURL() string {
+	return strings.TrimSuffix(f.releasesURL(), "/releases")
+}
+
+// ResolveTag returns the commit tag points to. Annotated tags are dereferenced until a commit is
+// reached and report the date of the outermost tag; lightweight tags report the date of the commit.
+func (f *Downloader) ResolveTag(tag string) (*TagRef, error) {
+	var ref struct {
+		Object gitObject `json:"object"`
+	}
+	if _, err := f.getJSON(f.repoAPIURL()+"/git/ref/tags/"+url.PathEscape(tag), &ref); err != nil {
+		return nil, fmt.Errorf("error resolving tag %s: %w", tag, err)
+	}
+
+	resolved := &TagRef{Tag: tag}
+	object := ref.Object
+	for depth := 0; object.Type == "tag"; depth++ {
+		if depth == maxTagDepth {
+			return nil, fmt.Errorf("error resolving tag %s: too many nested tags", tag)
+		}
+		var annotated struct {
+			Object gitObject    `json:"object"`
+			Tagger gitSignature `json:"tagger"`
+		}
+		if _, err := f.getJSON(f.repoAPIURL()+"/git/tags/"+object.SHA, &annotated); err != nil {
+			return nil, fmt.Errorf("error resolving tag %s: %w", tag, err)
+		}
+		if !resolved.Annotated {
+			resolved.Annotated = true
+			resolved.Date = annotated.Tagger.Date
+		}
+		object = annotated.Object
+	}
+	if object.Type != "commit" {
+		return nil, fmt.Errorf("tag %s points to a %s, not a commit", tag, object.Type)
+	}
+	resolved.SHA = object.SHA
+
+	if !resolved.Annotated {
+		var commit struct {
+			Committer gitSignature `json:"committer"`
+		}
+		if _, err := f.getJSON(f.repoAPIURL()+"/git/commits/"+object.SHA, &commit); err != nil {
+			return nil, fmt.Errorf("error resolving tag %s: %w", tag, err)
+		}
+		resolved.Date = commit.Committer.Date
+	}
+	return resolved, nil
+}
+
+// ResolveVersion returns the release tag version is installed as: for the tags listed in
+// MovingTags the tag of the release it currently points to, see ResolveMovingTag, and version
+// itself otherwise.
+func (f *Downloader) ResolveVersion(version string) (string, error) {
+	if !slices.Contains(f.MovingTags, version) {
+		return version, nil
+	}
+	resolved, err := f.ResolveMovingTag(version)
+	if err == nil {
+		f.logger().Info("resolved moving tag", "tag", version, "version", resolved)
+	}
+	return resolved, err
+}
+
+// ResolveMovingTag returns the tag of the most recently published non-draft release, other than
+// the release of tag itself, whose tag points to the same commit as tag, e.g. the dated release a
+// "nightly" tag was last moved to. The tags of the releases are resolved newest first until one
+// matches.
+func (f *Downloader) ResolveMovingTag(tag string) (string, error) {
+	target, err := f.ResolveTag(tag)
+	if err != nil {
+		return "", err
+	}
+
+	releases, err := f.ListReleases()
+	if err != nil {
+		return "", err
+	}
+	sort.SliceStable(releases, func(i, j int) bool {
+		return releases[i].PublishedAt.After(releases[j].PublishedAt)
+	})
+
+	for _, release := range releases {
+		if release.Draft || release.TagName == tag {
+			continue
+		}
+		ref, err := f.ResolveTag(release.TagName)
+		if isNotFound(err) {
+			// The tag of the release was deleted
+			continue
+		}
+		if err != nil {
+			return "", err
+		}
+		if ref.SHA == target.SHA {
+			return release.TagName, nil
+		}
+	}
+	return "", fmt.Errorf("no release other than %s points to commit %s", tag, target.SHA)
+}
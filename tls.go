@@ -0,0 +1,77 @@
+package libfetch
+
+import (
+	"bytes"
+	"crypto/tls"
+	"crypto/x509"
+	"encoding/pem"
+	"errors"
+	"fmt"
+	"strings"
+)
+
+// ParseCertificates parses the certificates in data, either PEM encoded "CERTIFICATE" blocks or
+// a single DER encoded certificate, e.g. to add a private CA to Downloader.RootCertificates.
+func ParseCertificates(data []byte) ([]*x509.Certificate, error) {
+	if !bytes.Contains(data, []byte("-----BEGIN")) {
+		cert, err := x509.ParseCertificate(data)
+		if err != nil {
+			return nil, fmt.Errorf("invalid DER certificate: %w", err)
+		}
+		return []*x509.Certificate{cert}, nil
+	}
+
+	var certs []*x509.Certificate
+	for {
+		var block *pem.Block
+		block, data = pem.Decode(data)
+		if block == nil {
+			break
+		}
+		if block.Type != "CERTIFICATE" {
+			continue
+		}
+		cert, err := x509.ParseCertificate(block.Bytes)
+		if err != nil {
+			return nil, fmt.Errorf("invalid PEM certificate: %w", err)
+		}
+		certs = append(certs, cert)
+	}
+	if len(certs) == 0 {
+		return nil, errors.New("no certificate found in PEM data")
+	}
+	return certs, nil
+}
+
+// rootCertificatesKey returns the DER encodings of certs concatenated, identifying the
+// certificates in a transportConfig.
+func rootCertificatesKey(certs []*x509.Certificate) string {
+	var sb strings.Builder
+	for _, cert := range certs {
+		sb.Write(cert.Raw)
+	}
+	return sb.String()
+}
+
+// tlsConfig returns the TLS configuration of the transport built for config, or nil when it uses
+// the defaults.
+func tlsConfig(config transportConfig) *tls.Config {
+	if config.rootCertificates == "" && !config.insecureSkipVerify {
+		return nil
+	}
+
+	tlsConfig := &tls.Config{InsecureSkipVerify: config.insecureSkipVerify}
+	if config.rootCertificates != "" {
+		pool, err := x509.SystemCertPool()
+		if err != nil {
+			pool = x509.NewCertPool()
+		}
+		// The key holds certificates that were parsed before, so they parse again
+		certs, _ := x509.ParseCertificates([]byte(config.rootCertificates))
+		for _, cert := range certs {
+			pool.AddCert(cert)
+		}
+		tlsConfig.RootCAs = pool
+	}
+	return tlsConfig
+}
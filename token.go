@@ -0,0 +1,58 @@
+package libfetch
+
+import (
+	"errors"
+	"fmt"
+	"io"
+	"net/http"
+)
+
+// tokenScopeError returns err, the failure of a request made with Token, as an
+// ErrInsufficientTokenScope error if it is a 404 although the token can see the repository.
+// GitHub reports the releases and assets of a private repository as missing to fine-grained
+// tokens without the contents permission, so the repository and its release list are probed to
+// tell such a token from an asset that does not exist.
+func (f *Downloader) tokenScopeError(err error) error {
+	if f.Token == "" || !isNotFound(err) {
+		return err
+	}
+	if status, _ := f.probe(f.repoAPIURL()); status != http.StatusOK {
+		return err
+	}
+	status, permissions := f.probe(f.releasesURL() + "?per_page=1")
+	if status != http.StatusNotFound {
+		return err
+	}
+
+	if permissions == "" {
+		permissions = "contents=read"
+	}
+	return fmt.Errorf("%w: the repository is visible to the token but its releases are not, grant the token the %s permission (%v)", ErrInsufficientTokenScope, permissions, err)
+}
+
+// isNotFound reports whether err is a 404 response of the GitHub API or of an asset download.
+func isNotFound(err error) bool {
+	var aerr *GitHubAPIError
+	if errors.As(err, &aerr) {
+		return aerr.StatusCode == http.StatusNotFound
+	}
+	var serr *DownloadStatusError
+	return errors.As(err, &serr) && serr.StatusCode == http.StatusNotFound
+}
+
+// probe requests apiURL and returns the response status together with the permissions GitHub
+// reports a fine-grained token would need for it, if any. Failed requests report status 0.
+func (f *Downloader) probe(apiURL string) (int, string) {
+	req, err := f.newAPIRequest(apiURL)
+	if err != nil {
+		return 0, ""
+	}
+	resp, err := f.createHTTPClient().Do(req)
+	if err != nil {
+		return 0, ""
+	}
+	defer resp.Body.Close()
+	io.Copy(io.Discard, resp.Body)
+
+	return resp.StatusCode, resp.Header.Get("X-Accepted-GitHub-Permissions")
+}
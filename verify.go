@@ -0,0 +1,82 @@
+package libfetch
+
+import (
+	"errors"
+	"fmt"
+	"os"
+	"path/filepath"
+	"sort"
+)
+
+// fileChecksums returns the SHA-256 checksums of the regular files among files, which are
+// relative to dir and use forward slashes. Symlinks and other special files are not hashed.
+func fileChecksums(dir string, files []string) (map[string]string, error) {
+	if len(files) == 0 {
+		return nil, nil
+	}
+
+	checksums := make(map[string]string, len(files))
+	for _, file := range files {
+		name := filepath.Join(dir, filepath.FromSlash(file))
+		info, err := os.Lstat(name)
+		if err != nil {
+			return nil, fmt.Errorf("error hashing installed file: %w", err)
+		}
+		if !info.Mode().IsRegular() {
+			continue
+		}
+
+		checksum, err := fileSHA256(name)
+		if err != nil {
+			return nil, fmt.Errorf("error hashing installed file: %w", err)
+		}
+		checksums[file] = checksum
+	}
+	return checksums, nil
+}
+
+// Verify re-hashes the installed files and returns the ones that are missing or no longer match
+// the checksums recorded when they were installed, sorted by name. An intact installation
+// returns no files. Verify fails for installations that predate checksum tracking; installing
+// with Force repairs a corrupted installation.
+func (i *Install) Verify() ([]string, error) {
+	if i.VersionedLayout {
+		install, _, err := i.latestVersionedInstall()
+		if err != nil {
+			return nil, err
+		}
+		if install == nil {
+			return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+		}
+		return install.Verify()
+	}
+
+	if !i.alreadyInstalled() {
+		return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+	}
+
+	versionInfo, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if versionInfo.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", versionInfo.Repo)
+	}
+	if len(versionInfo.Checksums) == 0 {
+		return nil, errors.New("installation has no recorded checksums")
+	}
+
+	var mismatched []string
+	for file, checksum := range versionInfo.Checksums {
+		target, err := safeJoin(i.InstallPath, filepath.FromSlash(file))
+		if err != nil {
+			return nil, err
+		}
+		if err := verifySHA256(target, checksum); err != nil {
+			mismatched = append(mismatched, file)
+		}
+	}
+	sort.Strings(mismatched)
+
+	return mismatched, nil
+}
@@ -0,0 +1,180 @@
+package libfetch
+
+import (
+	"encoding/json"
+	"errors"
+	"fmt"
+	"net/url"
+	"os"
+	"path/filepath"
+	"slices"
+)
+
+const (
+	// versionsDir is the directory below the install path previous versions are retained in.
+	versionsDir = ".versions"
+	// versionsIndex is the file in versionsDir listing the retained versions.
+	versionsIndex = "index.json"
+)
+
+// retainedVersions is the index of the previous versions retained in versionsDir.
+type retainedVersions struct {
+	// Versions lists the retained versions, most recently replaced first.
+	Versions []VersionInfo `json:"versions"`
+}
+
+// retainedVersionDir returns the directory the files of version tag are retained in.
+func (i *Install) retainedVersionDir(tag string) string {
+	name := url.PathEscape(tag)
+	if name == "" || name == "." || name == ".." || name == versionsIndex {
+		name = "_" + name
+	}
+	return filepath.Join(i.InstallPath, versionsDir, name)
+}
+
+// readRetainedVersions reads the index of retained versions, which is empty if none were retained.
+func (i *Install) readRetainedVersions() (*retainedVersions, error) {
+	d, err := os.ReadFile(filepath.Join(i.InstallPath, versionsDir, versionsIndex))
+	if os.IsNotExist(err) {
+		return &retainedVersions{}, nil
+	}
+	if err != nil {
+		return nil, fmt.Errorf("error reading retained versions: %w", err)
+	}
+
+	var index retainedVersions
+	if err := json.Unmarshal(d, &index); err != nil {
+		return nil, fmt.Errorf("error unmarshalling retained versions: %w", err)
+	}
+	return &index, nil
+}
+
+// writeRetainedVersions writes the index of retained versions, removing the versions directory
+// once no version is retained.
+func (i *Install) writeRetainedVersions(index *retainedVersions) error {
+	dir := filepath.Join(i.InstallPath, versionsDir)
+	if len(index.Versions) == 0 {
+		return os.RemoveAll(dir)
+	}
+
+	d, err := json.Marshal(index)
+	if err != nil {
+		return fmt.Errorf("error marshalling retained versions: %w", err)
+	}
+	if err := os.MkdirAll(dir, 0755); err != nil {
+		return err
+	}
+	return writeFileAtomic(filepath.Join(dir, versionsIndex), d)
+}
+
+// retireInstallation removes the installation described by versionInfo to make room for a new
+// version. When KeepVersions is set its files are retained for Rollback instead.
+func (i *Install) retireInstallation(versionInfo *VersionInfo) error {
+	i.Downloader.logger().Debug("removing previous version", "version", versionInfo.TagName, "path", i.InstallPath, "keep_versions", i.KeepVersions)
+	// Installations that predate file tracking cannot be told apart from other files
+	if i.KeepVersions > 0 && versionInfo.Files != nil {
+		return i.retainInstallation(versionInfo)
+	}
+	return i.removeInstalledFiles(versionInfo)
+}
+
+// retainInstallation moves the files of the installation described by versionInfo into the
+// versions directory and records it in the index, pruning it to the KeepVersions most recent
+// versions. A version retained before is replaced.
+func (i *Install) retainInstallation(versionInfo *VersionInfo) error {
+	index, err := i.readRetainedVersions()
+	if err != nil {
+		return err
+	}
+
+	dir := i.retainedVersionDir(versionInfo.TagName)
+	if err := os.RemoveAll(dir); err != nil {
+		return err
+	}
+
+	// Files removed since the installation are not retained
+	retained := *versionInfo
+	retained.Files = i.installedFiles(versionInfo)
+	if err := moveFiles(i.InstallPath, dir, retained.Files); err != nil {
+		return err
+	}
+	for _, file := range retained.Files {
+		removeEmptyDirs(i.InstallPath, filepath.Dir(filepath.Join(i.InstallPath, filepath.FromSlash(file))))
+	}
+	if err := os.Remove(filepath.Join(i.InstallPath, i.versionFile)); err != nil && !os.IsNotExist(err) {
+		return err
+	}
+
+	index.Versions = slices.DeleteFunc(index.Versions, func(v VersionInfo) bool {
+		return v.TagName == retained.TagName
+	})
+	index.Versions = slices.Insert(index.Versions, 0, retained)
+	for len(index.Versions) > i.KeepVersions {
+		pruned := index.Versions[len(index.Versions)-1]
+		if err := os.RemoveAll(i.retainedVersionDir(pruned.TagName)); err != nil {
+			return err
+		}
+		index.Versions = index.Versions[:len(index.Versions)-1]
+	}
+
+	return i.writeRetainedVersions(index)
+}
+
+// RetainedVersions returns the previous versions retained for Rollback, most recently replaced first.
+func (i *Install) RetainedVersions() ([]VersionInfo, error) {
+	index, err := i.readRetainedVersions()
+	if err != nil {
+		return nil, err
+	}
+	return index.Versions, nil
+}
+
+// Rollback replaces the installation with the most recently retained previous version, see
+// KeepVersions, and returns it. The current installation is removed.
+func (i *Install) Rollback() (*VersionInfo, error) {
+	if !i.alreadyInstalled() {
+		return nil, fmt.Errorf("%w: %s", ErrNotInstalled, i.InstallPath)
+	}
+
+	installed, err := i.GetInstalledVersion()
+	if err != nil {
+		return nil, err
+	}
+	if installed.Repo != i.repo {
+		return nil, fmt.Errorf("installed version is for a different repository: %s", installed.Repo)
+	}
+	if installed.Files == nil {
+		return nil, errors.New("the installation does not record its files and cannot be rolled back")
+	}
+
+	index, err := i.readRetainedVersions()
+	if err != nil {
+		return nil, err
+	}
+	if len(index.Versions) == 0 {
+		return nil, fmt.Errorf("no previous version retained in %s", i.InstallPath)
+	}
+	previous := index.Versions[0]
+
+	if err := i.removeInstalledFiles(installed); err != nil {
+		return nil, fmt.Errorf("error removing current version: %w", err)
+	}
+
+	dir := i.retainedVersionDir(previous.TagName)
+	if err := moveFiles(dir, i.InstallPath, previous.Files); err != nil {
+		return nil, fmt.Errorf("error restoring previous version: %w", err)
+	}
+	if err := i.writeVersionInfo(&previous); err != nil {
+		return nil, err
+	}
+
+	if err := os.RemoveAll(dir); err != nil {
+		return nil, err
+	}
+	index.Versions = index.Versions[1:]
+	if err := i.writeRetainedVersions(index); err != nil {
+		return nil, err
+	}
+
+	return &previous, nil
+}